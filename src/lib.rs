@@ -23,6 +23,9 @@
 //!
 //! With the serde feature enabled, you can also use the [`crate::serde`] module to convert rust types to
 //! and from [`Value`]s, or serialize/deserialize them to/from other formats like JSON.
+//!
+//! Use the [`crate::metadata`] module to work with things like events and extrinsics from a node, using the
+//! node's metadata to know what shape they are.
 
 #![deny(missing_docs)]
 // BitVec only supports u64 BitStore if `target_pointer_width = "64"`.
@@ -33,13 +36,22 @@
     feature(32bit_target)
 )]
 
+#[cfg(feature = "from_string")]
+mod macros;
+mod metadata_impls;
 mod scale_impls;
 #[cfg(feature = "serde")]
 mod serde_impls;
 mod string_impls;
 mod value;
+mod value_index;
 
 pub use value::{BitSequence, Composite, Primitive, Value, ValueDef, Variant};
+pub use value_index::ValueIndex;
+
+#[cfg(feature = "from_string")]
+#[doc(hidden)]
+pub use macros::{__check_literal, __value_from_literal};
 
 /// Serializing and deserializing a [`crate::Value`] into/from other types via serde.
 #[cfg(feature = "serde")]
@@ -183,9 +195,20 @@ pub mod serde {
 /// assert_eq!(value, new_value.remove_context());
 /// ```
 pub mod scale {
-	pub use crate::scale_impls::{BitSequenceError, DecodeError, EncodeError, TypeId};
+	pub use crate::scale_impls::{
+		BitSequenceError, DecodeError, DecodeErrorKind, EncodeError, EncodeErrorKind, Location,
+		LocationSegment, TypeId, ValidationError, ValidationErrorKind,
+	};
 	pub use scale_info::PortableRegistry;
 
+	/// The [`Visitor`][visitor::Visitor] trait and the types handed to it, for use with
+	/// [`decode_with_visitor`].
+	pub mod visitor {
+		pub use crate::scale_impls::{
+			BitSequence, Compact, CompactLocation, Composite, Sequence, Variant, Visitor,
+		};
+	}
+
 	/// Attempt to decode some SCALE encoded bytes into a value, by providing a pointer
 	/// to the bytes (which will be moved forwards as bytes are used in the decoding),
 	/// a type ID, and a type registry from which we'll look up the relevant type information.
@@ -197,6 +220,203 @@ pub mod scale {
 		crate::scale_impls::decode_value_as_type(data, ty_id, types)
 	}
 
+	/// Attempt to decode some SCALE encoded bytes according to the type ID provided,
+	/// handing each of the values found to a [`visitor::Visitor`] rather than building
+	/// a [`crate::Value`]. This can be used to decode into other structures, or to avoid
+	/// allocating when only part of the data is needed. The pointer to the bytes is moved
+	/// forwards past the decoded value, regardless of how much of it the visitor looks at.
+	///
+	/// # Example
+	///
+	/// Sum up the numbers in some SCALE encoded `Vec<u32>` without allocating a [`crate::Value`].
+	///
+	/// ```rust
+	/// # fn make_type<T: scale_info::TypeInfo + 'static>() -> (u32, scale_info::PortableRegistry) {
+	/// #     let m = scale_info::MetaType::new::<T>();
+	/// #     let mut types = scale_info::Registry::new();
+	/// #     let id = types.register_type(&m);
+	/// #     let portable_registry: scale_info::PortableRegistry = types.into();
+	/// #     (id.id(), portable_registry)
+	/// # }
+	/// use codec::Encode;
+	/// use scale_value::scale::{ DecodeError, TypeId, visitor::{ Sequence, Visitor } };
+	///
+	/// struct SumVisitor;
+	///
+	/// impl Visitor for SumVisitor {
+	///     type Value = u128;
+	///     type Error = DecodeError;
+	///
+	///     fn visit_u128(self, value: u128, _type_id: TypeId) -> Result<u128, DecodeError> {
+	///         Ok(value)
+	///     }
+	///     fn visit_sequence(self, value: &mut Sequence, _type_id: TypeId) -> Result<u128, DecodeError> {
+	///         let mut sum = 0;
+	///         while let Some(n) = value.decode_item(SumVisitor) {
+	///             sum += n?;
+	///         }
+	///         Ok(sum)
+	///     }
+	/// }
+	///
+	/// let (type_id, registry) = make_type::<Vec<u32>>();
+	/// let bytes = vec![1u32, 2, 3].encode();
+	///
+	/// let sum = scale_value::scale::decode_with_visitor(&mut &*bytes, type_id, &registry, SumVisitor);
+	/// assert_eq!(sum, Ok(6));
+	/// ```
+	pub fn decode_with_visitor<V: visitor::Visitor, Id: Into<TypeId>>(
+		data: &mut &[u8],
+		ty_id: Id,
+		types: &PortableRegistry,
+		visitor: V,
+	) -> Result<V::Value, V::Error> {
+		crate::scale_impls::decode_with_visitor(data, ty_id, types, visitor)
+	}
+
+	/// Move the pointer to some SCALE encoded bytes forwards past a value of the type
+	/// given, without decoding it. This is useful for finding where some encoded value
+	/// ends (for instance to get to the next one), and avoids any allocations that
+	/// decoding into a [`crate::Value`] would incur.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// # fn make_type<T: scale_info::TypeInfo + 'static>() -> (u32, scale_info::PortableRegistry) {
+	/// #     let m = scale_info::MetaType::new::<T>();
+	/// #     let mut types = scale_info::Registry::new();
+	/// #     let id = types.register_type(&m);
+	/// #     let portable_registry: scale_info::PortableRegistry = types.into();
+	/// #     (id.id(), portable_registry)
+	/// # }
+	/// use codec::Encode;
+	///
+	/// let (type_id, registry) = make_type::<Vec<String>>();
+	/// let mut bytes = vec!["hello".to_string(), "world".to_string()].encode();
+	/// bytes.push(123);
+	///
+	/// let data = &mut &*bytes;
+	/// scale_value::scale::skip_type(data, type_id, &registry).unwrap();
+	/// assert_eq!(*data, &[123]);
+	/// ```
+	pub fn skip_type<Id: Into<TypeId>>(
+		data: &mut &[u8],
+		ty_id: Id,
+		types: &PortableRegistry,
+	) -> Result<(), DecodeError> {
+		crate::scale_impls::skip_type(data, ty_id, types)
+	}
+
+	/// Check whether some [`crate::Value<T>`] is compatible with the type ID given, such that
+	/// [`encode_as_type`] would succeed, without encoding it. Rather than stopping at the
+	/// first problem, every problem that's found is returned, each with the [`Location`]
+	/// in the value at which it was found.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// # fn make_type<T: scale_info::TypeInfo + 'static>() -> (u32, scale_info::PortableRegistry) {
+	/// #     let m = scale_info::MetaType::new::<T>();
+	/// #     let mut types = scale_info::Registry::new();
+	/// #     let id = types.register_type(&m);
+	/// #     let portable_registry: scale_info::PortableRegistry = types.into();
+	/// #     (id.id(), portable_registry)
+	/// # }
+	/// use scale_value::Value;
+	///
+	/// #[derive(scale_info::TypeInfo)]
+	/// struct Foo {
+	///     name: String,
+	///     values: Vec<u8>,
+	/// }
+	///
+	/// let (type_id, registry) = make_type::<Foo>();
+	///
+	/// let value = Value::named_composite(vec![
+	///     ("name".into(), Value::bool(true)),
+	///     ("values".into(), Value::unnamed_composite(vec![Value::uint(1u8), Value::uint(300u16)])),
+	/// ]);
+	///
+	/// let errors = scale_value::scale::validate_as_type(&value, type_id, &registry).unwrap_err();
+	/// assert_eq!(errors.len(), 2);
+	/// assert_eq!(errors[0].location.to_string(), "name");
+	/// assert_eq!(errors[1].location.to_string(), "values[1]");
+	/// ```
+	pub fn validate_as_type<T, Id: Into<TypeId>>(
+		value: &crate::Value<T>,
+		ty_id: Id,
+		types: &PortableRegistry,
+	) -> Result<(), Vec<ValidationError>> {
+		crate::scale_impls::validate_value_as_type(value, ty_id, types)
+	}
+
+	/// Build an example [`crate::Value`] for the type with the given ID, which can be used as a
+	/// template for users to fill in. Numbers are zero, strings, sequences and bit sequences are
+	/// empty, arrays are filled with example values, and enums take the first variant (skipping any
+	/// which would lead to a never-ending value for recursive types). The result can always be
+	/// encoded back into the type with [`encode_as_type`].
+	///
+	/// If the type cannot be found, or no finite value exists for it, an empty composite is returned.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// # fn make_type<T: scale_info::TypeInfo + 'static>() -> (u32, scale_info::PortableRegistry) {
+	/// #     let m = scale_info::MetaType::new::<T>();
+	/// #     let mut types = scale_info::Registry::new();
+	/// #     let id = types.register_type(&m);
+	/// #     let portable_registry: scale_info::PortableRegistry = types.into();
+	/// #     (id.id(), portable_registry)
+	/// # }
+	/// use scale_value::Value;
+	///
+	/// #[derive(scale_info::TypeInfo)]
+	/// struct Transfer {
+	///     dest: [u8; 2],
+	///     amount: u128,
+	///     memo: Option<String>,
+	/// }
+	///
+	/// let (type_id, registry) = make_type::<Transfer>();
+	/// let value = scale_value::scale::example_value(type_id, &registry);
+	///
+	/// assert_eq!(value.remove_context(), Value::named_composite(vec![
+	///     ("dest".into(), Value::unnamed_composite(vec![Value::uint(0u8), Value::uint(0u8)])),
+	///     ("amount".into(), Value::uint(0u8)),
+	///     ("memo".into(), Value::unnamed_variant("None", vec![])),
+	/// ]));
+	/// ```
+	pub fn example_value<Id: Into<TypeId>>(
+		ty_id: Id,
+		types: &PortableRegistry,
+	) -> crate::Value<TypeId> {
+		crate::scale_impls::example_value(ty_id, types)
+	}
+
+	/// Return a human readable name for the type with the given ID, like `Vec<(u8, bool)>`
+	/// or `Option<sp_runtime::multiaddress::MultiAddress<sp_core::crypto::AccountId32, ()>>`.
+	/// Types are named by their path where they have one; this is how the types in encode,
+	/// decode and validation errors are named.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// # fn make_type<T: scale_info::TypeInfo + 'static>() -> (u32, scale_info::PortableRegistry) {
+	/// #     let m = scale_info::MetaType::new::<T>();
+	/// #     let mut types = scale_info::Registry::new();
+	/// #     let id = types.register_type(&m);
+	/// #     let portable_registry: scale_info::PortableRegistry = types.into();
+	/// #     (id.id(), portable_registry)
+	/// # }
+	/// let (type_id, registry) = make_type::<Option<Vec<(u8, bool)>>>();
+	///
+	/// let name = scale_value::scale::type_name(type_id, &registry);
+	/// assert_eq!(name, "Option<Vec<(u8, bool)>>");
+	/// ```
+	pub fn type_name<Id: Into<TypeId>>(ty_id: Id, types: &PortableRegistry) -> String {
+		crate::scale_impls::type_name(ty_id, types)
+	}
+
 	/// Attempt to encode some [`crate::Value<T>`] into SCALE bytes, by providing a pointer to the
 	/// type ID that we'd like to encode it as, a type registry from which we'll look
 	/// up the relevant type information, and a buffer to encode the bytes to.
@@ -210,28 +430,404 @@ pub mod scale {
 	}
 }
 
+/// Decoding and encoding SCALE bytes from a node, like events, using the type information
+/// in its [`RuntimeMetadataV14`][metadata::RuntimeMetadataV14].
+pub mod metadata {
+	pub use crate::metadata_impls::{
+		Call, Constant, EventRecord, Extrinsic, ExtrinsicSignature, MetadataEncodeError,
+		MetadataError, ModuleError,
+	};
+	use crate::scale::TypeId;
+	use crate::{Composite, Value};
+	pub use frame_metadata::v14::RuntimeMetadataV14;
+
+	/// Decode the SCALE encoded bytes stored in `System::Events` into a list of the events
+	/// in them. Each event comes with the phase of the block that it was emitted in, the names
+	/// of the pallet and the event, the event's fields and the event's topics.
+	///
+	/// An error is returned if the bytes can't be decoded, or if they aren't all used up.
+	pub fn decode_events(
+		metadata: &RuntimeMetadataV14,
+		bytes: &[u8],
+	) -> Result<Vec<EventRecord>, MetadataError> {
+		crate::metadata_impls::decode_events(metadata, bytes)
+	}
+
+	/// Encode a call to `call_name` in the pallet `pallet_name` into SCALE bytes. The pallet
+	/// and call are looked up by name in the metadata, and the arguments can be given by name
+	/// in any order, or without names in the order that the call expects them. Each argument
+	/// is encoded into the type that the call expects for it.
+	///
+	/// An error is returned if the pallet or call can't be found, if arguments are missing or
+	/// unexpected, or if an argument can't be encoded into the type expected for it.
+	pub fn encode_call<T>(
+		metadata: &RuntimeMetadataV14,
+		pallet_name: &str,
+		call_name: &str,
+		args: Composite<T>,
+	) -> Result<Vec<u8>, MetadataEncodeError<T>> {
+		crate::metadata_impls::encode_call(metadata, pallet_name, call_name, args)
+	}
+
+	/// Decode the SCALE encoded bytes of a call into the name of the pallet that it's in, the
+	/// name of the call and its named arguments.
+	///
+	/// An error is returned if the bytes can't be decoded, or if they aren't all used up.
+	pub fn decode_call(metadata: &RuntimeMetadataV14, bytes: &[u8]) -> Result<Call, MetadataError> {
+		crate::metadata_impls::decode_call(metadata, bytes)
+	}
+
+	/// Build the full storage key for the entry `entry_name` in the pallet `pallet_name`, given
+	/// a key for each of the entry's hashers (so none for plain entries). The key starts with
+	/// the `twox_128` hashes of the pallet's storage prefix and the entry name, followed by
+	/// each key hashed with the hasher that the metadata gives for it.
+	pub fn storage_key<T>(
+		metadata: &RuntimeMetadataV14,
+		pallet_name: &str,
+		entry_name: &str,
+		keys: Vec<Value<T>>,
+	) -> Result<Vec<u8>, MetadataEncodeError<T>> {
+		crate::metadata_impls::storage_key(metadata, pallet_name, entry_name, keys)
+	}
+
+	/// Recover the keys from a full storage key for the entry `entry_name` in the pallet
+	/// `pallet_name`. Keys hashed with `Blake2_128Concat`, `Twox64Concat` or `Identity`
+	/// are appended to their hash and so can be decoded; the others are given back as `None`.
+	///
+	/// An error is returned if the key isn't a key for the entry given, or if it can't be decoded.
+	pub fn decode_storage_key(
+		metadata: &RuntimeMetadataV14,
+		pallet_name: &str,
+		entry_name: &str,
+		key: &[u8],
+	) -> Result<Vec<Option<Value<TypeId>>>, MetadataError> {
+		crate::metadata_impls::decode_storage_key(metadata, pallet_name, entry_name, key)
+	}
+
+	/// Decode the bytes stored at some key of the entry `entry_name` in the pallet `pallet_name`.
+	/// Pass `None` if nothing is stored at the key; the entry's default value is then decoded
+	/// instead, unless the entry is optional, in which case `None` is returned.
+	pub fn decode_storage_value(
+		metadata: &RuntimeMetadataV14,
+		pallet_name: &str,
+		entry_name: &str,
+		bytes: Option<&[u8]>,
+	) -> Result<Option<Value<TypeId>>, MetadataError> {
+		crate::metadata_impls::decode_storage_value(metadata, pallet_name, entry_name, bytes)
+	}
+
+	/// Decode every constant of every pallet in the metadata, along with its docs. Each item
+	/// is an error if the constant's bytes can't be decoded into its type.
+	pub fn constants(
+		metadata: &RuntimeMetadataV14,
+	) -> impl Iterator<Item = Result<Constant, MetadataError>> + '_ {
+		crate::metadata_impls::constants(metadata)
+	}
+
+	/// Decode a `DispatchError::Module` into the name of the pallet that the error is from,
+	/// the name of the error, its fields and its docs. `pallet_index` is the index of the
+	/// pallet, and `error_bytes` are the bytes of the error, which begin with the index of
+	/// the error in the pallet and may be padded with zeros.
+	///
+	/// An error is returned if the pallet or error can't be found, or if the bytes can't be
+	/// decoded into the error's fields.
+	pub fn decode_module_error(
+		metadata: &RuntimeMetadataV14,
+		pallet_index: u8,
+		error_bytes: &[u8],
+	) -> Result<ModuleError, MetadataError> {
+		crate::metadata_impls::decode_module_error(metadata, pallet_index, error_bytes)
+	}
+
+	/// Decode an opaque extrinsic, which is prefixed with its compact encoded length. This
+	/// gives back the extrinsic version, the address, signature and signed extensions if it's
+	/// signed, and the call that it makes along with the call's named arguments.
+	///
+	/// An error is returned if the bytes can't be decoded, if the extrinsic version isn't the
+	/// one that the metadata describes, or if the bytes aren't all used up.
+	pub fn decode_extrinsic(
+		metadata: &RuntimeMetadataV14,
+		bytes: &[u8],
+	) -> Result<Extrinsic, MetadataError> {
+		crate::metadata_impls::decode_extrinsic(metadata, bytes)
+	}
+
+	/// Encode an unsigned extrinsic which calls `call_name` in the pallet `pallet_name`.
+	/// The arguments can be given by name in any order, or without names in the order that
+	/// the call expects them. The bytes are prefixed with their compact encoded length.
+	pub fn encode_unsigned_extrinsic<T>(
+		metadata: &RuntimeMetadataV14,
+		pallet_name: &str,
+		call_name: &str,
+		args: Composite<T>,
+	) -> Result<Vec<u8>, MetadataEncodeError<T>> {
+		crate::metadata_impls::encode_unsigned_extrinsic(metadata, pallet_name, call_name, args)
+	}
+}
+
 /// Converting a [`crate::Value`] to or from strings.
 pub mod stringify {
+	pub use crate::string_impls::FormatOptions;
 	#[cfg(feature = "from_string")]
-	pub use crate::string_impls::ParseError;
+	pub use crate::string_impls::{LineColumn, ParseError, ParseOptions, ReadError};
 
 	/// Attempt to parse a string into a [`crate::Value<()>`], returning a tuple
 	/// consisting of a result (either the value or a [`ParseError`] containing
 	/// location and error information) and the remainder of the string that wasn't
-	/// parsed.
+	/// parsed. Whitespace and comments before the value are skipped, but anything after it,
+	/// including whitespace, is left in the remainder.
+	///
+	/// [`ParseError::render`] can be used to show where in the input string an error is,
+	/// along with hints about how to fix it.
+	///
+	/// ```rust
+	/// use scale_value::stringify::from_str;
+	///
+	/// let input = "{\n  a: 1\n  b: 2\n}";
+	/// let err = from_str(input).0.unwrap_err();
+	///
+	/// assert_eq!(err.start_line_column(input).to_string(), "3:3");
+	/// assert!(err.render(input).ends_with("= help: did you forget a comma?\n"));
+	/// ```
 	#[cfg(feature = "from_string")]
 	pub fn from_str(s: &str) -> (Result<crate::Value<()>, ParseError>, &str) {
 		crate::string_impls::from_str(s)
 	}
 
+	/// Parse a string into a [`crate::Value<()>`] like [`from_str`], configuring the parsing
+	/// with some [`ParseOptions`].
+	///
+	/// Numbers like `1.5e12` are always parsed into exact integers, and with
+	/// [`ParseOptions::unit`], numbers can also be given in units like `12.5 DOT`. Either way,
+	/// an error is returned if the number isn't a whole number once any decimals are applied.
+	///
+	/// ```rust
+	/// use scale_value::{ Value, stringify::{ from_str_with_options, ParseOptions } };
+	///
+	/// let options = ParseOptions::new().unit("DOT", 10).unit("mDOT", 7);
+	///
+	/// let (value, _) = from_str_with_options("(12.5 DOT, 3mDOT, 1.5e12)", &options);
+	/// assert_eq!(value.unwrap(), Value::unnamed_composite(vec![
+	///     Value::uint(125_000_000_000u128),
+	///     Value::uint(30_000_000u128),
+	///     Value::uint(1_500_000_000_000u128),
+	/// ]));
+	///
+	/// // Amounts smaller than the smallest unit can't be represented:
+	/// let (value, _) = from_str_with_options("0.00000000001 DOT", &options);
+	/// assert!(value.is_err());
+	/// ```
+	#[cfg(feature = "from_string")]
+	pub fn from_str_with_options<'a>(
+		s: &'a str,
+		options: &ParseOptions,
+	) -> (Result<crate::Value<()>, ParseError>, &'a str) {
+		crate::string_impls::from_str_with_options(s, options)
+	}
+
+	/// Parse every value in a string, where values are separated by whitespace (such as
+	/// newlines) or commas, and comments are ignored. Locations in any [`ParseError`] are
+	/// relative to the whole string. Nothing more is returned after an error, since we can't
+	/// tell where the broken value was meant to end.
+	///
+	/// ```rust
+	/// use scale_value::{ Value, stringify::values_from_str };
+	///
+	/// let values: Vec<_> = values_from_str("1, true\n(2, 3) // a comment\n'a' 'bc'").collect();
+	///
+	/// assert_eq!(values[0], Ok(Value::uint(1u8)));
+	/// assert_eq!(values[1], Ok(Value::bool(true)));
+	/// assert_eq!(values[2], Ok(Value::unnamed_composite(vec![Value::uint(2u8), Value::uint(3u8)])));
+	/// assert_eq!(values[3], Ok(Value::char('a')));
+	/// assert_eq!(values[4].as_ref().unwrap_err().start_loc, 34);
+	/// assert_eq!(values.len(), 5);
+	/// ```
+	#[cfg(feature = "from_string")]
+	pub fn values_from_str(
+		s: &str,
+	) -> impl Iterator<Item = Result<crate::Value<()>, ParseError>> + '_ {
+		crate::string_impls::values_from_str(s)
+	}
+
+	/// Like [`values_from_str`], but reading the values from some [`std::io::BufRead`], such as
+	/// a file or stdin. Input is read a line at a time, and a value is handed back once the
+	/// input that follows it shows that it's complete. Values can span several lines, and a
+	/// variant name on its own line is joined with any values on the following lines, so
+	/// `Foo\n(1)` is read as `Foo(1)`. Locations in any [`ParseError`] are relative to the
+	/// start of everything read.
+	///
+	/// ```rust
+	/// use scale_value::{ Value, stringify::values_from_reader };
+	///
+	/// let input = "Foo {\n  a: 1,\n  b: 2,\n}\nBar(true)\n";
+	/// let values = values_from_reader(input.as_bytes())
+	///     .collect::<Result<Vec<_>, _>>()
+	///     .unwrap();
+	///
+	/// assert_eq!(values, vec![
+	///     Value::named_variant("Foo", vec![
+	///         ("a".into(), Value::uint(1u8)),
+	///         ("b".into(), Value::uint(2u8)),
+	///     ]),
+	///     Value::unnamed_variant("Bar", vec![Value::bool(true)]),
+	/// ]);
+	/// ```
+	#[cfg(feature = "from_string")]
+	pub fn values_from_reader<R: std::io::BufRead>(
+		reader: R,
+	) -> impl Iterator<Item = Result<crate::Value<()>, ReadError>> {
+		crate::string_impls::values_from_reader(reader)
+	}
+
+	/// Parse a string into a [`crate::Value`], carrying on past any errors found in composites
+	/// and variants so that every error in the string can be reported at once. This returns
+	/// the value that was parsed along with every error that was found. The whole string is
+	/// expected to be parsed; anything left over is reported as an error too.
+	///
+	/// Values which could not be parsed are replaced with empty unnamed composites, whose
+	/// context is the index of the corresponding error. Every other value has a context of
+	/// `None`, and so if no errors were found, the value is the same as that returned from
+	/// [`from_str`].
+	///
+	/// ```rust
+	/// use scale_value::{ Value, ValueDef, stringify::from_str_recovering };
+	///
+	/// let (value, errors) = from_str_recovering("(1, 'ab', 3 4)");
+	///
+	/// // One error for the char, and one for the missing comma:
+	/// assert_eq!(errors.len(), 2);
+	/// assert_eq!(errors[0].start_loc, 6);
+	/// assert_eq!(errors[1].start_loc, 12);
+	///
+	/// // The char is replaced with an empty composite pointing at its error:
+	/// let ValueDef::Composite(composite) = &value.value else { panic!("expected a composite") };
+	/// let contexts: Vec<_> = composite.values().map(|v| v.context).collect();
+	/// assert_eq!(contexts, vec![None, Some(0), None, None]);
+	/// assert_eq!(
+	///     value.remove_context(),
+	///     Value::unnamed_composite(vec![
+	///         Value::uint(1u8),
+	///         Value::unnamed_composite(vec![]),
+	///         Value::uint(3u8),
+	///         Value::uint(4u8),
+	///     ])
+	/// );
+	/// ```
+	#[cfg(feature = "from_string")]
+	pub fn from_str_recovering(s: &str) -> (crate::Value<Option<usize>>, Vec<ParseError>) {
+		crate::string_impls::from_str_recovering(s)
+	}
+
+	/// Parse a string into a [`crate::Value<TypeId>`] which fits the type given, returning a
+	/// tuple of the result and the remainder of the string that wasn't parsed, like [`from_str`].
+	///
+	/// The type information is used to fill in what the string leaves out, so for example:
+	/// - Values for wrapper types, like `struct Foo(u32)` or `Compact<u32>`, can be given
+	///   as just the inner value, like `5`.
+	/// - Variants with no values, like `None`, don't need any brackets after them.
+	/// - Hex literals with an even number of digits like `0x001234`, which [`from_str`] parses
+	///   into numbers, keep their leading zeros when given for sequences of bytes. Strings
+	///   like `"hello"` can be given for sequences and arrays of bytes, too.
+	/// - Numbers, chars and strings are converted into the kind of primitive that the type
+	///   expects, where this can be done without losing anything.
+	///
+	/// Anything which doesn't fit the type is left as it was parsed, so that
+	/// [`crate::scale::encode_as_type`] or [`crate::scale::validate_as_type`] can point
+	/// to exactly where the problem is.
+	///
+	/// ```rust
+	/// use scale_value::{ Value, stringify::from_str_as_type };
+	///
+	/// #[derive(scale_info::TypeInfo)]
+	/// struct AccountId([u8; 4]);
+	///
+	/// #[derive(scale_info::TypeInfo)]
+	/// struct Transfer {
+	///     dest: Option<AccountId>,
+	///     #[codec(compact)]
+	///     value: u128,
+	/// }
+	/// # fn make_type<T: scale_info::TypeInfo + 'static>() -> (u32, scale_info::PortableRegistry) {
+	/// #     let m = scale_info::MetaType::new::<T>();
+	/// #     let mut types = scale_info::Registry::new();
+	/// #     let id = types.register_type(&m);
+	/// #     (id.id, types.into())
+	/// # }
+	/// let (type_id, registry) = make_type::<Transfer>();
+	///
+	/// let (value, _) = from_str_as_type("{ dest: Some(0x01020304), value: 100 }", type_id, &registry);
+	/// let bytes = Value::unnamed_composite(vec![1u8, 2, 3, 4].into_iter().map(Value::uint).collect());
+	/// assert_eq!(
+	///     value.unwrap().remove_context(),
+	///     Value::named_composite(vec![
+	///         ("dest".into(), Value::unnamed_variant("Some", vec![Value::unnamed_composite(vec![bytes])])),
+	///         ("value".into(), Value::uint(100u8)),
+	///     ])
+	/// );
+	/// ```
+	#[cfg(feature = "from_string")]
+	pub fn from_str_as_type<'a, Id: Into<crate::scale::TypeId>>(
+		s: &'a str,
+		ty_id: Id,
+		types: &crate::scale::PortableRegistry,
+	) -> (Result<crate::Value<crate::scale::TypeId>, ParseError>, &'a str) {
+		crate::string_impls::from_str_as_type(s, ty_id, types)
+	}
+
 	/// Identical to calling `to_string()` on the [`crate::Value`], but here just
 	/// to make it a little more obvious that this is the inverse of [`from_str`].
 	///
-	/// # Panics
-	///
-	/// Panics if a `Primitive::U256`/`Primitive::I256` are a part of the value,
-	/// since we cannot properly format and parse those at the moment.
+	/// `Primitive::U256`/`Primitive::I256` values are formatted as decimal numbers with
+	/// a `u256`/`i256` suffix (for example `123u256`), so that they parse back into the
+	/// same type.
 	pub fn to_string<T>(v: &crate::Value<T>) -> String {
 		v.to_string()
 	}
+
+	/// Format a [`crate::Value`] into a string, configuring the output with some
+	/// [`FormatOptions`]. As with [`to_string`], the output can be parsed back into
+	/// the same value with [`from_str`], or with [`from_str_with_options`] given the same
+	/// units if [`FormatOptions::unit_for_field`] is used. Bytes formatted in hex with
+	/// [`FormatOptions::hex_bytes`] parse back into numbers, but these encode into the same
+	/// bytes (see [`FormatOptions::hex_bytes`] for the details).
+	///
+	/// ```rust
+	/// use scale_value::{ Value, stringify::{ to_string_with_options, FormatOptions } };
+	///
+	/// let bytes = Value::unnamed_composite(vec![Value::uint(0xdeu8), Value::uint(0xadu8)]);
+	/// let options = FormatOptions::new().hex_bytes(true);
+	/// assert_eq!(to_string_with_options(&bytes, &options), "0xdead");
+	///
+	/// let transfer = Value::named_composite(vec![
+	///     ("nonce".into(), Value::uint(3u8)),
+	///     ("value".into(), Value::uint(125_000_000_000u128)),
+	/// ]);
+	/// let options = FormatOptions::new().unit_for_field("value", "DOT", 10);
+	/// assert_eq!(to_string_with_options(&transfer, &options), "{ nonce: 3, value: 12.5 DOT }");
+	/// ```
+	pub fn to_string_with_options<T>(v: &crate::Value<T>, options: &FormatOptions) -> String {
+		crate::string_impls::to_string_with_options(v, options)
+	}
+
+	/// Format a [`crate::Value`] over multiple lines, indenting nested values by the number
+	/// of spaces given and wrapping long sequences. This is equivalent to formatting the
+	/// value with `{:#}`, which indents by 4 spaces. The output can still be parsed back into
+	/// the same value with [`from_str`].
+	///
+	/// ```rust
+	/// use scale_value::{ Value, stringify::to_string_pretty };
+	///
+	/// let value = Value::named_variant("Transfer", vec![
+	///     ("dest".into(), Value::unnamed_variant("Id", vec![Value::uint(1u8)])),
+	///     ("value".into(), Value::uint(1000u16)),
+	/// ]);
+	/// assert_eq!(
+	///     to_string_pretty(&value, 2),
+	///     "Transfer {\n  dest: Id(1),\n  value: 1000\n}"
+	/// );
+	/// assert_eq!(to_string_pretty(&value, 4), format!("{value:#}"));
+	/// ```
+	pub fn to_string_pretty<T>(v: &crate::Value<T>, indent: usize) -> String {
+		to_string_with_options(v, &FormatOptions::new().indent(indent))
+	}
 }
@@ -0,0 +1,388 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Value;
+
+/// Build a [`Value<()>`] using the same syntax that [`crate::stringify::from_str`] parses.
+/// The composites and variants that make up the value are checked at compile time, but
+/// literals are parsed when the code runs. Rust variables and expressions can be spliced
+/// in with `#var` or `#(expr)`, and are converted into values with [`Into`].
+///
+/// ```rust
+/// use scale_value::{ value, Value };
+///
+/// let dest = value!(Id(0x0102));
+/// let amount = 1_000u64;
+///
+/// let transfer = value!(Transfer { dest: #dest, value: #amount, memo: None });
+/// assert_eq!(
+///     transfer,
+///     Value::named_variant("Transfer", vec![
+///         ("dest".into(), Value::unnamed_variant("Id", vec![Value::uint(0x0102u16)])),
+///         ("value".into(), Value::uint(1000u16)),
+///         ("memo".into(), Value::unnamed_variant("None", vec![])),
+///     ])
+/// );
+///
+/// assert_eq!(
+///     value!((true, -1, 'a', "hello", #(amount * 2), <0101>)),
+///     "(true, -1, 'a', \"hello\", 2000, <0101>)".parse().unwrap()
+/// );
+/// ```
+///
+/// Literals that Rust accepts but the string syntax doesn't have, like `1u8`, `1.5f32` or
+/// `b'a'`, are rejected at compile time:
+///
+/// ```compile_fail
+/// let n = scale_value::value!(1u8);
+/// ```
+///
+/// Other literals are parsed just like they would be in a string when the code runs, and so
+/// this panics if one isn't a valid value, like `1.5`, which isn't a whole number.
+#[macro_export]
+macro_rules! value {
+	// Split the values in an unnamed composite at the commas between them. Lists whose values
+	// are all one or two token trees, like `1`, `(2, 3)`, `-4` or `Foo(5)`, are split in one
+	// go, and other values are split off one at a time, so that long lists don't hit the
+	// recursion limit.
+	(@unnamed [$($out:expr,)*] []) => {
+		vec![$($out),*]
+	};
+	(@unnamed [$($out:expr,)*] [] $($a:tt),+ $(,)?) => {
+		vec![$($out,)* $($crate::value!($a)),+]
+	};
+	(@unnamed [$($out:expr,)*] [] $($a:tt $b:tt),+ $(,)?) => {
+		vec![$($out,)* $($crate::value!($a $b)),+]
+	};
+	(@unnamed [$($out:expr,)*] [] $a:tt , $($rest:tt)*) => {
+		$crate::value!(@unnamed [$($out,)* $crate::value!($a),] [] $($rest)*)
+	};
+	(@unnamed [$($out:expr,)*] [] $a:tt $b:tt , $($rest:tt)*) => {
+		$crate::value!(@unnamed [$($out,)* $crate::value!($a $b),] [] $($rest)*)
+	};
+	(@unnamed [$($out:expr,)*] [] $a:tt $b:tt $c:tt , $($rest:tt)*) => {
+		$crate::value!(@unnamed [$($out,)* $crate::value!($a $b $c),] [] $($rest)*)
+	};
+	(@unnamed [$($out:expr,)*] [$($cur:tt)+] , $($rest:tt)*) => {
+		$crate::value!(@unnamed [$($out,)* $crate::value!($($cur)+),] [] $($rest)*)
+	};
+	(@unnamed [$($out:expr,)*] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+		$crate::value!(@unnamed [$($out,)*] [$($cur)* $next] $($rest)*)
+	};
+	(@unnamed [$($out:expr,)*] [$($cur:tt)+]) => {
+		vec![$($out,)* $crate::value!($($cur)+)]
+	};
+
+	// Split the fields in a named composite at the commas between them, in the same way.
+	(@named [$($out:expr,)*] []) => {
+		vec![$($out),*]
+	};
+	(@named [$($out:expr,)*] [] $($name:tt : $a:tt),+ $(,)?) => {
+		vec![$($out,)* $($crate::value!(@field $name : $a)),+]
+	};
+	(@named [$($out:expr,)*] [] $($name:tt : $a:tt $b:tt),+ $(,)?) => {
+		vec![$($out,)* $($crate::value!(@field $name : $a $b)),+]
+	};
+	(@named [$($out:expr,)*] [] $name:tt : $a:tt , $($rest:tt)*) => {
+		$crate::value!(@named [$($out,)* $crate::value!(@field $name : $a),] [] $($rest)*)
+	};
+	(@named [$($out:expr,)*] [] $name:tt : $a:tt $b:tt , $($rest:tt)*) => {
+		$crate::value!(@named [$($out,)* $crate::value!(@field $name : $a $b),] [] $($rest)*)
+	};
+	(@named [$($out:expr,)*] [] $name:tt : $a:tt $b:tt $c:tt , $($rest:tt)*) => {
+		$crate::value!(@named [$($out,)* $crate::value!(@field $name : $a $b $c),] [] $($rest)*)
+	};
+	(@named [$($out:expr,)*] [$($cur:tt)+] , $($rest:tt)*) => {
+		$crate::value!(@named [$($out,)* $crate::value!(@field $($cur)+),] [] $($rest)*)
+	};
+	(@named [$($out:expr,)*] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+		$crate::value!(@named [$($out,)*] [$($cur)* $next] $($rest)*)
+	};
+	(@named [$($out:expr,)*] [$($cur:tt)+]) => {
+		vec![$($out,)* $crate::value!(@field $($cur)+)]
+	};
+	(@field $name:ident : $($value:tt)+) => {
+		(::std::string::String::from(stringify!($name)), $crate::value!($($value)+))
+	};
+	(@field $name:literal : $($value:tt)+) => {
+		(::std::string::String::from($name), $crate::value!($($value)+))
+	};
+
+	(true) => {
+		$crate::Value::bool(true)
+	};
+	(false) => {
+		$crate::Value::bool(false)
+	};
+	(# $var:ident) => {
+		$crate::Value::from($var)
+	};
+	(# ($expr:expr)) => {
+		$crate::Value::from($expr)
+	};
+	(< $($bits:literal)? >) => {
+		$crate::__value_from_literal(concat!("<", $(stringify!($bits),)? ">"))
+	};
+	({ $($fields:tt)* }) => {
+		$crate::Value::named_composite($crate::value!(@named [] [] $($fields)*))
+	};
+	(( $($values:tt)* )) => {
+		$crate::Value::unnamed_composite($crate::value!(@unnamed [] [] $($values)*))
+	};
+	($name:ident { $($fields:tt)* }) => {
+		$crate::Value::named_variant(stringify!($name), $crate::value!(@named [] [] $($fields)*))
+	};
+	($name:ident ( $($values:tt)* )) => {
+		$crate::Value::unnamed_variant(stringify!($name), $crate::value!(@unnamed [] [] $($values)*))
+	};
+	($name:ident) => {
+		$crate::Value::unnamed_variant(stringify!($name), vec![])
+	};
+	(- $lit:literal) => {{
+		const _: () = $crate::__check_literal(stringify!($lit));
+		$crate::__value_from_literal(concat!("-", stringify!($lit)))
+	}};
+	($lit:literal) => {{
+		const _: () = $crate::__check_literal(stringify!($lit));
+		$crate::__value_from_literal(stringify!($lit))
+	}};
+}
+
+/// Check that a literal given to [`value!`] has no suffix or prefix that the string syntax
+/// doesn't know about, like `1u8` or `b'a'`. This runs at compile time, and isn't a part of
+/// the public API.
+#[doc(hidden)]
+pub const fn __check_literal(s: &str) {
+	let s = s.as_bytes();
+	// Chars, strings, raw strings and byte strings have no suffixes worth checking for:
+	if has_at(s, 0, b"'") || has_at(s, 0, b"\"") || has_at(s, 0, b"r") || has_at(s, 0, b"b\"") {
+		return;
+	}
+	if !s[0].is_ascii_digit() {
+		panic!("value!: byte, C string and raw byte string literals aren't supported");
+	}
+
+	// Skip over the digits of a number to find any suffix after them:
+	let i = if has_at(s, 0, b"0x") {
+		skip(s, 2, b"0123456789abcdefABCDEF_")
+	} else if has_at(s, 0, b"0o") || has_at(s, 0, b"0b") {
+		skip(s, 2, b"0123456789_")
+	} else {
+		let i = skip(s, 0, b"0123456789_.");
+		if i < s.len() && (s[i] == b'e' || s[i] == b'E') {
+			skip(s, skip(s, i + 1, b"+-"), b"0123456789_")
+		} else {
+			i
+		}
+	};
+	let is_256_suffix = s.len() - i == 4 && (has_at(s, i, b"u256") || has_at(s, i, b"i256"));
+	if i != s.len() && !is_256_suffix {
+		panic!("value!: number suffixes like `u8` aren't supported; splice in a Rust value with #(expr) instead");
+	}
+
+	// Is `s[at..]` prefixed by the bytes given?
+	const fn has_at(s: &[u8], at: usize, bytes: &[u8]) -> bool {
+		let mut i = 0;
+		while i < bytes.len() {
+			if at + i >= s.len() || s[at + i] != bytes[i] {
+				return false;
+			}
+			i += 1;
+		}
+		true
+	}
+	// The index of the first byte from `at` onwards that isn't one of the bytes given.
+	const fn skip(s: &[u8], mut at: usize, bytes: &[u8]) -> usize {
+		'outer: while at < s.len() {
+			let mut i = 0;
+			while i < bytes.len() {
+				if s[at] == bytes[i] {
+					at += 1;
+					continue 'outer;
+				}
+				i += 1;
+			}
+			break;
+		}
+		at
+	}
+}
+
+/// Parse a literal given to [`value!`]. This isn't a part of the public API.
+#[doc(hidden)]
+pub fn __value_from_literal(s: &str) -> Value<()> {
+	match s.parse() {
+		Ok(value) => value,
+		Err(e) => panic!("value!: cannot parse the literal `{s}`: {e}"),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{BitSequence, Value};
+
+	fn parse(s: &str) -> Value<()> {
+		s.parse().expect("valid value")
+	}
+
+	#[test]
+	fn literals() {
+		assert_eq!(value!(true), Value::bool(true));
+		assert_eq!(value!(false), Value::bool(false));
+		assert_eq!(value!(1_234), Value::uint(1234u16));
+		assert_eq!(value!(-1_234), Value::int(-1234));
+		assert_eq!(value!(1.5e12), Value::uint(1_500_000_000_000u64));
+		assert_eq!(value!(0b1010), parse("0b1010"));
+		assert_eq!(value!(0xdead), parse("0xdead"));
+		assert_eq!(value!('a'), Value::char('a'));
+		assert_eq!(value!("hi\n\u{1F600}"), Value::string("hi\n😀"));
+		assert_eq!(value!(r#"a "quote""#), Value::string("a \"quote\""));
+		assert_eq!(value!(b"hi"), parse("(104, 105)"));
+		assert_eq!(value!(<>), Value::bit_sequence(BitSequence::new()));
+		assert_eq!(value!(<0110>), parse("<0110>"));
+	}
+
+	#[test]
+	fn hex_literals_encode_to_the_bytes_written() {
+		use codec::Encode;
+
+		#[derive(Encode, scale_info::TypeInfo)]
+		enum MultiAddress {
+			Id([u8; 32]),
+		}
+		let m = scale_info::MetaType::new::<MultiAddress>();
+		let mut types = scale_info::Registry::new();
+		let id = types.register_type(&m);
+		let types: scale_info::PortableRegistry = types.into();
+
+		let value = value!(Id(0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20));
+		let mut bytes = Vec::new();
+		crate::scale::encode_as_type(value, id.id, &types, &mut bytes).expect("can encode");
+
+		let account: [u8; 32] = core::array::from_fn(|i| i as u8 + 1);
+		assert_eq!(bytes, MultiAddress::Id(account).encode());
+	}
+
+	#[test]
+	fn composites_and_variants() {
+		assert_eq!(value!(()), Value::unnamed_composite(vec![]));
+		assert_eq!(value!({}), Value::named_composite(vec![]));
+		assert_eq!(value!(None), Value::unnamed_variant("None", vec![]));
+		assert_eq!(value!(Foo()), Value::unnamed_variant("Foo", vec![]));
+		assert_eq!(value!(Foo {}), Value::named_variant("Foo", vec![]));
+
+		assert_eq!(
+			value!(Foo {
+				a: (1, -2, (), Bar),
+				"b c": { d: Some(<1>), e: r"\" },
+				f: Baz(true, { g: 'g' },),
+			}),
+			parse(
+				r#"Foo {
+					a: (1, -2, (), Bar()),
+					"b c": { d: Some(<1>), e: "\\" },
+					f: Baz(true, { g: 'g' },),
+				}"#
+			)
+		);
+	}
+
+	#[test]
+	fn splicing() {
+		let n = 5u8;
+		let name = "Alice";
+		let inner = value!(Inner(1, 2));
+		let bits: BitSequence = [true, false].into_iter().collect();
+
+		assert_eq!(
+			value!({ n: #n, name: #name, inner: #inner, sum: #(n as u64 + 10), bits: #bits }),
+			parse(r#"{ n: 5, name: "Alice", inner: Inner(1, 2), sum: 15, bits: <10> }"#)
+		);
+		assert_eq!(
+			value!(Some(#(-(n as i32)))),
+			Value::unnamed_variant("Some", vec![Value::int(-5)])
+		);
+		assert_eq!(value!((#n, #n,)), parse("(5, 5)"));
+	}
+
+	#[test]
+	fn long_lists() {
+		// Repeat the values or fields given ten times in one call to `value!`:
+		macro_rules! tenfold {
+			(($($t:tt)*)) => {
+				value!(($($t)* $($t)* $($t)* $($t)* $($t)* $($t)* $($t)* $($t)* $($t)* $($t)*))
+			};
+			({$($t:tt)*}) => {
+				value!({$($t)* $($t)* $($t)* $($t)* $($t)* $($t)* $($t)* $($t)* $($t)* $($t)*})
+			};
+		}
+		let n = 1u8;
+
+		assert_eq!(
+			tenfold!((
+				0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
+				23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43,
+				44, 45, 46, 47, 48, 49,
+			)),
+			parse(&format!(
+				"({})",
+				(0..50).map(|n| format!("{n}, ")).collect::<String>().repeat(10)
+			))
+		);
+		assert_eq!(
+			tenfold!((
+				Foo(1), -1, #n, Foo(1), -1, #n, Foo(1), -1, #n, Foo(1), -1, #n, Foo(1), -1, #n,
+				Foo(1), -1, #n, Foo(1), -1, #n,
+			)),
+			parse(&format!("({})", "Foo(1), -1, 1, ".repeat(70)))
+		);
+		assert_eq!(
+			tenfold!((1, Foo(2), <01>, { a: 3 }, #(n + 1), Bar { b: 4 }, -5, <>, "six", None,)),
+			parse(&format!(
+				"({})",
+				"1, Foo(2), <01>, { a: 3 }, 2, Bar { b: 4 }, -5, <>, \"six\", None(), ".repeat(10)
+			))
+		);
+		assert_eq!(
+			tenfold!({ a: 1, b: Foo(2), c: <01>, d: #(n + 1), e: -5, f: Bar { g: 'g' }, }),
+			parse(&format!(
+				"{{ {} }}",
+				"a: 1, b: Foo(2), c: <01>, d: 2, e: -5, f: Bar { g: 'g' }, ".repeat(10)
+			))
+		);
+	}
+
+	#[test]
+	fn rust_only_literals_are_rejected() {
+		use super::__check_literal;
+		let rejects = |s| std::panic::catch_unwind(|| __check_literal(s)).is_err();
+
+		for s in ["1u8", "1_000i64", "0xffu32", "0b1usize", "1.5f32", "1e3f64", "b'a'", "c\"a\""] {
+			assert!(rejects(s), "{s} should be rejected");
+		}
+		for s in ["1", "1_000", "0xff", "0xf32", "0b1", "1.5", "1e3", "1E-3", "1u256", "1i256"] {
+			assert!(!rejects(s), "{s} should be accepted");
+		}
+		for s in ["'a'", "\"a\"", "r#\"a\"#", "b\"a\""] {
+			assert!(!rejects(s), "{s} should be accepted");
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "cannot parse the literal `1.5`")]
+	fn invalid_literals_panic() {
+		value!(1.5);
+	}
+}
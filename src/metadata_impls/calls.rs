@@ -0,0 +1,328 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+	decode_fields, pallet_by_index, pallet_by_name, variants, MetadataEncodeError, MetadataError,
+};
+use crate::scale_impls::{encode_value_as_type, LocationSegment, TypeId};
+use crate::value::Composite;
+use codec::Decode;
+use frame_metadata::v14::{PalletMetadata, RuntimeMetadataV14};
+use scale_info::{form::PortableForm, Field};
+
+/// A call to some pallet, decoded from SCALE bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+	/// The name of the pallet that the call is in.
+	pub pallet_name: String,
+	/// The name of the call.
+	pub call_name: String,
+	/// The arguments given to the call. These are named, since call arguments always have names.
+	pub args: Composite<TypeId>,
+}
+
+/// Decode the SCALE encoded bytes of a call, using the type information in the metadata
+/// provided. An error is returned if the bytes aren't all used up.
+pub fn decode_call(metadata: &RuntimeMetadataV14, bytes: &[u8]) -> Result<Call, MetadataError> {
+	let data = &mut &*bytes;
+	let call = decode_call_from(data, metadata)?;
+	if !data.is_empty() {
+		return Err(MetadataError::TrailingBytes(data.len()));
+	}
+	Ok(call)
+}
+
+/// Encode a call to `call_name` in the pallet `pallet_name` into SCALE bytes, using the
+/// type information in the metadata provided.
+pub fn encode_call<T>(
+	metadata: &RuntimeMetadataV14,
+	pallet_name: &str,
+	call_name: &str,
+	args: Composite<T>,
+) -> Result<Vec<u8>, MetadataEncodeError<T>> {
+	let mut out = Vec::new();
+	encode_call_to(metadata, pallet_name, call_name, args, &mut out)?;
+	Ok(out)
+}
+
+/// Decode a call, which begins with the index of the pallet it's in, followed by the
+/// index of the call in that pallet and then the call's arguments.
+pub(super) fn decode_call_from(
+	data: &mut &[u8],
+	metadata: &RuntimeMetadataV14,
+) -> Result<Call, MetadataError> {
+	let pallet = pallet_by_index(metadata, u8::decode(data)?)?;
+	let call_ty = call_type(pallet)?;
+
+	let index = u8::decode(data)?;
+	let variant = variants(call_ty, &metadata.types)?
+		.iter()
+		.find(|v| v.index == index)
+		.ok_or_else(|| MetadataError::VariantIndexNotFound {
+			pallet: pallet.name.clone(),
+			kind: "a call",
+			index,
+		})?;
+
+	Ok(Call {
+		pallet_name: pallet.name.clone(),
+		call_name: variant.name.clone(),
+		args: decode_fields(data, &variant.fields, &metadata.types)?,
+	})
+}
+
+/// Encode a call to the pallet given, with the arguments given, to the output. The arguments
+/// can be named, or given in the order that the call expects them.
+pub(super) fn encode_call_to<T>(
+	metadata: &RuntimeMetadataV14,
+	pallet_name: &str,
+	call_name: &str,
+	args: Composite<T>,
+	out: &mut Vec<u8>,
+) -> Result<(), MetadataEncodeError<T>> {
+	let pallet = pallet_by_name(metadata, pallet_name)?;
+	let variant = variants(call_type(pallet)?, &metadata.types)?
+		.iter()
+		.find(|v| v.name == call_name)
+		.ok_or_else(|| MetadataError::VariantNotFound {
+			pallet: pallet.name.clone(),
+			kind: "a call",
+			name: call_name.to_owned(),
+		})?;
+
+	out.push(pallet.index);
+	out.push(variant.index);
+	encode_args(call_name, args, &variant.fields, metadata, out)
+}
+
+fn encode_args<T>(
+	call_name: &str,
+	args: Composite<T>,
+	fields: &[Field<PortableForm>],
+	metadata: &RuntimeMetadataV14,
+	out: &mut Vec<u8>,
+) -> Result<(), MetadataEncodeError<T>> {
+	let types = &metadata.types;
+	match args {
+		Composite::Named(mut args) => {
+			if fields.iter().any(|field| field.name.is_none()) {
+				return Err(MetadataEncodeError::ArgumentsAreUnnamed {
+					call: call_name.to_owned(),
+				});
+			}
+			for field in fields {
+				let name = field.name.as_deref().expect("fields are named");
+				let idx = args.iter().position(|(n, _)| n == name).ok_or_else(|| {
+					MetadataEncodeError::ArgumentMissing {
+						call: call_name.to_owned(),
+						argument: name.to_owned(),
+					}
+				})?;
+				let (name, value) = args.remove(idx);
+				encode_value_as_type(value, field.ty, types, out).map_err(|mut e| {
+					e.location.prepend(LocationSegment::Field(name));
+					e
+				})?;
+			}
+			if let Some((name, _)) = args.into_iter().next() {
+				return Err(MetadataEncodeError::UnexpectedArgument {
+					call: call_name.to_owned(),
+					argument: name,
+				});
+			}
+		}
+		Composite::Unnamed(args) => {
+			if args.len() != fields.len() {
+				return Err(MetadataEncodeError::WrongNumberOfArguments {
+					call: call_name.to_owned(),
+					expected: fields.len(),
+					actual: args.len(),
+				});
+			}
+			for (idx, (value, field)) in args.into_iter().zip(fields).enumerate() {
+				encode_value_as_type(value, field.ty, types, out).map_err(|mut e| {
+					let segment = match &field.name {
+						Some(name) => Some(LocationSegment::Field(name.clone())),
+						None => LocationSegment::unnamed_field(idx, fields.len()),
+					};
+					e.location.prepend(segment);
+					e
+				})?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// The type of the calls in the pallet given.
+fn call_type(pallet: &PalletMetadata<PortableForm>) -> Result<u32, MetadataError> {
+	pallet
+		.calls
+		.as_ref()
+		.map(|calls| calls.ty.id)
+		.ok_or_else(|| MetadataError::PalletHasNo { pallet: pallet.name.clone(), kind: "calls" })
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::metadata_impls::test_runtime::{metadata, BalancesCall, MultiAddress, RuntimeCall};
+	use crate::Value;
+	use codec::Encode;
+
+	#[test]
+	fn encodes_and_decodes_calls() {
+		let metadata = metadata();
+		let expected = RuntimeCall::Balances(BalancesCall::transfer {
+			dest: MultiAddress::Index(7),
+			value: 100,
+		})
+		.encode();
+
+		// Arguments can be named, in any order, or given in order without names:
+		let named = Composite::Named(vec![
+			("value".into(), Value::uint(100u8)),
+			("dest".into(), Value::unnamed_variant("Index", vec![Value::uint(7u8)])),
+		]);
+		let unnamed = Composite::Unnamed(vec![
+			Value::unnamed_variant("Index", vec![Value::uint(7u8)]),
+			Value::uint(100u8),
+		]);
+		for args in [named, unnamed] {
+			let bytes = encode_call(&metadata, "Balances", "transfer", args).expect("can encode");
+			assert_eq!(bytes, expected);
+		}
+
+		let call = decode_call(&metadata, &expected).expect("can decode");
+		assert_eq!(call.pallet_name, "Balances");
+		assert_eq!(call.call_name, "transfer");
+		assert_eq!(
+			Value::from(call.args.map_context(|_| ())),
+			Value::named_composite(vec![
+				("dest".into(), Value::unnamed_variant("Index", vec![Value::uint(7u8)])),
+				("value".into(), Value::uint(100u8)),
+			])
+		);
+
+		let trailing = [expected, vec![0, 0]].concat();
+		assert_eq!(decode_call(&metadata, &trailing), Err(MetadataError::TrailingBytes(2)));
+	}
+
+	#[cfg(feature = "from_string")]
+	#[test]
+	fn encodes_calls_parsed_from_strings() {
+		use crate::ValueDef;
+
+		let metadata = metadata();
+		let args: Value = "{ dest: Id(0x0101010101010101010101010101010101010101010101010101010101010101), keep_alive: false }"
+			.parse()
+			.unwrap();
+		let ValueDef::Composite(args) = args.value else { panic!("expected a composite") };
+
+		let bytes = encode_call(&metadata, "Balances", "transfer_all", args).expect("can encode");
+		let expected = RuntimeCall::Balances(BalancesCall::transfer_all {
+			dest: MultiAddress::Id([1; 32]),
+			keep_alive: false,
+		});
+		assert_eq!(bytes, expected.encode());
+	}
+
+	#[test]
+	fn reports_errors_encoding_calls() {
+		let metadata = metadata();
+		let encode =
+			|pallet, call, args| encode_call::<()>(&metadata, pallet, call, args).unwrap_err();
+
+		assert_eq!(
+			encode("Staking", "bond", Composite::Unnamed(vec![])),
+			MetadataError::PalletNotFound("Staking".into()).into()
+		);
+		assert_eq!(
+			encode("Balances", "burn", Composite::Unnamed(vec![])),
+			MetadataError::VariantNotFound {
+				pallet: "Balances".into(),
+				kind: "a call",
+				name: "burn".into()
+			}
+			.into()
+		);
+		assert_eq!(
+			encode(
+				"Balances",
+				"transfer",
+				Composite::Named(vec![("value".into(), Value::uint(1u8))])
+			),
+			MetadataEncodeError::ArgumentMissing {
+				call: "transfer".into(),
+				argument: "dest".into()
+			}
+		);
+		assert_eq!(
+			encode(
+				"Balances",
+				"transfer",
+				Composite::Named(vec![
+					("dest".into(), Value::unnamed_variant("Index", vec![Value::uint(1u8)])),
+					("value".into(), Value::uint(1u8)),
+					("memo".into(), Value::uint(1u8)),
+				])
+			),
+			MetadataEncodeError::UnexpectedArgument {
+				call: "transfer".into(),
+				argument: "memo".into()
+			}
+		);
+		assert_eq!(
+			encode("Balances", "transfer", Composite::Unnamed(vec![Value::uint(1u8)])),
+			MetadataEncodeError::WrongNumberOfArguments {
+				call: "transfer".into(),
+				expected: 2,
+				actual: 1
+			}
+		);
+
+		// Errors encoding the arguments point to the argument that's wrong:
+		let err = encode(
+			"Balances",
+			"transfer",
+			Composite::Named(vec![
+				("dest".into(), Value::unnamed_variant("Index", vec![Value::bool(true)])),
+				("value".into(), Value::uint(1u8)),
+			]),
+		);
+		let MetadataEncodeError::Encode(err) = err else { panic!("expected an encode error") };
+		assert_eq!(err.location.to_string(), "dest.Index");
+
+		// Calls whose arguments have no names can only be given them in order:
+		let mut metadata = metadata.clone();
+		for ty in &mut metadata.types.types {
+			let scale_info::TypeDef::Variant(inner) = &mut ty.ty.type_def else { continue };
+			for variant in inner.variants.iter_mut().filter(|v| v.name == "transfer_all") {
+				variant.fields.iter_mut().for_each(|field| field.name = None);
+			}
+		}
+		let dest = Value::unnamed_variant("Index", vec![Value::uint(1u8)]);
+		let args = Composite::Named(vec![
+			("dest".into(), dest.clone()),
+			("keep_alive".into(), Value::bool(true)),
+		]);
+		assert_eq!(
+			encode_call(&metadata, "Balances", "transfer_all", args),
+			Err(MetadataEncodeError::ArgumentsAreUnnamed { call: "transfer_all".into() })
+		);
+		let args = Composite::Unnamed(vec![dest, Value::bool(true)]);
+		assert!(encode_call(&metadata, "Balances", "transfer_all", args).is_ok());
+	}
+}
@@ -0,0 +1,91 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::MetadataError;
+use crate::scale_impls::{decode_value_as_type, TypeId};
+use crate::value::Value;
+use frame_metadata::v14::RuntimeMetadataV14;
+
+/// A constant from some pallet, decoded from the bytes in the metadata.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constant {
+	/// The name of the pallet that the constant belongs to.
+	pub pallet_name: String,
+	/// The name of the constant.
+	pub name: String,
+	/// The value of the constant.
+	pub value: Value<TypeId>,
+	/// The documentation for the constant, one line at a time.
+	pub docs: Vec<String>,
+}
+
+/// Decode every constant of every pallet in the metadata, in the order that the metadata
+/// lists them in.
+pub fn constants(
+	metadata: &RuntimeMetadataV14,
+) -> impl Iterator<Item = Result<Constant, MetadataError>> + '_ {
+	metadata.pallets.iter().flat_map(move |pallet| {
+		pallet.constants.iter().map(move |constant| {
+			let data = &mut &*constant.value;
+			let value = decode_value_as_type(data, constant.ty, &metadata.types)?;
+			if !data.is_empty() {
+				return Err(MetadataError::TrailingBytes(data.len()));
+			}
+			Ok(Constant {
+				pallet_name: pallet.name.clone(),
+				name: constant.name.clone(),
+				value,
+				docs: constant.docs.clone(),
+			})
+		})
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::metadata_impls::test_runtime::metadata;
+
+	#[test]
+	fn decodes_constants() {
+		let metadata = metadata();
+		let constants: Vec<_> = constants(&metadata)
+			.map(|c| {
+				let c = c.expect("can decode");
+				(c.pallet_name, c.name, c.value.map_context(|_| ()), c.docs)
+			})
+			.collect();
+
+		assert_eq!(
+			constants,
+			vec![
+				(
+					"System".to_owned(),
+					"BlockHashCount".to_owned(),
+					Value::uint(2400u16),
+					vec![" Maximum number of block number to block hash mappings to keep."
+						.to_owned()]
+				),
+				(
+					"Balances".to_owned(),
+					"ExistentialDeposit".to_owned(),
+					Value::uint(500u16),
+					vec![" The minimum amount required to keep an account open.".to_owned()]
+				),
+				("Balances".to_owned(), "MaxLocks".to_owned(), Value::uint(50u8), vec![]),
+			]
+		);
+	}
+}
@@ -0,0 +1,282 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{decode_variant_fields, pallet_by_index, storage_entry, variants, MetadataError};
+use crate::scale_impls::{decode_value_as_type, TypeId};
+use crate::value::{Value, ValueDef};
+use codec::{Compact, Decode};
+use frame_metadata::v14::{RuntimeMetadataV14, StorageEntryType};
+use scale_info::TypeDef;
+
+/// An event decoded from the bytes stored in `System::Events`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRecord {
+	/// The phase of the block that the event was emitted in.
+	pub phase: Value<TypeId>,
+	/// The name of the pallet that emitted the event.
+	pub pallet_name: String,
+	/// The name of the event.
+	pub event_name: String,
+	/// The fields of the event, as a composite value whose context is the pallet's event type.
+	pub fields: Value<TypeId>,
+	/// The topics of the event.
+	pub topics: Vec<Value<TypeId>>,
+}
+
+/// Decode the SCALE encoded bytes stored in `System::Events` into a list of events, using the
+/// type information in the metadata provided.
+pub fn decode_events(
+	metadata: &RuntimeMetadataV14,
+	bytes: &[u8],
+) -> Result<Vec<EventRecord>, MetadataError> {
+	let types = &metadata.types;
+	let record_ty = event_record_type(metadata)?;
+	let record_fields = match types.resolve(record_ty).map(|ty| &ty.type_def) {
+		Some(TypeDef::Composite(inner)) => &inner.fields,
+		_ => {
+			return Err(MetadataError::UnexpectedType {
+				type_id: record_ty,
+				expected: "an event record",
+			})
+		}
+	};
+
+	let data = &mut &*bytes;
+	let len = Compact::<u32>::decode(data)?.0;
+	let mut events = Vec::new();
+	for _ in 0..len {
+		let mut phase = None;
+		let mut event = None;
+		let mut topics = None;
+
+		// Decode the fields of each record in whatever order they're given in.
+		for field in record_fields {
+			match field.name.as_deref() {
+				Some("phase") => phase = Some(decode_value_as_type(data, field.ty, types)?),
+				Some("event") => event = Some(decode_event(data, metadata)?),
+				Some("topics") => {
+					topics = match decode_value_as_type(data, field.ty, types)?.value {
+						ValueDef::Composite(topics) => Some(topics.into_values().collect()),
+						_ => {
+							return Err(MetadataError::UnexpectedType {
+								type_id: field.ty.id,
+								expected: "a sequence of topics",
+							})
+						}
+					}
+				}
+				_ => {}
+			}
+		}
+
+		match (phase, event, topics) {
+			(Some(phase), Some((pallet_name, event_name, fields)), Some(topics)) => {
+				events.push(EventRecord { phase, pallet_name, event_name, fields, topics })
+			}
+			_ => {
+				return Err(MetadataError::UnexpectedType {
+					type_id: record_ty,
+					expected: "an event record with a phase, event and topics",
+				})
+			}
+		}
+	}
+
+	if !data.is_empty() {
+		return Err(MetadataError::TrailingBytes(data.len()));
+	}
+	Ok(events)
+}
+
+/// Find the type of each event record in `System::Events`.
+fn event_record_type(metadata: &RuntimeMetadataV14) -> Result<u32, MetadataError> {
+	let (_, entry) = storage_entry(metadata, "System", "Events")?;
+	let events_ty = match &entry.ty {
+		StorageEntryType::Plain(ty) => ty.id,
+		StorageEntryType::Map { value, .. } => value.id,
+	};
+	match metadata.types.resolve(events_ty).map(|ty| &ty.type_def) {
+		Some(TypeDef::Sequence(inner)) => Ok(inner.type_param.id),
+		_ => Err(MetadataError::UnexpectedType {
+			type_id: events_ty,
+			expected: "a sequence of event records",
+		}),
+	}
+}
+
+/// Decode an event, which begins with the index of the pallet it's from, followed by the
+/// index of the event in that pallet and then the event's fields.
+fn decode_event(
+	data: &mut &[u8],
+	metadata: &RuntimeMetadataV14,
+) -> Result<(String, String, Value<TypeId>), MetadataError> {
+	let pallet = pallet_by_index(metadata, u8::decode(data)?)?;
+	let event_ty = pallet
+		.event
+		.as_ref()
+		.ok_or_else(|| MetadataError::PalletHasNo { pallet: pallet.name.clone(), kind: "events" })?
+		.ty
+		.id;
+
+	let index = u8::decode(data)?;
+	let variant = variants(event_ty, &metadata.types)?
+		.iter()
+		.find(|v| v.index == index)
+		.ok_or_else(|| MetadataError::VariantIndexNotFound {
+			pallet: pallet.name.clone(),
+			kind: "an event",
+			index,
+		})?;
+
+	let fields = decode_variant_fields(data, variant, event_ty.into(), &metadata.types)?;
+	Ok((pallet.name.clone(), variant.name.clone(), fields))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::metadata_impls::test_runtime::{
+		metadata, BalancesEvent, EventRecord as Record, Phase, RuntimeEvent, SystemEvent,
+	};
+	use codec::Encode;
+	use scale_info::TypeDefPrimitive;
+
+	fn record(phase: Phase, event: RuntimeEvent, topics: Vec<[u8; 32]>) -> Record {
+		Record { phase, event, topics }
+	}
+
+	#[test]
+	fn decodes_events() {
+		let metadata = metadata();
+		let bytes = vec![
+			record(
+				Phase::ApplyExtrinsic(1),
+				RuntimeEvent::Balances(BalancesEvent::Transfer {
+					from: [1; 32],
+					to: [2; 32],
+					amount: 1000,
+				}),
+				vec![[3; 32]],
+			),
+			record(
+				Phase::Finalization,
+				RuntimeEvent::System(SystemEvent::ExtrinsicSuccess { weight: 10 }),
+				vec![],
+			),
+		]
+		.encode();
+
+		let events = decode_events(&metadata, &bytes).expect("can decode events");
+		assert_eq!(events.len(), 2);
+
+		let transfer = &events[0];
+		assert_eq!(
+			transfer.phase.clone().remove_context(),
+			Value::unnamed_variant("ApplyExtrinsic", vec![Value::uint(1u8)])
+		);
+		assert_eq!(transfer.pallet_name, "Balances");
+		assert_eq!(transfer.event_name, "Transfer");
+		let account = |b: u8| Value::unnamed_composite(vec![Value::uint(b); 32]);
+		assert_eq!(
+			transfer.fields.clone().remove_context(),
+			Value::named_composite(vec![
+				("from".into(), account(1)),
+				("to".into(), account(2)),
+				("amount".into(), Value::uint(1000u16)),
+			])
+		);
+		assert_eq!(
+			transfer.topics.iter().map(|t| t.clone().remove_context()).collect::<Vec<_>>(),
+			vec![account(3)]
+		);
+
+		let success = &events[1];
+		assert_eq!(
+			success.phase.clone().remove_context(),
+			Value::unnamed_variant("Finalization", vec![])
+		);
+		assert_eq!(
+			(success.pallet_name.as_str(), success.event_name.as_str()),
+			("System", "ExtrinsicSuccess")
+		);
+		assert_eq!(
+			success.fields.clone().remove_context(),
+			Value::named_composite(vec![("weight".into(), Value::uint(10u8))])
+		);
+		assert!(success.topics.is_empty());
+
+		assert_eq!(decode_events(&metadata, &[0]), Ok(vec![]));
+	}
+
+	#[test]
+	fn reports_errors_decoding_events() {
+		let metadata = metadata();
+		let phase = Phase::Initialization.encode();
+
+		// Unknown pallet:
+		let bytes = [&[4][..], &phase, &[9, 0]].concat();
+		assert_eq!(decode_events(&metadata, &bytes), Err(MetadataError::PalletIndexNotFound(9)));
+
+		// Unknown event:
+		let bytes = [&[4][..], &phase, &[5, 9]].concat();
+		assert_eq!(
+			decode_events(&metadata, &bytes),
+			Err(MetadataError::VariantIndexNotFound {
+				pallet: "Balances".into(),
+				kind: "an event",
+				index: 9
+			})
+		);
+
+		// Not enough bytes:
+		let bytes = vec![record(
+			Phase::Finalization,
+			RuntimeEvent::System(SystemEvent::ExtrinsicSuccess { weight: 10 }),
+			vec![],
+		)]
+		.encode();
+		assert!(matches!(
+			decode_events(&metadata, &bytes[..bytes.len() - 1]),
+			Err(MetadataError::Decode(_))
+		));
+
+		// Too many bytes:
+		let bytes = [&bytes[..], &[1, 2]].concat();
+		assert_eq!(decode_events(&metadata, &bytes), Err(MetadataError::TrailingBytes(2)));
+
+		// Topics which aren't a sequence:
+		let mut metadata = metadata;
+		let u8_ty = metadata
+			.types
+			.types
+			.iter()
+			.find(|ty| matches!(ty.ty.type_def, TypeDef::Primitive(TypeDefPrimitive::U8)))
+			.expect("a u8 type")
+			.id;
+		let record_ty = event_record_type(&metadata).unwrap() as usize;
+		let TypeDef::Composite(record) = &mut metadata.types.types[record_ty].ty.type_def else {
+			panic!("records are composites")
+		};
+		let topics = record.fields.iter_mut().find(|f| f.name.as_deref() == Some("topics"));
+		topics.expect("a topics field").ty = u8_ty.into();
+
+		let event = RuntimeEvent::System(SystemEvent::ExtrinsicSuccess { weight: 10 });
+		let bytes = [&[4][..], &phase, &event.encode(), &[7]].concat();
+		assert_eq!(
+			decode_events(&metadata, &bytes),
+			Err(MetadataError::UnexpectedType { type_id: u8_ty, expected: "a sequence of topics" })
+		);
+	}
+}
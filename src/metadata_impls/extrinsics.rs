@@ -0,0 +1,254 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::calls::{decode_call_from, encode_call_to, Call};
+use super::{MetadataEncodeError, MetadataError};
+use crate::scale_impls::{decode_value_as_type, TypeId};
+use crate::value::{Composite, Value};
+use codec::{Compact, Decode, Encode};
+use frame_metadata::v14::RuntimeMetadataV14;
+
+/// The bit in the version byte of an extrinsic that's set when it's signed.
+const SIGNED_BIT: u8 = 0b1000_0000;
+
+/// An extrinsic decoded from SCALE bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extrinsic {
+	/// The version of the extrinsic, not including the bit that denotes whether it's signed.
+	pub version: u8,
+	/// The signature of the extrinsic, if it's signed.
+	pub signature: Option<ExtrinsicSignature>,
+	/// The call that the extrinsic makes.
+	pub call: Call,
+}
+
+/// The signature of a signed extrinsic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtrinsicSignature {
+	/// The address of the account that signed the extrinsic.
+	pub address: Value<TypeId>,
+	/// The signature itself.
+	pub signature: Value<TypeId>,
+	/// The signed extensions, alongside their identifiers, in the order that the
+	/// metadata lists them in.
+	pub extensions: Vec<(String, Value<TypeId>)>,
+}
+
+/// Decode an opaque extrinsic, which is prefixed with its compact encoded length, using the
+/// type information in the metadata provided.
+pub fn decode_extrinsic(
+	metadata: &RuntimeMetadataV14,
+	bytes: &[u8],
+) -> Result<Extrinsic, MetadataError> {
+	let data = &mut &*bytes;
+	let len = Compact::<u32>::decode(data)?.0 as usize;
+	if len > data.len() {
+		return Err(codec::Error::from("Not enough bytes for the extrinsic length given").into());
+	}
+	if len < data.len() {
+		return Err(MetadataError::TrailingBytes(data.len() - len));
+	}
+
+	let version_byte = u8::decode(data)?;
+	let version = version_byte & !SIGNED_BIT;
+	if version != metadata.extrinsic.version {
+		return Err(MetadataError::UnsupportedExtrinsicVersion {
+			found: version,
+			expected: metadata.extrinsic.version,
+		});
+	}
+
+	let signature =
+		if version_byte & SIGNED_BIT != 0 { Some(decode_signature(data, metadata)?) } else { None };
+	let call = decode_call_from(data, metadata)?;
+
+	if !data.is_empty() {
+		return Err(MetadataError::TrailingBytes(data.len()));
+	}
+	Ok(Extrinsic { version, signature, call })
+}
+
+/// Encode an unsigned extrinsic which calls `call_name` in the pallet `pallet_name` with
+/// the arguments given. The bytes are prefixed with their compact encoded length.
+pub fn encode_unsigned_extrinsic<T>(
+	metadata: &RuntimeMetadataV14,
+	pallet_name: &str,
+	call_name: &str,
+	args: Composite<T>,
+) -> Result<Vec<u8>, MetadataEncodeError<T>> {
+	let mut extrinsic = vec![metadata.extrinsic.version];
+	encode_call_to(metadata, pallet_name, call_name, args, &mut extrinsic)?;
+
+	let mut out = Compact(extrinsic.len() as u32).encode();
+	out.extend(extrinsic);
+	Ok(out)
+}
+
+fn decode_signature(
+	data: &mut &[u8],
+	metadata: &RuntimeMetadataV14,
+) -> Result<ExtrinsicSignature, MetadataError> {
+	let types = &metadata.types;
+	let address = decode_value_as_type(data, extrinsic_type_param(metadata, "Address")?, types)?;
+	let signature =
+		decode_value_as_type(data, extrinsic_type_param(metadata, "Signature")?, types)?;
+	let extensions = metadata
+		.extrinsic
+		.signed_extensions
+		.iter()
+		.map(|ext| Ok((ext.identifier.clone(), decode_value_as_type(data, ext.ty, types)?)))
+		.collect::<Result<_, MetadataError>>()?;
+
+	Ok(ExtrinsicSignature { address, signature, extensions })
+}
+
+/// The metadata doesn't describe the address and signature types directly, but
+/// they're given as type parameters to the extrinsic type.
+fn extrinsic_type_param(
+	metadata: &RuntimeMetadataV14,
+	name: &'static str,
+) -> Result<u32, MetadataError> {
+	let extrinsic_ty = metadata.extrinsic.ty.id;
+	metadata
+		.types
+		.resolve(extrinsic_ty)
+		.and_then(|ty| ty.type_params.iter().find(|param| param.name == name))
+		.and_then(|param| param.ty)
+		.map(|ty| ty.id)
+		.ok_or(MetadataError::UnexpectedType {
+			type_id: extrinsic_ty,
+			expected: if name == "Address" {
+				"an extrinsic with an Address type parameter"
+			} else {
+				"an extrinsic with a Signature type parameter"
+			},
+		})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::metadata_impls::test_runtime::{
+		metadata, BalancesCall, ChargeTransactionPayment, CheckNonce, CheckSpecVersion,
+		MultiAddress, MultiSignature, RuntimeCall, SystemCall,
+	};
+	use codec::Encode;
+
+	fn with_length(bytes: Vec<u8>) -> Vec<u8> {
+		let mut out = Compact(bytes.len() as u32).encode();
+		out.extend(bytes);
+		out
+	}
+
+	fn without_context(value: Value<TypeId>) -> Value<()> {
+		value.map_context(|_| ())
+	}
+
+	#[test]
+	fn decodes_signed_extrinsics() {
+		let call = RuntimeCall::Balances(BalancesCall::transfer_all {
+			dest: MultiAddress::Index(1),
+			keep_alive: true,
+		});
+		let mut bytes = vec![SIGNED_BIT | 4];
+		MultiAddress::Id([1; 32]).encode_to(&mut bytes);
+		MultiSignature::Sr25519([2; 64]).encode_to(&mut bytes);
+		(CheckSpecVersion, CheckNonce(3), ChargeTransactionPayment(1_000)).encode_to(&mut bytes);
+		call.encode_to(&mut bytes);
+
+		let ext = decode_extrinsic(&metadata(), &with_length(bytes)).expect("can decode");
+		assert_eq!(ext.version, 4);
+		assert_eq!(ext.call.pallet_name, "Balances");
+		assert_eq!(ext.call.call_name, "transfer_all");
+		assert_eq!(
+			Value::from(ext.call.args.map_context(|_| ())),
+			Value::named_composite(vec![
+				("dest".into(), Value::unnamed_variant("Index", vec![Value::uint(1u8)])),
+				("keep_alive".into(), Value::bool(true)),
+			])
+		);
+
+		let sig = ext.signature.expect("extrinsic is signed");
+		let bytes = |n: u8, len| Value::unnamed_composite(vec![Value::uint(n); len]);
+		assert_eq!(without_context(sig.address), Value::unnamed_variant("Id", vec![bytes(1, 32)]));
+		assert_eq!(
+			without_context(sig.signature),
+			Value::unnamed_variant("Sr25519", vec![bytes(2, 64)])
+		);
+		let extensions: Vec<_> = sig
+			.extensions
+			.into_iter()
+			.map(|(name, value)| (name, without_context(value)))
+			.collect();
+		assert_eq!(
+			extensions,
+			vec![
+				("CheckSpecVersion".to_owned(), Value::unnamed_composite(vec![])),
+				("CheckNonce".to_owned(), Value::unnamed_composite(vec![Value::uint(3u8)])),
+				(
+					"ChargeTransactionPayment".to_owned(),
+					Value::unnamed_composite(vec![Value::uint(1000u16)])
+				),
+			]
+		);
+	}
+
+	#[test]
+	fn encodes_and_decodes_unsigned_extrinsics() {
+		let remark = || Value::unnamed_composite(vec![Value::uint(b'h'), Value::uint(b'i')]);
+		let metadata = metadata();
+		let args = Composite::Named(vec![("remark".into(), remark())]);
+		let bytes = encode_unsigned_extrinsic(&metadata, "System", "remark", args).unwrap();
+
+		let mut expected = vec![4];
+		RuntimeCall::System(SystemCall::remark { remark: b"hi".to_vec() }).encode_to(&mut expected);
+		assert_eq!(bytes, with_length(expected));
+
+		let ext = decode_extrinsic(&metadata, &bytes).expect("can decode");
+		assert_eq!(ext.version, 4);
+		assert_eq!(ext.signature, None);
+		assert_eq!((&*ext.call.pallet_name, &*ext.call.call_name), ("System", "remark"));
+		assert_eq!(
+			Value::from(ext.call.args.map_context(|_| ())),
+			Value::named_composite(vec![("remark".into(), remark())])
+		);
+	}
+
+	#[test]
+	fn reports_errors_decoding_extrinsics() {
+		let metadata = metadata();
+		let call = RuntimeCall::System(SystemCall::remark { remark: vec![] }).encode();
+
+		let v3 = with_length([vec![3], call.clone()].concat());
+		assert_eq!(
+			decode_extrinsic(&metadata, &v3),
+			Err(MetadataError::UnsupportedExtrinsicVersion { found: 3, expected: 4 })
+		);
+
+		let mut trailing = with_length([vec![4], call.clone()].concat());
+		trailing.push(0);
+		assert_eq!(decode_extrinsic(&metadata, &trailing), Err(MetadataError::TrailingBytes(1)));
+
+		let unknown_call = with_length(vec![4, 5, 9]);
+		assert_eq!(
+			decode_extrinsic(&metadata, &unknown_call),
+			Err(MetadataError::VariantIndexNotFound {
+				pallet: "Balances".into(),
+				kind: "a call",
+				index: 9
+			})
+		);
+	}
+}
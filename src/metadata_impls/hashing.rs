@@ -0,0 +1,140 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The hashers that storage keys are built with, so that storage keys can be constructed
+//! without any help from a node.
+
+use blake2::{
+	digest::{Update, VariableOutput},
+	Blake2bVar,
+};
+use frame_metadata::v14::StorageHasher;
+use std::hash::Hasher;
+use twox_hash::XxHash64;
+
+/// Hash some bytes with the hasher given. Concatenating hashers append the bytes
+/// themselves to the hash.
+pub(super) fn hash(hasher: &StorageHasher, bytes: &[u8]) -> Vec<u8> {
+	match hasher {
+		StorageHasher::Blake2_128 => blake2b(bytes, 16),
+		StorageHasher::Blake2_256 => blake2b(bytes, 32),
+		StorageHasher::Blake2_128Concat => [blake2b(bytes, 16), bytes.to_vec()].concat(),
+		StorageHasher::Twox128 => twox(bytes, 2),
+		StorageHasher::Twox256 => twox(bytes, 4),
+		StorageHasher::Twox64Concat => [twox(bytes, 1), bytes.to_vec()].concat(),
+		StorageHasher::Identity => bytes.to_vec(),
+	}
+}
+
+/// The number of bytes of hash that the hasher given outputs before the bytes that
+/// it appends (if any).
+pub(super) fn hash_len(hasher: &StorageHasher) -> usize {
+	match hasher {
+		StorageHasher::Blake2_128 | StorageHasher::Blake2_128Concat | StorageHasher::Twox128 => 16,
+		StorageHasher::Blake2_256 | StorageHasher::Twox256 => 32,
+		StorageHasher::Twox64Concat => 8,
+		StorageHasher::Identity => 0,
+	}
+}
+
+/// Does the hasher given append the bytes that were hashed to the hash?
+pub(super) fn is_concat(hasher: &StorageHasher) -> bool {
+	matches!(
+		hasher,
+		StorageHasher::Blake2_128Concat | StorageHasher::Twox64Concat | StorageHasher::Identity
+	)
+}
+
+/// The hash used for storage prefixes; the little endian bytes of two xxhash64 hashes.
+pub(super) fn twox_128(bytes: &[u8]) -> [u8; 16] {
+	let mut out = [0; 16];
+	out.copy_from_slice(&twox(bytes, 2));
+	out
+}
+
+/// Concatenate the little endian bytes of `n` xxhash64 hashes, seeded with 0 to `n - 1`.
+fn twox(bytes: &[u8], n: u64) -> Vec<u8> {
+	(0..n)
+		.flat_map(|seed| {
+			let mut hasher = XxHash64::with_seed(seed);
+			hasher.write(bytes);
+			hasher.finish().to_le_bytes()
+		})
+		.collect()
+}
+
+/// A blake2b hash of the length given (in bytes).
+fn blake2b(bytes: &[u8], out_len: usize) -> Vec<u8> {
+	let mut hasher = Blake2bVar::new(out_len).expect("storage hashes are a valid length");
+	hasher.update(bytes);
+	let mut out = vec![0; out_len];
+	hasher.finalize_variable(&mut out).expect("output is the length asked for");
+	out
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn hex(bytes: &[u8]) -> String {
+		bytes.iter().map(|b| format!("{b:02x}")).collect()
+	}
+
+	#[test]
+	fn twox_128_matches_substrate() {
+		assert_eq!(hex(&twox_128(b"System")), "26aa394eea5630e07c48ae0c9558cef7");
+		assert_eq!(hex(&twox_128(b"Events")), "80d41e5e16056765bc8461851072c9d7");
+		assert_eq!(hex(&twox_128(b"Balances")), "c2261276cc9d1f8598ea4b6a74b15c2f");
+	}
+
+	#[test]
+	fn blake2b_matches_reference() {
+		assert_eq!(hex(&blake2b(b"", 16)), "cae66941d9efbd404e4d88758ea67670");
+		assert_eq!(
+			hex(&blake2b(b"", 32)),
+			"0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+		);
+		assert_eq!(
+			hex(&blake2b(b"abc", 32)),
+			"bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319"
+		);
+		// Exactly one block, and then several blocks:
+		let block: Vec<u8> = (0..128).collect();
+		assert_eq!(hex(&blake2b(&block, 16)), "a74787004ef589e31149183900d0294a");
+		let blocks: Vec<u8> = (0..512).map(|n| n as u8).collect();
+		assert_eq!(
+			hex(&blake2b(&blocks, 32)),
+			"540b20132d8aeae54057cb69c24f95d26a1c472cc700dd450defe9bb796d4f14"
+		);
+	}
+
+	#[test]
+	fn concat_hashers_append_the_bytes() {
+		for hasher in [
+			StorageHasher::Blake2_128,
+			StorageHasher::Blake2_256,
+			StorageHasher::Blake2_128Concat,
+			StorageHasher::Twox128,
+			StorageHasher::Twox256,
+			StorageHasher::Twox64Concat,
+			StorageHasher::Identity,
+		] {
+			let hashed = hash(&hasher, b"hello");
+			let appended = if is_concat(&hasher) { 5 } else { 0 };
+			assert_eq!(hashed.len(), hash_len(&hasher) + appended);
+			assert!(hashed.ends_with(if is_concat(&hasher) { b"hello" } else { b"" }));
+		}
+	}
+}
@@ -0,0 +1,276 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod calls;
+mod constants;
+mod events;
+mod extrinsics;
+mod hashing;
+mod module_errors;
+mod storage;
+#[cfg(test)]
+mod test_runtime;
+
+use crate::scale_impls::{decode_value_as_type, DecodeError, EncodeError, LocationSegment, TypeId};
+use crate::value::{Composite, Value};
+use frame_metadata::v14::{PalletMetadata, RuntimeMetadataV14, StorageEntryMetadata};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, Variant};
+
+pub use calls::{decode_call, encode_call, Call};
+pub use constants::{constants, Constant};
+pub use events::{decode_events, EventRecord};
+pub use extrinsics::{decode_extrinsic, encode_unsigned_extrinsic, Extrinsic, ExtrinsicSignature};
+pub use module_errors::{decode_module_error, ModuleError};
+pub use storage::{decode_storage_key, decode_storage_value, storage_key};
+
+/// An error working with some SCALE encoded data using the information in some metadata.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum MetadataError {
+	/// The bytes could not be decoded into the expected type.
+	#[error("{0}")]
+	Decode(#[from] DecodeError),
+	/// No pallet with the index given exists.
+	#[error("Cannot find a pallet with index {0}")]
+	PalletIndexNotFound(u8),
+	/// No pallet with the name given exists.
+	#[error("Cannot find a pallet called {0}")]
+	PalletNotFound(String),
+	/// The pallet doesn't have any of the things (for instance events) that we're looking for.
+	#[error("The {pallet} pallet has no {kind}")]
+	PalletHasNo {
+		/// The name of the pallet.
+		pallet: String,
+		/// What the pallet doesn't have, for instance "events" or "calls".
+		kind: &'static str,
+	},
+	/// The pallet doesn't have a variant (for instance an event) with the index given.
+	#[error("Cannot find {kind} with index {index} in the {pallet} pallet")]
+	VariantIndexNotFound {
+		/// The name of the pallet.
+		pallet: String,
+		/// What we were looking for, for instance "an event" or "a call".
+		kind: &'static str,
+		/// The index we were looking for.
+		index: u8,
+	},
+	/// The pallet doesn't have a variant (for instance a call) with the name given.
+	#[error("Cannot find {kind} called {name} in the {pallet} pallet")]
+	VariantNotFound {
+		/// The name of the pallet.
+		pallet: String,
+		/// What we were looking for, for instance "an event" or "a call".
+		kind: &'static str,
+		/// The name we were looking for.
+		name: String,
+	},
+	/// The extrinsic is a version that the metadata doesn't describe.
+	#[error("Extrinsic version {found} is not supported; expected version {expected}")]
+	UnsupportedExtrinsicVersion {
+		/// The version of the extrinsic.
+		found: u8,
+		/// The version described by the metadata.
+		expected: u8,
+	},
+	/// The storage entry that we're looking for doesn't exist.
+	#[error("Cannot find the storage entry {pallet}.{entry}")]
+	StorageEntryNotFound {
+		/// The name of the pallet.
+		pallet: String,
+		/// The name of the storage entry.
+		entry: String,
+	},
+	/// The storage key doesn't belong to the storage entry that it's being decoded for.
+	#[error("The storage key given is not a key for {pallet}.{entry}")]
+	StorageKeyMismatch {
+		/// The name of the pallet.
+		pallet: String,
+		/// The name of the storage entry.
+		entry: String,
+	},
+	/// A type in the metadata isn't the shape that we expect.
+	#[error("Type {type_id} is not {expected}")]
+	UnexpectedType {
+		/// The type that isn't what we expect.
+		type_id: u32,
+		/// What we expected the type to be.
+		expected: &'static str,
+	},
+	/// Some bytes were left over after decoding everything that we expected to.
+	#[error("{0} bytes were left over after decoding")]
+	TrailingBytes(usize),
+}
+
+/// An error encoding some [`Value`]s into SCALE bytes using the information in some metadata.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum MetadataEncodeError<T> {
+	/// Something we need couldn't be found in the metadata.
+	#[error("{0}")]
+	Metadata(#[from] MetadataError),
+	/// A value could not be encoded into the expected type.
+	#[error("{0}")]
+	Encode(#[from] EncodeError<T>),
+	/// An argument that the call expects wasn't given.
+	#[error("The argument {argument} of {call} was not given")]
+	ArgumentMissing {
+		/// The name of the call.
+		call: String,
+		/// The name of the argument that's missing.
+		argument: String,
+	},
+	/// An argument was given that the call doesn't expect.
+	#[error("{call} has no argument called {argument}")]
+	UnexpectedArgument {
+		/// The name of the call.
+		call: String,
+		/// The name of the argument that isn't expected.
+		argument: String,
+	},
+	/// Named arguments were given to a call whose arguments don't have names.
+	#[error("The arguments of {call} have no names, so they must be given in order")]
+	ArgumentsAreUnnamed {
+		/// The name of the call.
+		call: String,
+	},
+	/// The storage entry was given the wrong number of keys.
+	#[error("{pallet}.{entry} expects {expected} keys, but {actual} were given")]
+	WrongNumberOfKeys {
+		/// The name of the pallet.
+		pallet: String,
+		/// The name of the storage entry.
+		entry: String,
+		/// The number of keys that the storage entry expects.
+		expected: usize,
+		/// The number of keys given.
+		actual: usize,
+	},
+	/// The call was given the wrong number of arguments.
+	#[error("{call} expects {expected} arguments, but {actual} were given")]
+	WrongNumberOfArguments {
+		/// The name of the call.
+		call: String,
+		/// The number of arguments that the call expects.
+		expected: usize,
+		/// The number of arguments given.
+		actual: usize,
+	},
+}
+
+impl From<codec::Error> for MetadataError {
+	fn from(err: codec::Error) -> Self {
+		MetadataError::Decode(err.into())
+	}
+}
+
+/// Find the pallet with the index given.
+fn pallet_by_index(
+	metadata: &RuntimeMetadataV14,
+	index: u8,
+) -> Result<&PalletMetadata<PortableForm>, MetadataError> {
+	metadata
+		.pallets
+		.iter()
+		.find(|p| p.index == index)
+		.ok_or(MetadataError::PalletIndexNotFound(index))
+}
+
+/// Find the pallet with the name given.
+fn pallet_by_name<'a>(
+	metadata: &'a RuntimeMetadataV14,
+	name: &str,
+) -> Result<&'a PalletMetadata<PortableForm>, MetadataError> {
+	metadata
+		.pallets
+		.iter()
+		.find(|p| p.name == name)
+		.ok_or_else(|| MetadataError::PalletNotFound(name.to_owned()))
+}
+
+/// Find the storage entry with the name given in the pallet with the name given, returning
+/// the prefix of the pallet's storage alongside it.
+fn storage_entry<'a>(
+	metadata: &'a RuntimeMetadataV14,
+	pallet_name: &str,
+	entry_name: &str,
+) -> Result<(&'a str, &'a StorageEntryMetadata<PortableForm>), MetadataError> {
+	pallet_by_name(metadata, pallet_name)?
+		.storage
+		.as_ref()
+		.and_then(|storage| {
+			let entry = storage.entries.iter().find(|e| e.name == entry_name)?;
+			Some((&*storage.prefix, entry))
+		})
+		.ok_or_else(|| MetadataError::StorageEntryNotFound {
+			pallet: pallet_name.to_owned(),
+			entry: entry_name.to_owned(),
+		})
+}
+
+/// Return the variants of the enum type given.
+fn variants(
+	ty_id: u32,
+	types: &PortableRegistry,
+) -> Result<&[Variant<PortableForm>], MetadataError> {
+	match types.resolve(ty_id).map(|ty| &ty.type_def) {
+		Some(TypeDef::Variant(inner)) => Ok(&inner.variants),
+		_ => Err(MetadataError::UnexpectedType { type_id: ty_id, expected: "an enum" }),
+	}
+}
+
+/// Decode each of the fields given, one after the other, into a composite value.
+fn decode_fields(
+	data: &mut &[u8],
+	fields: &[Field<PortableForm>],
+	types: &PortableRegistry,
+) -> Result<Composite<TypeId>, DecodeError> {
+	let is_named = fields.first().map(|f| f.name.is_some()).unwrap_or(false);
+	if is_named {
+		fields
+			.iter()
+			.map(|field| {
+				let name = field.name.clone().unwrap_or_default();
+				let value = decode_value_as_type(data, field.ty, types).map_err(|mut e| {
+					e.location.prepend(LocationSegment::Field(name.clone()));
+					e
+				})?;
+				Ok((name, value))
+			})
+			.collect::<Result<_, _>>()
+			.map(Composite::Named)
+	} else {
+		fields
+			.iter()
+			.enumerate()
+			.map(|(idx, field)| {
+				decode_value_as_type(data, field.ty, types).map_err(|mut e| {
+					e.location.prepend(LocationSegment::unnamed_field(idx, fields.len()));
+					e
+				})
+			})
+			.collect::<Result<_, _>>()
+			.map(Composite::Unnamed)
+	}
+}
+
+/// Decode the fields of a variant (for instance an event or a call) into a composite value,
+/// whose context is the type of the enum that the variant belongs to.
+fn decode_variant_fields(
+	data: &mut &[u8],
+	variant: &Variant<PortableForm>,
+	enum_ty: TypeId,
+	types: &PortableRegistry,
+) -> Result<Value<TypeId>, DecodeError> {
+	let fields = decode_fields(data, &variant.fields, types)?;
+	Ok(Value::with_context(fields.into(), enum_ty))
+}
@@ -0,0 +1,126 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{decode_variant_fields, pallet_by_index, variants, MetadataError};
+use crate::scale_impls::TypeId;
+use crate::value::Value;
+use codec::Decode;
+use frame_metadata::v14::RuntimeMetadataV14;
+
+/// An error from some pallet, decoded from the index of the pallet and the error bytes
+/// in a `DispatchError::Module`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleError {
+	/// The name of the pallet that the error is from.
+	pub pallet_name: String,
+	/// The name of the error.
+	pub error_name: String,
+	/// The fields of the error, as a composite value whose context is the pallet's error type.
+	pub fields: Value<TypeId>,
+	/// The documentation for the error, one line at a time.
+	pub docs: Vec<String>,
+}
+
+/// Decode the error bytes of a `DispatchError::Module` from the pallet at `pallet_index`. The
+/// first byte is the index of the error, followed by its fields. Runtimes pad the error out to
+/// a fixed size with zeros, and so any zeros left over after decoding are ignored.
+pub fn decode_module_error(
+	metadata: &RuntimeMetadataV14,
+	pallet_index: u8,
+	error_bytes: &[u8],
+) -> Result<ModuleError, MetadataError> {
+	let pallet = pallet_by_index(metadata, pallet_index)?;
+	let error_ty = pallet
+		.error
+		.as_ref()
+		.ok_or_else(|| MetadataError::PalletHasNo { pallet: pallet.name.clone(), kind: "errors" })?
+		.ty
+		.id;
+
+	let data = &mut &*error_bytes;
+	let index = u8::decode(data)?;
+	let variant = variants(error_ty, &metadata.types)?
+		.iter()
+		.find(|v| v.index == index)
+		.ok_or_else(|| MetadataError::VariantIndexNotFound {
+			pallet: pallet.name.clone(),
+			kind: "an error",
+			index,
+		})?;
+
+	let fields = decode_variant_fields(data, variant, error_ty.into(), &metadata.types)?;
+	if data.iter().any(|&b| b != 0) {
+		return Err(MetadataError::TrailingBytes(data.len()));
+	}
+
+	Ok(ModuleError {
+		pallet_name: pallet.name.clone(),
+		error_name: variant.name.clone(),
+		fields,
+		docs: variant.docs.clone(),
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::metadata_impls::test_runtime::metadata;
+
+	#[test]
+	fn decodes_module_errors() {
+		let metadata = metadata();
+
+		// Errors can be a single byte, or padded out to a fixed size:
+		for bytes in [&[1][..], &[1, 0, 0, 0]] {
+			let err = decode_module_error(&metadata, 5, bytes).expect("can decode");
+			assert_eq!(err.pallet_name, "Balances");
+			assert_eq!(err.error_name, "InsufficientBalance");
+			assert_eq!(err.fields.map_context(|_| ()), Value::unnamed_composite(vec![]));
+			assert_eq!(err.docs, vec!["Balance too low to send value.".to_owned()]);
+		}
+
+		let err = decode_module_error(&metadata, 5, &[2, 7, 0, 0]).expect("can decode");
+		assert_eq!((&*err.pallet_name, &*err.error_name), ("Balances", "TooManyReserves"));
+		assert_eq!(
+			err.fields.map_context(|_| ()),
+			Value::unnamed_composite(vec![Value::uint(7u8)])
+		);
+	}
+
+	#[test]
+	fn reports_errors_decoding_module_errors() {
+		let metadata = metadata();
+		assert_eq!(
+			decode_module_error(&metadata, 9, &[0]),
+			Err(MetadataError::PalletIndexNotFound(9))
+		);
+		assert_eq!(
+			decode_module_error(&metadata, 0, &[0]),
+			Err(MetadataError::PalletHasNo { pallet: "System".into(), kind: "errors" })
+		);
+		assert_eq!(
+			decode_module_error(&metadata, 5, &[3, 0, 0, 0]),
+			Err(MetadataError::VariantIndexNotFound {
+				pallet: "Balances".into(),
+				kind: "an error",
+				index: 3
+			})
+		);
+		assert_eq!(
+			decode_module_error(&metadata, 5, &[1, 0, 1, 0]),
+			Err(MetadataError::TrailingBytes(3))
+		);
+	}
+}
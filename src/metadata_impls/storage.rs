@@ -0,0 +1,275 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::hashing::{hash, hash_len, is_concat, twox_128};
+use super::{storage_entry, MetadataEncodeError, MetadataError};
+use crate::scale_impls::{decode_value_as_type, encode_value_as_type, LocationSegment, TypeId};
+use crate::value::Value;
+use frame_metadata::v14::{
+	RuntimeMetadataV14, StorageEntryMetadata, StorageEntryModifier, StorageEntryType, StorageHasher,
+};
+use scale_info::{form::PortableForm, TypeDef};
+
+/// Build the full storage key for the entry `entry_name` in the pallet `pallet_name`. Plain
+/// entries take no keys, and maps take one key for each hasher that they're declared with.
+pub fn storage_key<T>(
+	metadata: &RuntimeMetadataV14,
+	pallet_name: &str,
+	entry_name: &str,
+	keys: Vec<Value<T>>,
+) -> Result<Vec<u8>, MetadataEncodeError<T>> {
+	let (prefix, entry) = storage_entry(metadata, pallet_name, entry_name)?;
+	let hashed_keys = hashed_keys(metadata, entry)?;
+	if keys.len() != hashed_keys.len() {
+		return Err(MetadataEncodeError::WrongNumberOfKeys {
+			pallet: pallet_name.to_owned(),
+			entry: entry_name.to_owned(),
+			expected: hashed_keys.len(),
+			actual: keys.len(),
+		});
+	}
+
+	let mut out = storage_prefix(prefix, entry);
+	let mut bytes = Vec::new();
+	for (idx, (key, (hasher, ty))) in keys.into_iter().zip(hashed_keys).enumerate() {
+		bytes.clear();
+		encode_value_as_type(key, ty, &metadata.types, &mut bytes).map_err(|mut e| {
+			e.location.prepend(LocationSegment::Index(idx));
+			e
+		})?;
+		out.extend(hash(hasher, &bytes));
+	}
+	Ok(out)
+}
+
+/// Decode the keys from a full storage key for the entry `entry_name` in the pallet
+/// `pallet_name`. Only keys hashed with a concatenating hasher (`Blake2_128Concat`,
+/// `Twox64Concat` or `Identity`) can be recovered, and so the others are given back as
+/// `None`.
+pub fn decode_storage_key(
+	metadata: &RuntimeMetadataV14,
+	pallet_name: &str,
+	entry_name: &str,
+	key: &[u8],
+) -> Result<Vec<Option<Value<TypeId>>>, MetadataError> {
+	let (prefix, entry) = storage_entry(metadata, pallet_name, entry_name)?;
+	let mismatch = || MetadataError::StorageKeyMismatch {
+		pallet: pallet_name.to_owned(),
+		entry: entry_name.to_owned(),
+	};
+
+	let data = &mut key.strip_prefix(&*storage_prefix(prefix, entry)).ok_or_else(mismatch)?;
+	let mut values = Vec::new();
+	for (hasher, ty) in hashed_keys(metadata, entry)? {
+		let hashed = *data;
+		*data = data.get(hash_len(hasher)..).ok_or_else(mismatch)?;
+		if !is_concat(hasher) {
+			values.push(None);
+			continue;
+		}
+
+		let encoded = *data;
+		let value = decode_value_as_type(data, ty, &metadata.types)?;
+		let encoded = &encoded[..encoded.len() - data.len()];
+		// Make sure that the bytes really do hash to what's in the key:
+		if !hashed.starts_with(&hash(hasher, encoded)) {
+			return Err(mismatch());
+		}
+		values.push(Some(value));
+	}
+
+	if !data.is_empty() {
+		return Err(MetadataError::TrailingBytes(data.len()));
+	}
+	Ok(values)
+}
+
+/// Decode the value stored at some key of the entry `entry_name` in the pallet `pallet_name`.
+/// If nothing is stored there, the default value that the metadata gives is decoded instead,
+/// unless the entry is optional, in which case `None` is returned.
+pub fn decode_storage_value(
+	metadata: &RuntimeMetadataV14,
+	pallet_name: &str,
+	entry_name: &str,
+	bytes: Option<&[u8]>,
+) -> Result<Option<Value<TypeId>>, MetadataError> {
+	let (_, entry) = storage_entry(metadata, pallet_name, entry_name)?;
+	let data = &mut match (bytes, &entry.modifier) {
+		(Some(bytes), _) => bytes,
+		(None, StorageEntryModifier::Optional) => return Ok(None),
+		(None, StorageEntryModifier::Default) => &*entry.default,
+	};
+
+	let value_ty = match &entry.ty {
+		StorageEntryType::Plain(ty) => ty.id,
+		StorageEntryType::Map { value, .. } => value.id,
+	};
+	let value = decode_value_as_type(data, value_ty, &metadata.types)?;
+	if !data.is_empty() {
+		return Err(MetadataError::TrailingBytes(data.len()));
+	}
+	Ok(Some(value))
+}
+
+/// Every storage key starts with the hashed pallet prefix and the hashed entry name.
+fn storage_prefix(prefix: &str, entry: &StorageEntryMetadata<PortableForm>) -> Vec<u8> {
+	[twox_128(prefix.as_bytes()), twox_128(entry.name.as_bytes())].concat()
+}
+
+/// Each hasher that a storage map uses alongside the type of the key that it hashes. Maps
+/// with more than one hasher have a tuple of keys, one for each hasher.
+fn hashed_keys<'a>(
+	metadata: &RuntimeMetadataV14,
+	entry: &'a StorageEntryMetadata<PortableForm>,
+) -> Result<Vec<(&'a StorageHasher, u32)>, MetadataError> {
+	let (hashers, key_ty) = match &entry.ty {
+		StorageEntryType::Plain(_) => return Ok(Vec::new()),
+		StorageEntryType::Map { hashers, key, .. } => (hashers, key.id),
+	};
+	if hashers.len() == 1 {
+		return Ok(vec![(&hashers[0], key_ty)]);
+	}
+
+	match metadata.types.resolve(key_ty).map(|ty| &ty.type_def) {
+		Some(TypeDef::Tuple(tuple)) if tuple.fields.len() == hashers.len() => {
+			Ok(hashers.iter().zip(tuple.fields.iter().map(|ty| ty.id)).collect())
+		}
+		_ => Err(MetadataError::UnexpectedType {
+			type_id: key_ty,
+			expected: "a tuple with a key for each hasher",
+		}),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::metadata_impls::test_runtime::{metadata, AccountData};
+	use codec::Encode;
+
+	fn without_context(value: Option<Value<TypeId>>) -> Option<Value<()>> {
+		value.map(|v| v.map_context(|_| ()))
+	}
+
+	fn account_id(n: u8) -> Value<()> {
+		Value::unnamed_composite(vec![Value::uint(n); 32])
+	}
+
+	#[test]
+	fn builds_storage_keys() {
+		let metadata = metadata();
+		let balances = twox_128(b"Balances");
+
+		let key = storage_key::<()>(&metadata, "Balances", "TotalIssuance", vec![]).unwrap();
+		assert_eq!(key, [balances, twox_128(b"TotalIssuance")].concat());
+
+		let key = storage_key(&metadata, "Balances", "Account", vec![account_id(1)]).unwrap();
+		let expected = [
+			&balances[..],
+			&twox_128(b"Account"),
+			&hash(&StorageHasher::Blake2_128Concat, &[1; 32]),
+		]
+		.concat();
+		assert_eq!(key, expected);
+	}
+
+	#[test]
+	fn decodes_storage_keys() {
+		let metadata = metadata();
+		let key =
+			storage_key(&metadata, "Balances", "Reserves", vec![account_id(2), Value::uint(7u8)])
+				.unwrap();
+
+		// The account was hashed with a non-concatenating hasher and so can't be recovered:
+		let keys = decode_storage_key(&metadata, "Balances", "Reserves", &key).unwrap();
+		let keys: Vec<_> = keys.into_iter().map(without_context).collect();
+		assert_eq!(keys, vec![None, Some(Value::uint(7u8))]);
+
+		let key = storage_key(&metadata, "Balances", "Account", vec![account_id(3)]).unwrap();
+		let keys = decode_storage_key(&metadata, "Balances", "Account", &key).unwrap();
+		let keys: Vec<_> = keys.into_iter().map(without_context).collect();
+		assert_eq!(keys, vec![Some(account_id(3))]);
+
+		// Keys for other entries, or whose hashes don't match, are rejected:
+		let mismatch = Err(MetadataError::StorageKeyMismatch {
+			pallet: "Balances".into(),
+			entry: "Reserves".into(),
+		});
+		assert_eq!(decode_storage_key(&metadata, "Balances", "Reserves", &key), mismatch);
+		let mut tampered = key.clone();
+		*tampered.last_mut().unwrap() = 4;
+		assert_eq!(
+			decode_storage_key(&metadata, "Balances", "Account", &tampered),
+			Err(MetadataError::StorageKeyMismatch {
+				pallet: "Balances".into(),
+				entry: "Account".into()
+			})
+		);
+	}
+
+	#[test]
+	fn reports_errors_building_storage_keys() {
+		let metadata = metadata();
+		assert_eq!(
+			storage_key::<()>(&metadata, "Balances", "Account", vec![]),
+			Err(MetadataEncodeError::WrongNumberOfKeys {
+				pallet: "Balances".into(),
+				entry: "Account".into(),
+				expected: 1,
+				actual: 0
+			})
+		);
+		assert_eq!(
+			storage_key::<()>(&metadata, "Balances", "Locks", vec![]),
+			Err(MetadataError::StorageEntryNotFound {
+				pallet: "Balances".into(),
+				entry: "Locks".into()
+			}
+			.into())
+		);
+
+		let err =
+			storage_key(&metadata, "Balances", "Reserves", vec![account_id(1), Value::bool(true)])
+				.unwrap_err();
+		let MetadataEncodeError::Encode(err) = err else { panic!("expected an encode error") };
+		assert_eq!(err.location.to_string(), "[1]");
+	}
+
+	#[test]
+	fn decodes_storage_values() {
+		let metadata = metadata();
+		let account_data = |free: u128, reserved: u128| {
+			Value::named_composite(vec![
+				("free".into(), Value::uint(free)),
+				("reserved".into(), Value::uint(reserved)),
+			])
+		};
+
+		let stored = AccountData { free: 100, reserved: 5 }.encode();
+		let value = decode_storage_value(&metadata, "Balances", "Account", Some(&stored)).unwrap();
+		assert_eq!(without_context(value), Some(account_data(100, 5)));
+
+		// Nothing stored means the default value, or None for optional entries:
+		let value = decode_storage_value(&metadata, "Balances", "Account", None).unwrap();
+		assert_eq!(without_context(value), Some(account_data(0, 0)));
+		assert_eq!(decode_storage_value(&metadata, "Balances", "Reserves", None), Ok(None));
+
+		let stored = [stored, vec![0]].concat();
+		assert_eq!(
+			decode_storage_value(&metadata, "Balances", "Account", Some(&stored)),
+			Err(MetadataError::TrailingBytes(1))
+		);
+	}
+}
@@ -0,0 +1,257 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small, made up runtime, and metadata describing it, to test against.
+
+// Not every type is constructed in every test.
+#![allow(dead_code)]
+
+use codec::Encode;
+use frame_metadata::v14::{
+	ExtrinsicMetadata, PalletCallMetadata, PalletConstantMetadata, PalletErrorMetadata,
+	PalletEventMetadata, PalletMetadata, PalletStorageMetadata, RuntimeMetadataV14,
+	SignedExtensionMetadata, StorageEntryMetadata, StorageEntryModifier, StorageEntryType,
+	StorageHasher,
+};
+use scale_info::{meta_type, TypeInfo};
+use std::marker::PhantomData;
+
+pub type AccountId = [u8; 32];
+
+#[derive(Encode, TypeInfo)]
+pub enum Phase {
+	ApplyExtrinsic(u32),
+	Finalization,
+	Initialization,
+}
+
+#[derive(Encode, TypeInfo)]
+pub struct EventRecord {
+	pub phase: Phase,
+	pub event: RuntimeEvent,
+	pub topics: Vec<[u8; 32]>,
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum RuntimeEvent {
+	#[codec(index = 0)]
+	System(SystemEvent),
+	#[codec(index = 5)]
+	Balances(BalancesEvent),
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum SystemEvent {
+	ExtrinsicSuccess { weight: u64 },
+	NewAccount { account: AccountId },
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum BalancesEvent {
+	Endowed { account: AccountId, free_balance: u128 },
+	Transfer { from: AccountId, to: AccountId, amount: u128 },
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum BalancesError {
+	/// Vesting balance too high to send value.
+	VestingBalance,
+	/// Balance too low to send value.
+	InsufficientBalance,
+	/// Too many reserves were placed on the account.
+	TooManyReserves(u8),
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum RuntimeCall {
+	#[codec(index = 0)]
+	System(SystemCall),
+	#[codec(index = 5)]
+	Balances(BalancesCall),
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Encode, TypeInfo)]
+pub enum SystemCall {
+	remark { remark: Vec<u8> },
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Encode, TypeInfo)]
+pub enum BalancesCall {
+	#[codec(index = 3)]
+	transfer {
+		dest: MultiAddress,
+		#[codec(compact)]
+		value: u128,
+	},
+	#[codec(index = 4)]
+	transfer_all { dest: MultiAddress, keep_alive: bool },
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum MultiAddress {
+	Id(AccountId),
+	Index(#[codec(compact)] u32),
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum MultiSignature {
+	Ed25519([u8; 64]),
+	Sr25519([u8; 64]),
+}
+
+#[derive(Encode, TypeInfo)]
+pub struct AccountData {
+	pub free: u128,
+	pub reserved: u128,
+}
+
+#[derive(Encode, TypeInfo)]
+pub struct CheckSpecVersion;
+
+#[derive(Encode, TypeInfo)]
+pub struct CheckNonce(#[codec(compact)] pub u32);
+
+#[derive(Encode, TypeInfo)]
+pub struct ChargeTransactionPayment(#[codec(compact)] pub u128);
+
+/// The signed extensions, in the order that they're encoded in.
+pub type Extra = (CheckSpecVersion, CheckNonce, ChargeTransactionPayment);
+
+/// Only the type parameters of this matter, since they tell us the shape of the
+/// address and signature in a signed extrinsic.
+#[derive(TypeInfo)]
+pub struct UncheckedExtrinsic<Address, Call, Signature, Extra>(
+	PhantomData<(Address, Call, Signature, Extra)>,
+);
+
+#[derive(TypeInfo)]
+pub struct Runtime;
+
+fn system_pallet() -> PalletMetadata {
+	PalletMetadata {
+		name: "System",
+		storage: Some(PalletStorageMetadata {
+			prefix: "System",
+			entries: vec![StorageEntryMetadata {
+				name: "Events",
+				modifier: StorageEntryModifier::Default,
+				ty: StorageEntryType::Plain(meta_type::<Vec<EventRecord>>()),
+				default: vec![0],
+				docs: vec![],
+			}],
+		}),
+		calls: Some(PalletCallMetadata { ty: meta_type::<SystemCall>() }),
+		event: Some(PalletEventMetadata { ty: meta_type::<SystemEvent>() }),
+		constants: vec![PalletConstantMetadata {
+			name: "BlockHashCount",
+			ty: meta_type::<u32>(),
+			value: 2400u32.encode(),
+			docs: vec![" Maximum number of block number to block hash mappings to keep."],
+		}],
+		error: None,
+		index: 0,
+	}
+}
+
+fn balances_pallet() -> PalletMetadata {
+	PalletMetadata {
+		name: "Balances",
+		storage: Some(PalletStorageMetadata {
+			prefix: "Balances",
+			entries: vec![
+				StorageEntryMetadata {
+					name: "TotalIssuance",
+					modifier: StorageEntryModifier::Default,
+					ty: StorageEntryType::Plain(meta_type::<u128>()),
+					default: 0u128.encode(),
+					docs: vec![],
+				},
+				StorageEntryMetadata {
+					name: "Account",
+					modifier: StorageEntryModifier::Default,
+					ty: StorageEntryType::Map {
+						hashers: vec![StorageHasher::Blake2_128Concat],
+						key: meta_type::<AccountId>(),
+						value: meta_type::<AccountData>(),
+					},
+					default: AccountData { free: 0, reserved: 0 }.encode(),
+					docs: vec![],
+				},
+				StorageEntryMetadata {
+					name: "Reserves",
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::Map {
+						hashers: vec![StorageHasher::Blake2_128, StorageHasher::Twox64Concat],
+						key: meta_type::<(AccountId, u32)>(),
+						value: meta_type::<u128>(),
+					},
+					default: vec![0],
+					docs: vec![],
+				},
+			],
+		}),
+		calls: Some(PalletCallMetadata { ty: meta_type::<BalancesCall>() }),
+		event: Some(PalletEventMetadata { ty: meta_type::<BalancesEvent>() }),
+		constants: vec![
+			PalletConstantMetadata {
+				name: "ExistentialDeposit",
+				ty: meta_type::<u128>(),
+				value: 500u128.encode(),
+				docs: vec![" The minimum amount required to keep an account open."],
+			},
+			PalletConstantMetadata {
+				name: "MaxLocks",
+				ty: meta_type::<u32>(),
+				value: 50u32.encode(),
+				docs: vec![],
+			},
+		],
+		error: Some(PalletErrorMetadata { ty: meta_type::<BalancesError>() }),
+		index: 5,
+	}
+}
+
+/// Metadata for our test runtime.
+pub fn metadata() -> RuntimeMetadataV14 {
+	let signed_extension = |identifier, ty, additional_signed| SignedExtensionMetadata {
+		identifier,
+		ty,
+		additional_signed,
+	};
+	let extrinsic = ExtrinsicMetadata {
+		ty: meta_type::<UncheckedExtrinsic<MultiAddress, RuntimeCall, MultiSignature, Extra>>(),
+		version: 4,
+		signed_extensions: vec![
+			signed_extension(
+				"CheckSpecVersion",
+				meta_type::<CheckSpecVersion>(),
+				meta_type::<u32>(),
+			),
+			signed_extension("CheckNonce", meta_type::<CheckNonce>(), meta_type::<()>()),
+			signed_extension(
+				"ChargeTransactionPayment",
+				meta_type::<ChargeTransactionPayment>(),
+				meta_type::<()>(),
+			),
+		],
+	};
+	RuntimeMetadataV14::new(
+		vec![system_pallet(), balances_pallet()],
+		extrinsic,
+		meta_type::<Runtime>(),
+	)
+}
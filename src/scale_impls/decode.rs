@@ -14,25 +14,60 @@
 // limitations under the License.
 
 use super::{
-	bit_sequence::{get_bitsequence_details, BitOrderTy, BitSequenceError, BitStoreTy},
+	bit_sequence::BitSequenceError,
+	location::{Location, LocationSegment},
 	type_id::TypeId,
-	ScaleType as Type, ScaleTypeDef as TypeDef,
-};
-use crate::value::{BitSequence, Composite, Primitive, Value, ValueDef, Variant};
-use bitvec::{
-	order::{BitOrder, Lsb0, Msb0},
-	store::BitStore,
-	vec::BitVec,
-};
-use codec::{Compact, Decode};
-use scale_info::{
-	form::PortableForm, Field, PortableRegistry, TypeDefArray, TypeDefBitSequence, TypeDefCompact,
-	TypeDefComposite, TypeDefPrimitive, TypeDefSequence, TypeDefTuple, TypeDefVariant,
+	visitor::{self, decode_with_visitor, CompactLocation, Visitor},
 };
+use crate::value::{Composite, Primitive, Value, ValueDef, Variant};
+use scale_info::PortableRegistry;
+use std::fmt::Display;
+
+/// An error decoding SCALE bytes into a [`Value`], along with the [`Location`]
+/// of the value that could not be decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeError {
+	/// Where in the value being decoded the error occurred.
+	pub location: Location,
+	/// What went wrong.
+	pub kind: DecodeErrorKind,
+}
+
+impl DecodeError {
+	/// Note that this error occurred inside of some outer value.
+	fn at(mut self, segment: impl Into<Option<LocationSegment>>) -> Self {
+		self.location.prepend(segment);
+		self
+	}
+}
+
+impl From<DecodeErrorKind> for DecodeError {
+	fn from(kind: DecodeErrorKind) -> Self {
+		DecodeError { location: Location::new(), kind }
+	}
+}
+
+impl From<codec::Error> for DecodeError {
+	fn from(err: codec::Error) -> Self {
+		DecodeErrorKind::CodecError(err).into()
+	}
+}
+
+impl Display for DecodeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.location.is_empty() {
+			write!(f, "{}", self.kind)
+		} else {
+			write!(f, "at {}: {}", self.location, self.kind)
+		}
+	}
+}
+
+impl std::error::Error for DecodeError {}
 
-/// An error decoding SCALE bytes into a [`Value`].
+/// The different reasons that decoding SCALE bytes can fail.
 #[derive(Debug, Clone, thiserror::Error, PartialEq)]
-pub enum DecodeError {
+pub enum DecodeErrorKind {
 	/// Some error emitted from a [`codec::Decode`] impl.
 	#[error("{0}")]
 	CodecError(#[from] codec::Error),
@@ -45,15 +80,20 @@ pub enum DecodeError {
 	/// We expected more bytes to finish decoding, but could not find them.
 	#[error("Ran out of data during decoding")]
 	Eof,
-	/// We found a variant that does not match with any in the type we're trying to decode from.
-	#[error("Could not find variant with index {0} in {1:?}")]
-	VariantNotFound(u8, scale_info::TypeDefVariant<PortableForm>),
+	/// We found a variant index that does not match with any in the type we're trying to decode from,
+	/// whose name is given.
+	#[error("Could not find variant with index {0} in {1}")]
+	VariantNotFound(u8, String),
 	/// The type we're trying to decode is supposed to be compact encoded, but that is not possible.
-	#[error("Could not decode compact encoded type into {0:?}")]
-	CannotDecodeCompactIntoType(Type),
+	/// The name of the type that cannot be compact decoded is given.
+	#[error("Could not decode compact encoded type into {0}")]
+	CannotDecodeCompactIntoType(String),
 	/// We ran into an error trying to decode a bit sequence.
 	#[error("Cannot decode bit sequence: {0}")]
 	BitSequenceError(BitSequenceError),
+	/// The [`Visitor`] we're decoding with was handed a kind of value that it does not accept.
+	#[error("The visitor does not accept a {0} value (type ID {1})")]
+	UnexpectedValue(&'static str, u32),
 }
 
 /// Decode data according to the [`TypeId`] provided.
@@ -64,261 +104,152 @@ pub fn decode_value_as_type<Id: Into<TypeId>>(
 	ty_id: Id,
 	types: &PortableRegistry,
 ) -> Result<Value<TypeId>, DecodeError> {
-	let ty_id = ty_id.into();
-	let ty = types.resolve(ty_id.id()).ok_or_else(|| DecodeError::TypeIdNotFound(ty_id.id()))?;
+	decode_with_visitor(data, ty_id, types, ValueVisitor)
+}
 
-	let value = match ty.type_def() {
-		TypeDef::Composite(inner) => {
-			decode_composite_value(data, inner, types).map(ValueDef::Composite)
-		}
-		TypeDef::Sequence(inner) => {
-			decode_sequence_value(data, inner, types).map(ValueDef::Composite)
-		}
-		TypeDef::Array(inner) => decode_array_value(data, inner, types).map(ValueDef::Composite),
-		TypeDef::Tuple(inner) => decode_tuple_value(data, inner, types).map(ValueDef::Composite),
-		TypeDef::Variant(inner) => decode_variant_value(data, inner, types).map(ValueDef::Variant),
-		TypeDef::Primitive(inner) => decode_primitive_value(data, inner).map(ValueDef::Primitive),
-		TypeDef::Compact(inner) => decode_compact_value(data, inner, types),
-		TypeDef::BitSequence(inner) => {
-			decode_bit_sequence_value(data, inner, types).map(ValueDef::BitSequence)
-		}
-	}?;
+/// A [`Visitor`] which decodes bytes into a [`Value`].
+struct ValueVisitor;
 
-	Ok(Value { value, context: ty_id })
-}
+impl Visitor for ValueVisitor {
+	type Value = Value<TypeId>;
+	type Error = DecodeError;
 
-fn decode_composite_value(
-	data: &mut &[u8],
-	ty: &TypeDefComposite<PortableForm>,
-	types: &PortableRegistry,
-) -> Result<Composite<TypeId>, DecodeError> {
-	decode_fields(data, ty.fields(), types)
+	fn visit_bool(self, value: bool, type_id: TypeId) -> Result<Self::Value, DecodeError> {
+		Ok(primitive_value(Primitive::Bool(value), type_id))
+	}
+	fn visit_char(self, value: char, type_id: TypeId) -> Result<Self::Value, DecodeError> {
+		Ok(primitive_value(Primitive::Char(value), type_id))
+	}
+	fn visit_str(self, value: &str, type_id: TypeId) -> Result<Self::Value, DecodeError> {
+		Ok(primitive_value(Primitive::String(value.to_string()), type_id))
+	}
+	fn visit_u128(self, value: u128, type_id: TypeId) -> Result<Self::Value, DecodeError> {
+		Ok(primitive_value(Primitive::U128(value), type_id))
+	}
+	fn visit_i128(self, value: i128, type_id: TypeId) -> Result<Self::Value, DecodeError> {
+		Ok(primitive_value(Primitive::I128(value), type_id))
+	}
+	fn visit_u256(self, value: &[u8; 32], type_id: TypeId) -> Result<Self::Value, DecodeError> {
+		Ok(primitive_value(Primitive::U256(*value), type_id))
+	}
+	fn visit_i256(self, value: &[u8; 32], type_id: TypeId) -> Result<Self::Value, DecodeError> {
+		Ok(primitive_value(Primitive::I256(*value), type_id))
+	}
+	fn visit_compact(
+		self,
+		value: visitor::Compact<'_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, DecodeError> {
+		let locations: Vec<_> = value.locations().collect();
+
+		// Build the primitive value, and then wrap it in a representation of each
+		// single-field composite type that it lives inside.
+		let mut inner = primitive_value(Primitive::U128(value.value()), type_id);
+		for location in locations.into_iter().rev() {
+			inner = match location {
+				CompactLocation::Primitive(id) => Value { context: id, ..inner },
+				CompactLocation::Named(name, id) => Value {
+					value: ValueDef::Composite(Composite::Named(vec![(name.to_string(), inner)])),
+					context: id,
+				},
+				CompactLocation::Unnamed(id) => Value {
+					value: ValueDef::Composite(Composite::Unnamed(vec![inner])),
+					context: id,
+				},
+			};
+		}
+
+		// The outermost value is given the ID of the compact type itself.
+		Ok(Value { context: type_id, ..inner })
+	}
+	fn visit_sequence(
+		self,
+		value: &mut visitor::Sequence<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, DecodeError> {
+		// The length comes from the encoded bytes, so we don't trust it enough to
+		// allocate space for that many values up front.
+		let mut values = Vec::new();
+		while let Some(val) = value.decode_item(ValueVisitor) {
+			values.push(val.map_err(|e| e.at(LocationSegment::Index(values.len())))?);
+		}
+		Ok(Value { value: ValueDef::Composite(Composite::Unnamed(values)), context: type_id })
+	}
+	fn visit_array(
+		self,
+		value: &mut visitor::Sequence<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, DecodeError> {
+		self.visit_sequence(value, type_id)
+	}
+	fn visit_tuple(
+		self,
+		value: &mut visitor::Composite<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, DecodeError> {
+		self.visit_composite(value, type_id)
+	}
+	fn visit_composite(
+		self,
+		value: &mut visitor::Composite<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, DecodeError> {
+		Ok(Value { value: ValueDef::Composite(decode_fields(value)?), context: type_id })
+	}
+	fn visit_variant(
+		self,
+		value: &mut visitor::Variant<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, DecodeError> {
+		let name = value.name().to_string();
+		let values = decode_fields(value.fields())
+			.map_err(|e| e.at(LocationSegment::Variant(name.clone())))?;
+		let variant = Variant { name, values };
+		Ok(Value { value: ValueDef::Variant(variant), context: type_id })
+	}
+	fn visit_bit_sequence(
+		self,
+		value: visitor::BitSequence<'_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, DecodeError> {
+		Ok(Value { value: ValueDef::BitSequence(value.decode()?), context: type_id })
+	}
 }
 
-fn decode_variant_value(
-	data: &mut &[u8],
-	ty: &TypeDefVariant<PortableForm>,
-	types: &PortableRegistry,
-) -> Result<Variant<TypeId>, DecodeError> {
-	let index = *data.get(0).ok_or(DecodeError::Eof)?;
-	*data = &data[1..];
-
-	// Does a variant exist with the index we're looking for?
-	let variant = ty
-		.variants()
-		.iter()
-		.find(|v| v.index() == index)
-		.ok_or_else(|| DecodeError::VariantNotFound(index, ty.clone()))?;
-
-	let fields = decode_fields(data, variant.fields(), types)?;
-	Ok(Variant { name: variant.name().clone(), values: fields })
+fn primitive_value(primitive: Primitive, type_id: TypeId) -> Value<TypeId> {
+	Value { value: ValueDef::Primitive(primitive), context: type_id }
 }
 
 /// Variant and Composite types both have fields; this will decode them into values.
 fn decode_fields(
-	data: &mut &[u8],
-	fields: &[Field<PortableForm>],
-	types: &PortableRegistry,
+	fields: &mut visitor::Composite<'_, '_>,
 ) -> Result<Composite<TypeId>, DecodeError> {
-	let are_named = fields.iter().any(|f| f.name().is_some());
-	let named_field_vals = fields.iter().map(|f| {
-		let name = f.name().cloned().unwrap_or_default();
-		decode_value_as_type(data, f.ty(), types).map(|val| (name, val))
-	});
-
-	if are_named {
-		let vals = named_field_vals.collect::<Result<_, _>>()?;
+	if fields.is_named() {
+		let mut vals = Vec::with_capacity(fields.len());
+		loop {
+			let name = fields.next_field_name().unwrap_or_default().to_string();
+			match fields.decode_item(ValueVisitor) {
+				Some(Ok(val)) => vals.push((name, val)),
+				Some(Err(e)) => return Err(e.at(LocationSegment::Field(name))),
+				None => break,
+			}
+		}
 		Ok(Composite::Named(vals))
 	} else {
-		let vals = named_field_vals.map(|r| r.map(|(_, v)| v)).collect::<Result<_, _>>()?;
-		Ok(Composite::Unnamed(vals))
-	}
-}
-
-fn decode_sequence_value(
-	data: &mut &[u8],
-	ty: &TypeDefSequence<PortableForm>,
-	types: &PortableRegistry,
-) -> Result<Composite<TypeId>, DecodeError> {
-	// We assume that the sequence is preceeded by a compact encoded length, so that
-	// we know how many values to try pulling out of the data.
-	let len = Compact::<u64>::decode(data)?;
-	let values: Vec<_> = (0..len.0)
-		.map(|_| decode_value_as_type(data, ty.type_param(), types))
-		.collect::<Result<_, _>>()?;
-
-	Ok(Composite::Unnamed(values))
-}
-
-fn decode_array_value(
-	data: &mut &[u8],
-	ty: &TypeDefArray<PortableForm>,
-	types: &PortableRegistry,
-) -> Result<Composite<TypeId>, DecodeError> {
-	// The length is known based on the type we want to decode into, so we pull out the number of items according
-	// to that, and don't need a length to exist in the SCALE encoded bytes
-	let values: Vec<_> = (0..ty.len())
-		.map(|_| decode_value_as_type(data, ty.type_param(), types))
-		.collect::<Result<_, _>>()?;
-
-	Ok(Composite::Unnamed(values))
-}
-
-fn decode_tuple_value(
-	data: &mut &[u8],
-	ty: &TypeDefTuple<PortableForm>,
-	types: &PortableRegistry,
-) -> Result<Composite<TypeId>, DecodeError> {
-	let values: Vec<_> = ty
-		.fields()
-		.iter()
-		.map(|f| decode_value_as_type(data, f, types))
-		.collect::<Result<_, _>>()?;
-
-	Ok(Composite::Unnamed(values))
-}
-
-fn decode_primitive_value(
-	data: &mut &[u8],
-	ty: &TypeDefPrimitive,
-) -> Result<Primitive, DecodeError> {
-	let val = match ty {
-		TypeDefPrimitive::Bool => Primitive::Bool(bool::decode(data)?),
-		TypeDefPrimitive::Char => {
-			// Treat chars as u32's
-			let val = u32::decode(data)?;
-			Primitive::Char(char::from_u32(val).ok_or(DecodeError::InvalidChar(val))?)
+		let mut vals = Vec::with_capacity(fields.len());
+		while let Some(val) = fields.decode_item(ValueVisitor) {
+			let segment = LocationSegment::unnamed_field(vals.len(), fields.len());
+			vals.push(val.map_err(|e| e.at(segment))?);
 		}
-		TypeDefPrimitive::Str => Primitive::String(String::decode(data)?),
-		TypeDefPrimitive::U8 => Primitive::uint(u8::decode(data)?),
-		TypeDefPrimitive::U16 => Primitive::uint(u16::decode(data)?),
-		TypeDefPrimitive::U32 => Primitive::uint(u32::decode(data)?),
-		TypeDefPrimitive::U64 => Primitive::uint(u64::decode(data)?),
-		TypeDefPrimitive::U128 => Primitive::uint(u128::decode(data)?),
-		TypeDefPrimitive::U256 => Primitive::U256(<[u8; 32]>::decode(data)?),
-		TypeDefPrimitive::I8 => Primitive::int(i8::decode(data)?),
-		TypeDefPrimitive::I16 => Primitive::int(i16::decode(data)?),
-		TypeDefPrimitive::I32 => Primitive::int(i32::decode(data)?),
-		TypeDefPrimitive::I64 => Primitive::int(i64::decode(data)?),
-		TypeDefPrimitive::I128 => Primitive::int(i128::decode(data)?),
-		TypeDefPrimitive::I256 => Primitive::I256(<[u8; 32]>::decode(data)?),
-	};
-	Ok(val)
-}
-
-fn decode_compact_value(
-	data: &mut &[u8],
-	ty: &TypeDefCompact<PortableForm>,
-	types: &PortableRegistry,
-) -> Result<ValueDef<TypeId>, DecodeError> {
-	fn decode_compact(
-		data: &mut &[u8],
-		inner: &Type,
-		types: &PortableRegistry,
-	) -> Result<ValueDef<TypeId>, DecodeError> {
-		use TypeDefPrimitive::*;
-		let val = match inner.type_def() {
-			// It's obvious how to decode basic primitive unsigned types, since we have impls for them.
-			TypeDef::Primitive(U8) => {
-				ValueDef::Primitive(Primitive::uint(Compact::<u8>::decode(data)?.0))
-			}
-			TypeDef::Primitive(U16) => {
-				ValueDef::Primitive(Primitive::uint(Compact::<u16>::decode(data)?.0))
-			}
-			TypeDef::Primitive(U32) => {
-				ValueDef::Primitive(Primitive::uint(Compact::<u32>::decode(data)?.0))
-			}
-			TypeDef::Primitive(U64) => {
-				ValueDef::Primitive(Primitive::uint(Compact::<u64>::decode(data)?.0))
-			}
-			TypeDef::Primitive(U128) => {
-				ValueDef::Primitive(Primitive::uint(Compact::<u128>::decode(data)?.0))
-			}
-			// A struct with exactly 1 field containing one of the above types can be sensibly compact encoded/decoded.
-			TypeDef::Composite(composite) => {
-				if composite.fields().len() != 1 {
-					return Err(DecodeError::CannotDecodeCompactIntoType(inner.clone()));
-				}
-
-				// What type is the 1 field that we are able to decode?
-				let field = &composite.fields()[0];
-				let field_type_id = field.ty().id();
-				let inner_ty = types
-					.resolve(field_type_id)
-					.ok_or(DecodeError::TypeIdNotFound(field_type_id))?;
-
-				// Decode this inner type via compact decoding. This can recurse, in case
-				// the inner type is also a 1-field composite type.
-				let inner_value = Value {
-					value: decode_compact(data, inner_ty, types)?,
-					context: field.ty().into(),
-				};
-
-				// Wrap the inner type in a representation of this outer composite type.
-				let composite = match field.name() {
-					Some(name) => Composite::Named(vec![(name.clone(), inner_value)]),
-					None => Composite::Unnamed(vec![inner_value]),
-				};
-
-				ValueDef::Composite(composite)
-			}
-			// For now, we give up if we have been asked for any other type:
-			_cannot_decode_from => {
-				return Err(DecodeError::CannotDecodeCompactIntoType(inner.clone()))
-			}
-		};
-
-		Ok(val)
+		Ok(Composite::Unnamed(vals))
 	}
-
-	// Pluck the inner type out and run it through our compact decoding logic.
-	let inner = types
-		.resolve(ty.type_param().id())
-		.ok_or_else(|| DecodeError::TypeIdNotFound(ty.type_param().id()))?;
-	decode_compact(data, inner, types)
-}
-
-fn decode_bit_sequence_value(
-	data: &mut &[u8],
-	ty: &TypeDefBitSequence<PortableForm>,
-	types: &PortableRegistry,
-) -> Result<BitSequence, DecodeError> {
-	let details = get_bitsequence_details(ty, types).map_err(DecodeError::BitSequenceError)?;
-
-	fn to_bit_sequence<S: BitStore, O: BitOrder>(bits: BitVec<S, O>) -> BitSequence {
-		bits.iter().by_vals().collect()
-	}
-
-	// Decode the native BitSequence type easily, or else convert to it from the type given.
-	let bits = match details {
-		(BitStoreTy::U8, BitOrderTy::Lsb0) => BitVec::<u8, Lsb0>::decode(data)?,
-		(BitStoreTy::U8, BitOrderTy::Msb0) => to_bit_sequence(BitVec::<u8, Msb0>::decode(data)?),
-		(BitStoreTy::U16, BitOrderTy::Lsb0) => to_bit_sequence(BitVec::<u16, Lsb0>::decode(data)?),
-		(BitStoreTy::U16, BitOrderTy::Msb0) => to_bit_sequence(BitVec::<u16, Msb0>::decode(data)?),
-		(BitStoreTy::U32, BitOrderTy::Lsb0) => to_bit_sequence(BitVec::<u32, Lsb0>::decode(data)?),
-		(BitStoreTy::U32, BitOrderTy::Msb0) => to_bit_sequence(BitVec::<u32, Msb0>::decode(data)?),
-		// BitVec doesn't impl BitStore on u64 if pointer width isn't 64 bit, avoid using this store type here
-		// in that case to avoid compile errors (see https://docs.rs/bitvec/1.0.0/src/bitvec/store.rs.html#184)
-		#[cfg(not(feature = "32bit_target"))]
-		(BitStoreTy::U64, BitOrderTy::Lsb0) => to_bit_sequence(BitVec::<u64, Lsb0>::decode(data)?),
-		#[cfg(not(feature = "32bit_target"))]
-		(BitStoreTy::U64, BitOrderTy::Msb0) => to_bit_sequence(BitVec::<u64, Msb0>::decode(data)?),
-		#[cfg(feature = "32bit_target")]
-		(BitStoreTy::U64, _) => {
-			return Err(DecodeError::BitSequenceError(BitSequenceError::StoreTypeNotSupported(
-				"u64 (pointer-width on this compile target is not 64)".into(),
-			)))
-		}
-	};
-
-	Ok(bits)
 }
 
 #[cfg(test)]
 mod test {
 
 	use super::*;
-	use codec::Encode;
+	use codec::{Compact, Encode};
 
 	/// Given a type definition, return the PortableType and PortableRegistry
 	/// that our decode functions expect.
@@ -479,6 +410,20 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn decode_sequence_with_oversized_length() {
+		// Huge compact encoded lengths with no items after them. These shouldn't
+		// lead to us trying to allocate space for that many items.
+		let (id, types) = make_type::<Vec<u8>>();
+		for encoded in [
+			[0x13, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0f],
+			[0x13, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+		] {
+			let err = decode_value_as_type(&mut &encoded[..], id, &types).unwrap_err();
+			assert!(matches!(err.kind, DecodeErrorKind::Eof | DecodeErrorKind::CodecError(_)));
+		}
+	}
+
 	#[test]
 	fn decode_variant_types() {
 		#[derive(Encode, scale_info::TypeInfo)]
@@ -584,4 +529,36 @@ mod test {
 			Value::bit_sequence(bitvec![u8, Lsb0; 0, 1, 1, 0, 1, 0]),
 		);
 	}
+
+	#[test]
+	fn decode_errors_report_their_location() {
+		#[allow(non_camel_case_types)]
+		#[derive(Encode, scale_info::TypeInfo)]
+		enum BalancesCall {
+			transfer { dest: (u8, u8), value: u128 },
+		}
+		#[derive(Encode, scale_info::TypeInfo)]
+		enum Call {
+			Balances(BalancesCall),
+		}
+
+		let call = || Call::Balances(BalancesCall::transfer { dest: (1, 2), value: 3 });
+		let encoded = vec![call(), call(), call()].encode();
+		let (id, types) = make_type::<Vec<Call>>();
+
+		// Cut off the end of the last value:
+		let data = &mut &encoded[..encoded.len() - 1];
+		let err = decode_value_as_type(data, id, &types).unwrap_err();
+		assert_eq!(err.location.to_string(), "[2].Balances.transfer.value");
+		assert!(err.to_string().starts_with("at [2].Balances.transfer.value: "));
+
+		// Give the middle call an unknown variant index:
+		let mut encoded = encoded;
+		let second_call_idx = encoded.len() - 2 * call().encode().len() + 1;
+		encoded[second_call_idx] = 9;
+		let err = decode_value_as_type(&mut &*encoded, id, &types).unwrap_err();
+		assert_eq!(err.location.to_string(), "[1].Balances");
+		assert!(matches!(err.kind, DecodeErrorKind::VariantNotFound(9, _)));
+		assert!(err.to_string().ends_with("Could not find variant with index 9 in scale_value::scale_impls::decode::test::BalancesCall"));
+	}
 }
@@ -15,7 +15,9 @@
 
 use super::{
 	bit_sequence::{get_bitsequence_details, BitOrderTy, BitSequenceError, BitStoreTy},
+	location::{Location, LocationSegment},
 	type_id::TypeId,
+	type_name::type_name,
 	ScaleTypeDef as TypeDef,
 };
 use crate::value::{Composite, Primitive, Value, ValueDef, Variant};
@@ -28,10 +30,47 @@ use scale_info::{
 	form::PortableForm, Field, PortableRegistry, TypeDefArray, TypeDefBitSequence, TypeDefCompact,
 	TypeDefComposite, TypeDefPrimitive, TypeDefSequence, TypeDefTuple, TypeDefVariant,
 };
+use std::fmt::Display;
+
+/// An error encoding a [`Value`] into SCALE bytes, along with the [`Location`]
+/// of the value that could not be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodeError<T> {
+	/// Where in the value the error occurred.
+	pub location: Location,
+	/// What went wrong.
+	pub kind: EncodeErrorKind<T>,
+}
+
+impl<T> EncodeError<T> {
+	/// Note that this error occurred inside of some outer value.
+	fn at(mut self, segment: impl Into<Option<LocationSegment>>) -> Self {
+		self.location.prepend(segment);
+		self
+	}
+}
+
+impl<T> From<EncodeErrorKind<T>> for EncodeError<T> {
+	fn from(kind: EncodeErrorKind<T>) -> Self {
+		EncodeError { location: Location::new(), kind }
+	}
+}
+
+impl<T: std::fmt::Debug> Display for EncodeError<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.location.is_empty() {
+			write!(f, "{}", self.kind)
+		} else {
+			write!(f, "at {}: {}", self.location, self.kind)
+		}
+	}
+}
 
-/// An error encoding a [`Value`] into SCALE bytes.
+impl<T: std::fmt::Debug> std::error::Error for EncodeError<T> {}
+
+/// The different reasons that encoding a [`Value`] into SCALE bytes can fail.
 #[derive(Debug, Clone, thiserror::Error, PartialEq)]
-pub enum EncodeError<T> {
+pub enum EncodeErrorKind<T> {
 	/// The composite type we're trying to encode is the wrong length for the type we're trying to encode it into.
 	#[error("Composite type is the wrong length; expected length is {expected_len}, but got {}", actual.len())]
 	CompositeIsWrongLength {
@@ -43,20 +82,24 @@ pub enum EncodeError<T> {
 		expected_len: usize,
 	},
 	/// The composite is expected to contain named or unnamed values to encode properly, and the opposite is true.
-	#[error("The composite {actual:?} is not the same shape as the type we're trying to encode to ({expected})")]
+	#[error("The composite {actual:?} is not the same shape as the type we're trying to encode to ({expected_name})")]
 	CompositeIsWrongShape {
 		/// The composite value that is the wrong shape.
 		actual: Composite<T>,
 		/// The type we're trying to encode it into.
 		expected: TypeId,
+		/// The name of the type we're trying to encode it into.
+		expected_name: String,
 	},
 	/// The variant we're trying to encode was not found in the type we're encoding into.
-	#[error("Variant {} was not found", actual.name)]
+	#[error("Variant {} was not found in {expected_name}", actual.name)]
 	VariantNotFound {
 		/// The variant type we're trying to encode.
 		actual: Variant<T>,
 		/// The type we're trying to encode it into.
 		expected: TypeId,
+		/// The name of the type we're trying to encode it into.
+		expected_name: String,
 	},
 	/// The variant or composite field we're trying to encode is not present in the type we're encoding into.
 	#[error("The field {missing_field_name} is present on the type we're trying to encode to but hasn't been provided")]
@@ -70,19 +113,21 @@ pub enum EncodeError<T> {
 	#[error("Cannot find type with ID {0}")]
 	TypeIdNotFound(TypeId),
 	/// The [`Value`] type we're trying to encode is not the correct shape for the type we're trying to encode it into.
-	#[error("Value shape is wrong; expected type ID {expected}, but got value {actual:?}, which could not be coerced into it")]
+	#[error("Value shape is wrong; expected {expected_name}, but got value {actual:?}, which could not be coerced into it")]
 	WrongShape {
 		/// The value we're trying to encode.
 		actual: Value<T>,
 		/// The type we're trying to encode it into.
 		expected: TypeId,
+		/// The name of the type we're trying to encode it into.
+		expected_name: String,
 	},
 	/// There was an error trying to encode the bit sequence provided.
 	#[error("Cannot encode bit sequence: {0}")]
 	BitSequenceError(BitSequenceError),
 	/// The type ID given is supposed to be compact encoded, but this is not possible to do automatically.
-	#[error("The type {0} cannot be compact encoded")]
-	CannotCompactEncode(TypeId),
+	#[error("The type {1} cannot be compact encoded")]
+	CannotCompactEncode(TypeId, String),
 }
 
 /// Attempt to SCALE Encode a Value according to the [`TypeId`] and
@@ -94,15 +139,15 @@ pub fn encode_value_as_type<T, Id: Into<TypeId>>(
 	bytes: &mut Vec<u8>,
 ) -> Result<(), EncodeError<T>> {
 	let ty_id = ty_id.into();
-	let ty = types.resolve(ty_id.id()).ok_or(EncodeError::TypeIdNotFound(ty_id))?;
+	let ty = types.resolve(ty_id.id()).ok_or(EncodeErrorKind::TypeIdNotFound(ty_id))?;
 
-	match ty.type_def() {
+	match &ty.type_def {
 		TypeDef::Composite(inner) => encode_composite_value(value, ty_id, inner, types, bytes),
 		TypeDef::Sequence(inner) => encode_sequence_value(value, ty_id, inner, types, bytes),
 		TypeDef::Array(inner) => encode_array_value(value, ty_id, inner, types, bytes),
 		TypeDef::Tuple(inner) => encode_tuple_value(value, ty_id, inner, types, bytes),
 		TypeDef::Variant(inner) => encode_variant_value(value, ty_id, inner, types, bytes),
-		TypeDef::Primitive(inner) => encode_primitive_value(value, ty_id, inner, bytes),
+		TypeDef::Primitive(inner) => encode_primitive_value(value, ty_id, inner, types, bytes),
 		TypeDef::Compact(inner) => encode_compact_value(value, ty_id, inner, types, bytes),
 		TypeDef::BitSequence(inner) => encode_bitsequence_value(value, ty_id, inner, types, bytes),
 	}?;
@@ -119,14 +164,14 @@ fn encode_composite_value<T>(
 ) -> Result<(), EncodeError<T>> {
 	match value.value {
 		ValueDef::Composite(composite) => {
-			encode_composite_fields(composite, ty.fields(), type_id, types, bytes)
+			encode_composite_fields(composite, &ty.fields, type_id, types, bytes)
 		}
 		_ => {
-			if ty.fields().len() == 1 {
+			if ty.fields.len() == 1 {
 				// A 1-field composite type? try encoding inner content then.
-				encode_value_as_type(value, ty.fields()[0].ty(), types, bytes)
+				encode_value_as_type(value, ty.fields[0].ty, types, bytes)
 			} else {
-				Err(EncodeError::WrongShape { actual: value, expected: type_id })
+				Err(wrong_shape(value, type_id, types).into())
 			}
 		}
 	}
@@ -145,24 +190,26 @@ fn encode_sequence_value<T>(
 		ValueDef::Composite(c) => {
 			// Compact encoded length comes first
 			Compact(c.len() as u64).encode_to(bytes);
-			let ty = ty.type_param();
-			for value in c.into_values() {
-				encode_value_as_type(value, ty, types, bytes)?;
+			let ty = ty.type_param;
+			for (idx, value) in c.into_values().enumerate() {
+				encode_value_as_type(value, ty, types, bytes)
+					.map_err(|e| e.at(LocationSegment::Index(idx)))?;
 			}
 		}
-		// As a special case, primitive U256/I256s are arrays, and may be compatible
+		// As a special case, some primitives stand for bytes, and may be compatible
 		// with the sequence type being asked for, too.
-		ValueDef::Primitive(Primitive::I256(a) | Primitive::U256(a)) => {
+		ValueDef::Primitive(ref p) if primitive_as_bytes(p).is_some() => {
+			let a = primitive_as_bytes(p).expect("checked above");
 			// Compact encoded length comes first
 			Compact(a.len() as u64).encode_to(bytes);
-			let ty = ty.type_param();
+			let ty = ty.type_param;
 			for val in a {
 				if encode_value_as_type(Value::uint(val), ty, types, bytes).is_err() {
-					return Err(EncodeError::WrongShape { actual: value, expected: type_id });
+					return Err(wrong_shape(value, type_id, types).into());
 				}
 			}
 		}
-		_ => return Err(EncodeError::WrongShape { actual: value, expected: type_id }),
+		_ => return Err(wrong_shape(value, type_id, types).into()),
 	};
 	Ok(())
 }
@@ -178,40 +225,59 @@ fn encode_array_value<T>(
 		// Let's see whether our composite type is the right length,
 		// and try to encode each inner value into what the array wants.
 		ValueDef::Composite(c) => {
-			let arr_len = ty.len() as usize;
+			let arr_len = ty.len as usize;
 			if c.len() != arr_len {
-				return Err(EncodeError::CompositeIsWrongLength {
+				return Err(EncodeErrorKind::CompositeIsWrongLength {
 					actual: c,
 					expected: type_id,
 					expected_len: arr_len,
-				});
+				}
+				.into());
 			}
 
-			let ty = ty.type_param();
-			for value in c.into_values() {
-				encode_value_as_type(value, ty, types, bytes)?;
+			let ty = ty.type_param;
+			for (idx, value) in c.into_values().enumerate() {
+				encode_value_as_type(value, ty, types, bytes)
+					.map_err(|e| e.at(LocationSegment::Index(idx)))?;
 			}
 		}
-		// As a special case, primitive U256/I256s are arrays, and may be compatible
-		// with the array type being asked for, too.
-		ValueDef::Primitive(Primitive::I256(a) | Primitive::U256(a)) => {
-			let arr_len = ty.len() as usize;
-			if a.len() != arr_len {
-				return Err(EncodeError::WrongShape { actual: value, expected: type_id });
+		// As a special case, some primitives stand for bytes, and may be compatible
+		// with the array type being asked for, too. Numbers are padded with leading
+		// zeros to fill the array.
+		ValueDef::Primitive(ref p) if primitive_as_bytes(p).is_some() => {
+			let a = primitive_as_bytes(p).expect("checked above");
+			let arr_len = ty.len as usize;
+			if a.len() > arr_len {
+				return Err(wrong_shape(value, type_id, types).into());
 			}
 
-			let ty = ty.type_param();
-			for val in a {
+			let ty = ty.type_param;
+			let padding = std::iter::repeat_n(0, arr_len - a.len());
+			for val in padding.chain(a) {
 				if encode_value_as_type(Value::uint(val), ty, types, bytes).is_err() {
-					return Err(EncodeError::WrongShape { actual: value, expected: type_id });
+					return Err(wrong_shape(value, type_id, types).into());
 				}
 			}
 		}
-		_ => return Err(EncodeError::WrongShape { actual: value, expected: type_id }),
+		_ => return Err(wrong_shape(value, type_id, types).into()),
 	};
 	Ok(())
 }
 
+/// The bytes that a primitive stands for when it's encoded into a sequence or array. Unsigned
+/// numbers, like `0xdeadbeef`, are written as their big endian bytes without any leading
+/// zeros, and I256s are arrays of bytes already.
+pub(crate) fn primitive_as_bytes(p: &Primitive) -> Option<Vec<u8>> {
+	let be_bytes = match p {
+		Primitive::U128(n) => n.to_be_bytes().to_vec(),
+		Primitive::U256(n) => n.iter().rev().copied().collect(),
+		Primitive::I256(a) => return Some(a.to_vec()),
+		_ => return None,
+	};
+	let zeros = be_bytes.iter().take_while(|&&b| b == 0).count().min(be_bytes.len() - 1);
+	Some(be_bytes[zeros..].to_vec())
+}
+
 fn encode_tuple_value<T>(
 	value: Value<T>,
 	type_id: TypeId,
@@ -221,27 +287,30 @@ fn encode_tuple_value<T>(
 ) -> Result<(), EncodeError<T>> {
 	match value.value {
 		ValueDef::Composite(composite) => {
-			if composite.len() != ty.fields().len() {
-				return Err(EncodeError::CompositeIsWrongLength {
+			if composite.len() != ty.fields.len() {
+				return Err(EncodeErrorKind::CompositeIsWrongLength {
 					actual: composite,
 					expected: type_id,
-					expected_len: ty.fields().len(),
-				});
+					expected_len: ty.fields.len(),
+				}
+				.into());
 			}
 			// We don't care whether the fields are named or unnamed
 			// as long as we have the number of them that we expect..
-			let field_value_pairs = ty.fields().iter().zip(composite.into_values());
-			for (ty, value) in field_value_pairs {
-				encode_value_as_type(value, ty, types, bytes)?;
+			let len = ty.fields.len();
+			let field_value_pairs = ty.fields.iter().zip(composite.into_values());
+			for (idx, (ty, value)) in field_value_pairs.enumerate() {
+				encode_value_as_type(value, ty, types, bytes)
+					.map_err(|e| e.at(LocationSegment::unnamed_field(idx, len)))?;
 			}
 			Ok(())
 		}
 		_ => {
-			if ty.fields().len() == 1 {
+			if ty.fields.len() == 1 {
 				// A 1-field tuple? try encoding inner content then.
-				encode_value_as_type(value, ty.fields()[0], types, bytes)
+				encode_value_as_type(value, ty.fields[0], types, bytes)
 			} else {
-				Err(EncodeError::WrongShape { actual: value, expected: type_id })
+				Err(wrong_shape(value, type_id, types).into())
 			}
 		}
 	}
@@ -256,18 +325,26 @@ fn encode_variant_value<T>(
 ) -> Result<(), EncodeError<T>> {
 	let variant = match value.value {
 		ValueDef::Variant(variant) => variant,
-		_ => return Err(EncodeError::WrongShape { actual: value, expected: type_id }),
+		_ => return Err(wrong_shape(value, type_id, types).into()),
 	};
 
-	let variant_type = ty.variants().iter().find(|v| v.name() == &variant.name);
+	let variant_type = ty.variants.iter().find(|v| v.name == variant.name);
 
 	let variant_type = match variant_type {
-		None => return Err(EncodeError::VariantNotFound { actual: variant, expected: type_id }),
+		None => {
+			return Err(EncodeErrorKind::VariantNotFound {
+				actual: variant,
+				expected: type_id,
+				expected_name: type_name(type_id, types),
+			}
+			.into())
+		}
 		Some(v) => v,
 	};
 
-	variant_type.index().encode_to(bytes);
-	encode_composite_fields(variant.values, variant_type.fields(), type_id, types, bytes)
+	variant_type.index.encode_to(bytes);
+	encode_composite_fields(variant.values, &variant_type.fields, type_id, types, bytes)
+		.map_err(|e| e.at(LocationSegment::Variant(variant_type.name.clone())))
 }
 
 fn encode_composite_fields<T>(
@@ -278,11 +355,12 @@ fn encode_composite_fields<T>(
 	bytes: &mut Vec<u8>,
 ) -> Result<(), EncodeError<T>> {
 	if fields.len() != composite.len() {
-		return Err(EncodeError::CompositeIsWrongLength {
+		return Err(EncodeErrorKind::CompositeIsWrongLength {
 			actual: composite,
 			expected: type_id,
 			expected_len: fields.len(),
-		});
+		}
+		.into());
 	}
 
 	// 0 length? Nothing more to do!
@@ -291,13 +369,13 @@ fn encode_composite_fields<T>(
 	}
 
 	// Does the type we're encoding to have named fields or not?
-	let is_named = fields[0].name().is_some();
+	let is_named = fields[0].name.is_some();
 
 	match (composite, is_named) {
 		(Composite::Named(mut values), true) => {
 			// Match up named values with those of the type we're encoding to.
 			for field in fields.iter() {
-				let field_name = field.name().expect("field should be named; checked above");
+				let field_name = field.name.as_ref().expect("field should be named; checked above");
 				let value = values
 					.iter()
 					.position(|(n, _)| field_name == n)
@@ -305,13 +383,15 @@ fn encode_composite_fields<T>(
 
 				match value {
 					Some(value) => {
-						encode_value_as_type(value, field.ty(), types, bytes)?;
+						encode_value_as_type(value, field.ty, types, bytes)
+							.map_err(|e| e.at(LocationSegment::Field(field_name.clone())))?;
 					}
 					None => {
-						return Err(EncodeError::CompositeFieldIsMissing {
+						return Err(EncodeErrorKind::CompositeFieldIsMissing {
 							expected: type_id,
 							missing_field_name: field_name.clone(),
-						})
+						}
+						.into())
 					}
 				}
 			}
@@ -320,28 +400,47 @@ fn encode_composite_fields<T>(
 		}
 		(Composite::Unnamed(values), false) => {
 			// Expect values in correct order only and encode.
-			for (field, value) in fields.iter().zip(values) {
-				encode_value_as_type(value, field.ty(), types, bytes)?;
+			for (idx, (field, value)) in fields.iter().zip(values).enumerate() {
+				encode_value_as_type(value, field.ty, types, bytes)
+					.map_err(|e| e.at(LocationSegment::unnamed_field(idx, fields.len())))?;
 			}
 			Ok(())
 		}
 		(values, _) => {
 			// We expect named/unnamed fields and need the opposite.
-			Err(EncodeError::CompositeIsWrongShape { actual: values, expected: type_id })
+			Err(EncodeErrorKind::CompositeIsWrongShape {
+				actual: values,
+				expected: type_id,
+				expected_name: type_name(type_id, types),
+			}
+			.into())
 		}
 	}
 }
 
+fn wrong_shape<T>(
+	actual: Value<T>,
+	expected: TypeId,
+	types: &PortableRegistry,
+) -> EncodeErrorKind<T> {
+	EncodeErrorKind::WrongShape { actual, expected, expected_name: type_name(expected, types) }
+}
+
+fn cannot_compact_encode<T>(ty_id: u32, types: &PortableRegistry) -> EncodeErrorKind<T> {
+	EncodeErrorKind::CannotCompactEncode(ty_id.into(), type_name(ty_id, types))
+}
+
 // Attempt to convert a given primitive value into the integer type
 // required, failing with an appropriate EncodeValueError if not successful.
 macro_rules! primitive_to_integer {
-	($id:ident, $prim:ident, $context:expr => $ty:ident) => {{
+	($id:ident, $types:ident, $prim:ident, $context:expr => $ty:ident) => {{
 		macro_rules! err {
 			() => {
-				EncodeError::WrongShape {
-					actual: Value { context: $context, value: ValueDef::Primitive($prim) },
-					expected: $id,
-				}
+				wrong_shape(
+					Value { context: $context, value: ValueDef::Primitive($prim) },
+					$id,
+					$types,
+				)
 			};
 		}
 		let out: Result<$ty, _> = match $prim {
@@ -359,11 +458,12 @@ fn encode_primitive_value<T>(
 	value: Value<T>,
 	type_id: TypeId,
 	ty: &TypeDefPrimitive,
+	types: &PortableRegistry,
 	bytes: &mut Vec<u8>,
 ) -> Result<(), EncodeError<T>> {
 	let primitive = match value.value {
 		ValueDef::Primitive(primitive) => primitive,
-		_ => return Err(EncodeError::WrongShape { actual: value, expected: type_id }),
+		_ => return Err(wrong_shape(value, type_id, types).into()),
 	};
 
 	// Attempt to encode our value type into the expected shape.
@@ -385,41 +485,47 @@ fn encode_primitive_value<T>(
 			a.encode_to(bytes);
 		}
 		(TypeDefPrimitive::U8, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => u8)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => u8)?.encode_to(bytes);
 		}
 		(TypeDefPrimitive::U16, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => u16)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => u16)?
+				.encode_to(bytes);
 		}
 		(TypeDefPrimitive::U32, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => u32)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => u32)?
+				.encode_to(bytes);
 		}
 		(TypeDefPrimitive::U64, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => u64)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => u64)?
+				.encode_to(bytes);
 		}
 		(TypeDefPrimitive::U128, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => u128)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => u128)?
+				.encode_to(bytes);
 		}
 		(TypeDefPrimitive::I8, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => i8)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => i8)?.encode_to(bytes);
 		}
 		(TypeDefPrimitive::I16, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => i16)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => i16)?
+				.encode_to(bytes);
 		}
 		(TypeDefPrimitive::I32, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => i32)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => i32)?
+				.encode_to(bytes);
 		}
 		(TypeDefPrimitive::I64, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => i64)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => i64)?
+				.encode_to(bytes);
 		}
 		(TypeDefPrimitive::I128, primitive) => {
-			primitive_to_integer!(type_id, primitive, value.context => i128)?.encode_to(bytes);
+			primitive_to_integer!(type_id, types, primitive, value.context => i128)?
+				.encode_to(bytes);
 		}
 		(_, primitive) => {
-			return Err(EncodeError::WrongShape {
-				// Reconstruct a Value to give back:
-				actual: Value { context: value.context, value: ValueDef::Primitive(primitive) },
-				expected: type_id,
-			});
+			// Reconstruct a Value to give back:
+			let actual = Value { context: value.context, value: ValueDef::Primitive(primitive) };
+			return Err(wrong_shape(actual, type_id, types).into());
 		}
 	}
 	Ok(())
@@ -443,26 +549,26 @@ fn encode_compact_value<T>(
 
 	// Resolve to a primitive type inside the compact encoded type (or fail if
 	// we hit some type we wouldn't know how to work with).
-	let mut inner_ty_id = ty.type_param().id();
+	let mut inner_ty_id = ty.type_param.id;
 	let inner_ty = loop {
-		let inner_ty = types
+		let inner_ty = &types
 			.resolve(inner_ty_id)
-			.ok_or_else(|| EncodeError::TypeIdNotFound(inner_ty_id.into()))?
-			.type_def();
+			.ok_or_else(|| EncodeErrorKind::TypeIdNotFound(inner_ty_id.into()))?
+			.type_def;
 
 		match inner_ty {
 			TypeDef::Composite(c) => {
-				if c.fields().len() == 1 {
-					inner_ty_id = c.fields()[0].ty().id();
+				if c.fields.len() == 1 {
+					inner_ty_id = c.fields[0].ty.id;
 				} else {
-					return Err(EncodeError::CannotCompactEncode(inner_ty_id.into()));
+					return Err(cannot_compact_encode(inner_ty_id, types).into());
 				}
 			}
 			TypeDef::Tuple(t) => {
-				if t.fields().len() == 1 {
-					inner_ty_id = t.fields()[0].id();
+				if t.fields.len() == 1 {
+					inner_ty_id = t.fields[0].id;
 				} else {
-					return Err(EncodeError::CannotCompactEncode(inner_ty_id.into()));
+					return Err(cannot_compact_encode(inner_ty_id, types).into());
 				}
 			}
 			TypeDef::Primitive(primitive) => {
@@ -473,14 +579,14 @@ fn encode_compact_value<T>(
 					TypeDefPrimitive::U32 => CompactTy::U32,
 					TypeDefPrimitive::U64 => CompactTy::U64,
 					TypeDefPrimitive::U128 => CompactTy::U128,
-					_ => return Err(EncodeError::CannotCompactEncode(inner_ty_id.into())),
+					_ => return Err(cannot_compact_encode(inner_ty_id, types).into()),
 				};
 			}
 			TypeDef::Variant(_)
 			| TypeDef::Sequence(_)
 			| TypeDef::Array(_)
 			| TypeDef::Compact(_)
-			| TypeDef::BitSequence(_) => return Err(EncodeError::CannotCompactEncode(inner_ty_id.into())),
+			| TypeDef::BitSequence(_) => return Err(cannot_compact_encode(inner_ty_id, types).into()),
 		}
 	};
 
@@ -494,18 +600,14 @@ fn encode_compact_value<T>(
 					if c.len() == 1 {
 						value = c.into_values().next().expect("length of 1; value should exist");
 					} else {
-						return Err(EncodeError::WrongShape {
-							actual: Value { context: value.context, value: ValueDef::Composite(c) },
-							expected: inner_ty_id.into(),
-						});
+						let actual =
+							Value { context: value.context, value: ValueDef::Composite(c) };
+						return Err(wrong_shape(actual, inner_ty_id.into(), types).into());
 					}
 				}
 				ValueDef::Primitive(primitive) => break primitive,
 				ValueDef::Variant(_) | ValueDef::BitSequence(_) => {
-					return Err(EncodeError::WrongShape {
-						actual: value,
-						expected: inner_ty_id.into(),
-					})
+					return Err(wrong_shape(value, inner_ty_id.into(), types).into())
 				}
 			}
 		}
@@ -514,23 +616,24 @@ fn encode_compact_value<T>(
 	// Try to compact encode the primitive type we have into the type asked for:
 	match inner_ty {
 		CompactTy::U8 => {
-			let val = primitive_to_integer!(type_id, inner_primitive, value.context => u8)?;
+			let val = primitive_to_integer!(type_id, types, inner_primitive, value.context => u8)?;
 			Compact(val).encode_to(bytes);
 		}
 		CompactTy::U16 => {
-			let val = primitive_to_integer!(type_id, inner_primitive, value.context => u16)?;
+			let val = primitive_to_integer!(type_id, types, inner_primitive, value.context => u16)?;
 			Compact(val).encode_to(bytes);
 		}
 		CompactTy::U32 => {
-			let val = primitive_to_integer!(type_id, inner_primitive, value.context => u32)?;
+			let val = primitive_to_integer!(type_id, types, inner_primitive, value.context => u32)?;
 			Compact(val).encode_to(bytes);
 		}
 		CompactTy::U64 => {
-			let val = primitive_to_integer!(type_id, inner_primitive, value.context => u64)?;
+			let val = primitive_to_integer!(type_id, types, inner_primitive, value.context => u64)?;
 			Compact(val).encode_to(bytes);
 		}
 		CompactTy::U128 => {
-			let val = primitive_to_integer!(type_id, inner_primitive, value.context => u128)?;
+			let val =
+				primitive_to_integer!(type_id, types, inner_primitive, value.context => u128)?;
 			Compact(val).encode_to(bytes);
 		}
 	};
@@ -553,16 +656,16 @@ fn encode_bitsequence_value<T>(
 			for val in vals {
 				match val.value {
 					ValueDef::Primitive(Primitive::Bool(b)) => bools.push(b),
-					_ => return Err(EncodeError::WrongShape { actual: val, expected: type_id }),
+					_ => return Err(wrong_shape(val, type_id, types).into()),
 				}
 			}
 			bools
 		}
-		_ => return Err(EncodeError::WrongShape { actual: value, expected: type_id }),
+		_ => return Err(wrong_shape(value, type_id, types).into()),
 	};
 
 	// next, turn those bools into a bit sequence of the expected shape.
-	match get_bitsequence_details(ty, types).map_err(EncodeError::BitSequenceError)? {
+	match get_bitsequence_details(ty, types).map_err(EncodeErrorKind::BitSequenceError)? {
 		(BitStoreTy::U8, BitOrderTy::Lsb0) => {
 			bools.into_iter().collect::<BitVec<u8, Lsb0>>().encode_to(bytes);
 		}
@@ -664,6 +767,28 @@ mod test {
 		assert_can_encode_to_type(Value::primitive(Primitive::I256([12u8; 32])), vec![12u8; 32]);
 	}
 
+	#[test]
+	fn can_encode_unsigned_numbers_to_big_endian_bytes() {
+		use crate::Primitive;
+
+		assert_can_encode_to_type(Value::uint(0xdeadbeefu32), [0xdeu8, 0xad, 0xbe, 0xef]);
+		assert_can_encode_to_type(Value::uint(0xbeefu16), [0u8, 0, 0xbe, 0xef]);
+		assert_can_encode_to_type(Value::uint(0xbeefu16), vec![0xbeu8, 0xef]);
+		assert_can_encode_to_type(Value::uint(0u8), vec![0u8]);
+
+		// U256s are little endian, so the bytes come out reversed:
+		let le: [u8; 32] = core::array::from_fn(|i| i as u8);
+		let mut be = le;
+		be.reverse();
+		assert_can_encode_to_type(Value::primitive(Primitive::U256(le)), be);
+		assert_can_encode_to_type(Value::primitive(Primitive::U256(le)), be.to_vec());
+
+		// Numbers that don't fit don't encode:
+		let mut bytes = Vec::new();
+		let (id, types) = make_type::<[u8; 2]>();
+		assert!(encode_value_as_type(Value::uint(0x10000u32), id, &types, &mut bytes).is_err());
+	}
+
 	#[test]
 	fn can_encode_arrays() {
 		let value = Value::unnamed_composite(vec![
@@ -784,4 +909,57 @@ mod test {
 			Compact(123u64),
 		);
 	}
+
+	#[test]
+	fn encode_errors_report_their_location() {
+		#[allow(dead_code, non_camel_case_types)]
+		#[derive(scale_info::TypeInfo)]
+		enum BalancesCall {
+			transfer { dest: (u8, u8), value: u128 },
+		}
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		enum Call {
+			Balances(BalancesCall),
+		}
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		struct Batch {
+			calls: Vec<Call>,
+		}
+
+		let call = |dest: Value<()>, value: Value<()>| {
+			Value::unnamed_variant(
+				"Balances",
+				vec![Value::named_variant(
+					"transfer",
+					vec![("dest".into(), dest), ("value".into(), value)],
+				)],
+			)
+		};
+		let dest = || Value::unnamed_composite(vec![Value::uint(1u8), Value::uint(2u8)]);
+		let (ty_id, types) = make_type::<Batch>();
+
+		let value = Value::named_composite(vec![(
+			"calls".into(),
+			Value::unnamed_composite(vec![
+				call(dest(), Value::uint(1u8)),
+				call(dest(), Value::uint(2u8)),
+				call(dest(), Value::string("three")),
+			]),
+		)]);
+		let err = encode_value_as_type(value, ty_id, &types, &mut Vec::new()).unwrap_err();
+		assert_eq!(err.location.to_string(), "calls[2].Balances.transfer.value");
+		assert!(err.to_string().starts_with(
+			"at calls[2].Balances.transfer.value: Value shape is wrong; expected u128,"
+		));
+
+		let bad_dest = Value::unnamed_composite(vec![Value::uint(1u8), Value::uint(256u16)]);
+		let value = Value::named_composite(vec![(
+			"calls".into(),
+			Value::unnamed_composite(vec![call(bad_dest, Value::uint(1u8))]),
+		)]);
+		let err = encode_value_as_type(value, ty_id, &types, &mut Vec::new()).unwrap_err();
+		assert_eq!(err.location.to_string(), "calls[0].Balances.transfer.dest[1]");
+	}
 }
@@ -0,0 +1,264 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{type_id::TypeId, ScaleTypeDef as TypeDef};
+use crate::value::{BitSequence, Composite, Primitive, Value, ValueDef, Variant};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDefPrimitive};
+
+/// Build an example [`Value`] for the type given, which can be encoded back into that type.
+/// Numbers are zero, strings, sequences and bit sequences are empty, arrays are filled with
+/// example values and enums take the first variant that doesn't lead back to a type that
+/// we're already building.
+///
+/// If no finite value can be built for the type (for instance, a struct which contains
+/// itself), or the type cannot be found, an empty composite is returned.
+pub fn example_value<Id: Into<TypeId>>(ty_id: Id, types: &PortableRegistry) -> Value<TypeId> {
+	let ty_id = ty_id.into();
+	let mut building = Vec::new();
+	example(ty_id, types, &mut building)
+		.unwrap_or(Value { value: ValueDef::Composite(Composite::Unnamed(vec![])), context: ty_id })
+}
+
+/// Build an example value, or return `None` if the only way to do so is to build one of the types
+/// that we're already in the middle of building (which would never end).
+fn example(
+	ty_id: TypeId,
+	types: &PortableRegistry,
+	building: &mut Vec<u32>,
+) -> Option<Value<TypeId>> {
+	if building.contains(&ty_id.id()) {
+		return None;
+	}
+	let ty = types.resolve(ty_id.id())?;
+
+	building.push(ty_id.id());
+	let value = match &ty.type_def {
+		TypeDef::Composite(inner) => {
+			example_fields(&inner.fields, types, building).map(ValueDef::Composite)
+		}
+		TypeDef::Variant(inner) => inner.variants.iter().find_map(|variant| {
+			let values = example_fields(&variant.fields, types, building)?;
+			Some(ValueDef::Variant(Variant { name: variant.name.clone(), values }))
+		}),
+		TypeDef::Sequence(_) => Some(ValueDef::Composite(Composite::Unnamed(vec![]))),
+		TypeDef::Array(inner) => {
+			let values = match inner.len {
+				0 => vec![],
+				len => vec![example(inner.type_param.into(), types, building)?; len as usize],
+			};
+			Some(ValueDef::Composite(Composite::Unnamed(values)))
+		}
+		TypeDef::Tuple(inner) => inner
+			.fields
+			.iter()
+			.map(|id| example(id.into(), types, building))
+			.collect::<Option<_>>()
+			.map(|values| ValueDef::Composite(Composite::Unnamed(values))),
+		TypeDef::Primitive(inner) => Some(ValueDef::Primitive(example_primitive(inner))),
+		// Compact values look like the type inside them.
+		TypeDef::Compact(inner) => {
+			example(inner.type_param.into(), types, building).map(|value| value.value)
+		}
+		TypeDef::BitSequence(_) => Some(ValueDef::BitSequence(BitSequence::new())),
+	};
+	building.pop();
+
+	value.map(|value| Value { value, context: ty_id })
+}
+
+fn example_fields(
+	fields: &[Field<PortableForm>],
+	types: &PortableRegistry,
+	building: &mut Vec<u32>,
+) -> Option<Composite<TypeId>> {
+	let is_named = fields.first().map(|f| f.name.is_some()).unwrap_or(false);
+	if is_named {
+		fields
+			.iter()
+			.map(|field| {
+				let name = field.name.clone().unwrap_or_default();
+				Some((name, example(field.ty.into(), types, building)?))
+			})
+			.collect::<Option<_>>()
+			.map(Composite::Named)
+	} else {
+		fields
+			.iter()
+			.map(|field| example(field.ty.into(), types, building))
+			.collect::<Option<_>>()
+			.map(Composite::Unnamed)
+	}
+}
+
+fn example_primitive(ty: &TypeDefPrimitive) -> Primitive {
+	match ty {
+		TypeDefPrimitive::Bool => Primitive::Bool(false),
+		TypeDefPrimitive::Char => Primitive::Char('\0'),
+		TypeDefPrimitive::Str => Primitive::String(String::new()),
+		TypeDefPrimitive::U8
+		| TypeDefPrimitive::U16
+		| TypeDefPrimitive::U32
+		| TypeDefPrimitive::U64
+		| TypeDefPrimitive::U128 => Primitive::U128(0),
+		TypeDefPrimitive::I8
+		| TypeDefPrimitive::I16
+		| TypeDefPrimitive::I32
+		| TypeDefPrimitive::I64
+		| TypeDefPrimitive::I128 => Primitive::I128(0),
+		TypeDefPrimitive::U256 => Primitive::U256([0; 32]),
+		TypeDefPrimitive::I256 => Primitive::I256([0; 32]),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::scale_impls::{decode_value_as_type, encode_value_as_type};
+	use codec::Encode;
+
+	/// Given a type definition, return the PortableType and PortableRegistry
+	/// that our example functions expect.
+	fn make_type<T: scale_info::TypeInfo + 'static>() -> (TypeId, PortableRegistry) {
+		let m = scale_info::MetaType::new::<T>();
+		let mut types = scale_info::Registry::new();
+		let id = types.register_type(&m);
+		let portable_registry: PortableRegistry = types.into();
+
+		(id.into(), portable_registry)
+	}
+
+	/// Build an example value for the type given, check that it encodes and then
+	/// decodes back to the same thing, and hand it back without context.
+	fn example_of<T: scale_info::TypeInfo + 'static>() -> Value<()> {
+		let (id, types) = make_type::<T>();
+		let value = example_value(id, &types);
+
+		let mut bytes = Vec::new();
+		encode_value_as_type(value.clone(), id, &types, &mut bytes)
+			.expect("example value should encode");
+		let decoded = decode_value_as_type(&mut &*bytes, id, &types).expect("can decode");
+		assert_eq!(value, decoded, "example value should round trip");
+
+		value.remove_context()
+	}
+
+	#[test]
+	fn example_primitives() {
+		assert_eq!(example_of::<bool>(), Value::bool(false));
+		assert_eq!(example_of::<String>(), Value::string(""));
+		assert_eq!(example_of::<u32>(), Value::uint(0u8));
+		assert_eq!(example_of::<i64>(), Value::int(0));
+		assert_eq!(example_of::<codec::Compact<u64>>(), Value::uint(0u8));
+		assert_eq!(example_of::<char>(), Value::char('\0'));
+	}
+
+	#[test]
+	fn example_collections() {
+		assert_eq!(example_of::<Vec<u8>>(), Value::unnamed_composite(vec![]));
+		assert_eq!(
+			example_of::<[bool; 3]>(),
+			Value::unnamed_composite(vec![Value::bool(false); 3])
+		);
+		assert_eq!(
+			example_of::<(u8, String)>(),
+			Value::unnamed_composite(vec![Value::uint(0u8), Value::string("")])
+		);
+		assert_eq!(
+			example_of::<bitvec::vec::BitVec<u8, bitvec::order::Lsb0>>(),
+			Value::bit_sequence(BitSequence::new())
+		);
+	}
+
+	#[test]
+	fn example_composites_and_variants() {
+		#[allow(dead_code)]
+		#[derive(Encode, scale_info::TypeInfo)]
+		enum MyEnum {
+			Foo { a: u8, b: (bool, [u16; 2]) },
+			Bar(String),
+		}
+		#[derive(Encode, scale_info::TypeInfo)]
+		struct MyStruct {
+			e: MyEnum,
+			maybe: Option<u64>,
+			#[codec(compact)]
+			num: u32,
+		}
+
+		assert_eq!(
+			example_of::<MyStruct>(),
+			Value::named_composite(vec![
+				(
+					"e".into(),
+					Value::named_variant(
+						"Foo",
+						vec![
+							("a".into(), Value::uint(0u8)),
+							(
+								"b".into(),
+								Value::unnamed_composite(vec![
+									Value::bool(false),
+									Value::unnamed_composite(vec![
+										Value::uint(0u8),
+										Value::uint(0u8)
+									]),
+								])
+							),
+						]
+					)
+				),
+				("maybe".into(), Value::unnamed_variant("None", vec![])),
+				("num".into(), Value::uint(0u8)),
+			])
+		);
+	}
+
+	#[test]
+	fn example_recursive_types() {
+		// The first variant points back to this type, so we should pick the second.
+		#[allow(dead_code)]
+		#[derive(Encode, scale_info::TypeInfo)]
+		enum Tree {
+			Node(Box<Tree>, Box<Tree>),
+			Leaf(u8),
+		}
+		assert_eq!(example_of::<Tree>(), Value::unnamed_variant("Leaf", vec![Value::uint(0u8)]));
+
+		#[derive(Encode, scale_info::TypeInfo)]
+		struct Node {
+			children: Vec<Node>,
+			parent: Option<Box<Node>>,
+		}
+		assert_eq!(
+			example_of::<Node>(),
+			Value::named_composite(vec![
+				("children".into(), Value::unnamed_composite(vec![])),
+				("parent".into(), Value::unnamed_variant("None", vec![])),
+			])
+		);
+	}
+
+	#[test]
+	fn example_of_impossible_type_is_empty() {
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		struct Forever {
+			inner: Box<Forever>,
+		}
+
+		let (id, types) = make_type::<Forever>();
+		assert_eq!(example_value(id, &types).remove_context(), Value::unnamed_composite(vec![]));
+	}
+}
@@ -0,0 +1,120 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+
+/// The location of some value inside of a larger value, described by the
+/// field names, indexes and variant names that lead to it. This is displayed
+/// like `args.dest.Id[3]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+	segments: Vec<LocationSegment>,
+}
+
+/// A single step into some value, as part of a [`Location`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocationSegment {
+	/// A named field of a composite or variant.
+	Field(String),
+	/// An index into an unnamed composite, tuple, array or sequence.
+	Index(usize),
+	/// The name of an enum variant.
+	Variant(String),
+}
+
+impl LocationSegment {
+	/// The segment pointing to the field at some index in an unnamed composite or tuple
+	/// with `len` fields. Wrapper types with a single unnamed field are looked straight
+	/// through, so that we see `args.dest.Id[3]` rather than `args.dest.Id[0][0][3]`.
+	pub(crate) fn unnamed_field(idx: usize, len: usize) -> Option<LocationSegment> {
+		(len != 1).then_some(LocationSegment::Index(idx))
+	}
+}
+
+impl Location {
+	/// Create a new, empty location, pointing at the root value.
+	pub fn new() -> Self {
+		Location { segments: Vec::new() }
+	}
+
+	/// The segments that make up this location, outermost first.
+	pub fn segments(&self) -> &[LocationSegment] {
+		&self.segments
+	}
+
+	/// Does this location point at the root value?
+	pub fn is_empty(&self) -> bool {
+		self.segments.is_empty()
+	}
+
+	/// Step into some inner value.
+	pub(crate) fn push(&mut self, segment: LocationSegment) {
+		self.segments.push(segment);
+	}
+
+	/// Note that this location is inside of some outer value.
+	pub(crate) fn prepend(&mut self, segment: impl Into<Option<LocationSegment>>) {
+		if let Some(segment) = segment.into() {
+			self.segments.insert(0, segment);
+		}
+	}
+
+	/// Step back out of the innermost value.
+	pub(crate) fn pop(&mut self) {
+		self.segments.pop();
+	}
+}
+
+impl Display for Location {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (idx, segment) in self.segments.iter().enumerate() {
+			match segment {
+				LocationSegment::Index(n) => write!(f, "[{n}]")?,
+				LocationSegment::Field(name) | LocationSegment::Variant(name) => {
+					if idx > 0 {
+						f.write_str(".")?;
+					}
+					f.write_str(name)?;
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn location_display() {
+		let mut loc = Location::new();
+		assert_eq!(loc.to_string(), "");
+
+		loc.push(LocationSegment::Field("args".into()));
+		loc.push(LocationSegment::Field("dest".into()));
+		loc.push(LocationSegment::Variant("Id".into()));
+		loc.push(LocationSegment::Index(3));
+		assert_eq!(loc.to_string(), "args.dest.Id[3]");
+
+		loc.pop();
+		assert_eq!(loc.to_string(), "args.dest.Id");
+
+		let mut loc = Location::new();
+		loc.push(LocationSegment::Index(2));
+		loc.push(LocationSegment::Variant("Balances".into()));
+		assert_eq!(loc.to_string(), "[2].Balances");
+	}
+}
@@ -16,10 +16,12 @@
 mod bit_sequence;
 mod decode;
 mod encode;
+mod example;
+mod location;
 mod type_id;
-
-/// The portable version of [`scale_info::Type`]
-type ScaleType = scale_info::Type<scale_info::form::PortableForm>;
+mod type_name;
+mod validate;
+mod visitor;
 
 /// The portable version of a [`scale_info`] type ID.
 type ScaleTypeId = scale_info::interner::UntrackedSymbol<std::any::TypeId>; // equivalent to: <scale_info::form::PortableForm as scale_info::form::Form>::Type;
@@ -28,7 +30,15 @@ type ScaleTypeId = scale_info::interner::UntrackedSymbol<std::any::TypeId>; // e
 type ScaleTypeDef = scale_info::TypeDef<scale_info::form::PortableForm>;
 
 pub use bit_sequence::BitSequenceError;
-pub use decode::{decode_value_as_type, DecodeError};
-pub use encode::{encode_value_as_type, EncodeError};
+pub use decode::{decode_value_as_type, DecodeError, DecodeErrorKind};
+pub use encode::{encode_value_as_type, EncodeError, EncodeErrorKind};
+pub use example::example_value;
+pub use location::{Location, LocationSegment};
 
 pub use type_id::TypeId;
+pub use type_name::type_name;
+pub use validate::{validate_value_as_type, ValidationError, ValidationErrorKind};
+pub use visitor::{
+	decode_with_visitor, skip_type, BitSequence, Compact, CompactLocation, Composite, Sequence,
+	Variant, Visitor,
+};
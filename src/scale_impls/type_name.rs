@@ -0,0 +1,223 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{type_id::TypeId, ScaleTypeDef as TypeDef};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDefPrimitive};
+use std::fmt::Write;
+
+/// Types without a path (tuples, sequences and so on) are named by naming the types
+/// inside them. Self-referential types always pass through a type with a path, but
+/// we stop after this many levels regardless, just to be safe.
+const MAX_DEPTH: usize = 32;
+
+/// Return a human readable name for the type given, like `Vec<(u8, bool)>` or
+/// `Option<sp_runtime::multiaddress::MultiAddress<sp_core::crypto::AccountId32, ()>>`.
+pub fn type_name<Id: Into<TypeId>>(ty_id: Id, types: &PortableRegistry) -> String {
+	let mut out = String::new();
+	write_type_name(ty_id.into().id(), types, 0, &mut out);
+	out
+}
+
+fn write_type_name(id: u32, types: &PortableRegistry, depth: usize, out: &mut String) {
+	let ty = match types.resolve(id) {
+		Some(ty) => ty,
+		None => {
+			let _ = write!(out, "<unknown type ID {id}>");
+			return;
+		}
+	};
+	if depth > MAX_DEPTH {
+		out.push_str("..");
+		return;
+	}
+
+	// Types with a path are named by it, along with any generic parameters.
+	if !ty.path.is_empty() {
+		out.push_str(&ty.path.segments.join("::"));
+		if !ty.type_params.is_empty() {
+			out.push('<');
+			for (idx, param) in ty.type_params.iter().enumerate() {
+				if idx > 0 {
+					out.push_str(", ");
+				}
+				match &param.ty {
+					Some(param_ty) => write_type_name(param_ty.id, types, depth + 1, out),
+					None => out.push_str(&param.name),
+				}
+			}
+			out.push('>');
+		}
+		return;
+	}
+
+	match &ty.type_def {
+		TypeDef::Composite(inner) => write_fields(&inner.fields, types, depth, out),
+		TypeDef::Variant(inner) => {
+			for (idx, variant) in inner.variants.iter().enumerate() {
+				if idx > 0 {
+					out.push_str(" | ");
+				}
+				out.push_str(&variant.name);
+				if !variant.fields.is_empty() {
+					write_fields(&variant.fields, types, depth, out);
+				}
+			}
+		}
+		TypeDef::Sequence(inner) => {
+			out.push_str("Vec<");
+			write_type_name(inner.type_param.id, types, depth + 1, out);
+			out.push('>');
+		}
+		TypeDef::Array(inner) => {
+			out.push('[');
+			write_type_name(inner.type_param.id, types, depth + 1, out);
+			let _ = write!(out, "; {}]", inner.len);
+		}
+		TypeDef::Tuple(inner) => {
+			out.push('(');
+			for (idx, field) in inner.fields.iter().enumerate() {
+				if idx > 0 {
+					out.push_str(", ");
+				}
+				write_type_name(field.id, types, depth + 1, out);
+			}
+			// A tuple of one item has a trailing comma to distinguish it from brackets.
+			if inner.fields.len() == 1 {
+				out.push(',');
+			}
+			out.push(')');
+		}
+		TypeDef::Primitive(inner) => out.push_str(primitive_name(inner)),
+		TypeDef::Compact(inner) => {
+			out.push_str("Compact<");
+			write_type_name(inner.type_param.id, types, depth + 1, out);
+			out.push('>');
+		}
+		TypeDef::BitSequence(inner) => {
+			out.push_str("BitVec<");
+			write_type_name(inner.bit_store_type.id, types, depth + 1, out);
+			out.push_str(", ");
+			write_type_name(inner.bit_order_type.id, types, depth + 1, out);
+			out.push('>');
+		}
+	}
+}
+
+/// Name some struct or variant fields, like `{ a: u8, b: bool }` or `(u8, bool)`.
+fn write_fields(
+	fields: &[Field<PortableForm>],
+	types: &PortableRegistry,
+	depth: usize,
+	out: &mut String,
+) {
+	let is_named = fields.first().map(|f| f.name.is_some()).unwrap_or(false);
+	out.push_str(if is_named { "{ " } else { "(" });
+	for (idx, field) in fields.iter().enumerate() {
+		if idx > 0 {
+			out.push_str(", ");
+		}
+		if let Some(name) = &field.name {
+			out.push_str(name);
+			out.push_str(": ");
+		}
+		write_type_name(field.ty.id, types, depth + 1, out);
+	}
+	out.push_str(if is_named { " }" } else { ")" });
+}
+
+fn primitive_name(ty: &TypeDefPrimitive) -> &'static str {
+	match ty {
+		TypeDefPrimitive::Bool => "bool",
+		TypeDefPrimitive::Char => "char",
+		TypeDefPrimitive::Str => "String",
+		TypeDefPrimitive::U8 => "u8",
+		TypeDefPrimitive::U16 => "u16",
+		TypeDefPrimitive::U32 => "u32",
+		TypeDefPrimitive::U64 => "u64",
+		TypeDefPrimitive::U128 => "u128",
+		TypeDefPrimitive::U256 => "u256",
+		TypeDefPrimitive::I8 => "i8",
+		TypeDefPrimitive::I16 => "i16",
+		TypeDefPrimitive::I32 => "i32",
+		TypeDefPrimitive::I64 => "i64",
+		TypeDefPrimitive::I128 => "i128",
+		TypeDefPrimitive::I256 => "i256",
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Return the name of the type given, as seen through a registry.
+	fn name_of<T: scale_info::TypeInfo + 'static>() -> String {
+		let m = scale_info::MetaType::new::<T>();
+		let mut types = scale_info::Registry::new();
+		let id = types.register_type(&m);
+		let portable_registry: PortableRegistry = types.into();
+
+		type_name(id.id, &portable_registry)
+	}
+
+	#[test]
+	fn names_builtin_types() {
+		assert_eq!(name_of::<u8>(), "u8");
+		assert_eq!(name_of::<String>(), "String");
+		assert_eq!(name_of::<Vec<(bool, i64)>>(), "Vec<(bool, i64)>");
+		assert_eq!(name_of::<[u16; 4]>(), "[u16; 4]");
+		assert_eq!(name_of::<(u8,)>(), "(u8,)");
+		assert_eq!(name_of::<()>(), "()");
+		assert_eq!(name_of::<codec::Compact<u64>>(), "Compact<u64>");
+		assert_eq!(
+			name_of::<bitvec::vec::BitVec<u8, bitvec::order::Lsb0>>(),
+			"BitVec<u8, bitvec::order::Lsb0>"
+		);
+	}
+
+	#[test]
+	fn names_types_with_paths() {
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		enum MultiAddress<AccountId, AccountIndex> {
+			Id(AccountId),
+			Index(AccountIndex),
+		}
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		struct AccountId32([u8; 32]);
+
+		assert_eq!(
+			name_of::<Option<MultiAddress<AccountId32, ()>>>(),
+			"Option<scale_value::scale_impls::type_name::test::MultiAddress<scale_value::scale_impls::type_name::test::AccountId32, ()>>"
+		);
+	}
+
+	#[test]
+	fn names_recursive_types() {
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		struct Node {
+			children: Vec<Node>,
+		}
+
+		assert_eq!(name_of::<Vec<Node>>(), "Vec<scale_value::scale_impls::type_name::test::Node>");
+	}
+
+	#[test]
+	fn unknown_types_are_named() {
+		let types: PortableRegistry = scale_info::Registry::new().into();
+		assert_eq!(type_name(123, &types), "<unknown type ID 123>");
+	}
+}
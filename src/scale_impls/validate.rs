@@ -0,0 +1,711 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Check whether a [`Value`] could be encoded into some type, without encoding it.
+//! The rules here mirror those in the `encode` module, but rather than stopping at
+//! the first problem, we note it down and carry on looking for more.
+
+use super::{
+	bit_sequence::{get_bitsequence_details, BitSequenceError},
+	encode::primitive_as_bytes,
+	location::{Location, LocationSegment},
+	type_id::TypeId,
+	type_name::type_name,
+	ScaleTypeDef as TypeDef,
+};
+use crate::value::{Composite, Primitive, Value, ValueDef};
+use scale_info::{
+	form::PortableForm, Field, PortableRegistry, TypeDefCompact, TypeDefPrimitive, TypeDefVariant,
+};
+use std::fmt::Display;
+
+/// A reason that a [`Value`] is not compatible with the type it's being validated against,
+/// along with the [`Location`] of the offending value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+	/// Where in the value the problem was found.
+	pub location: Location,
+	/// What the problem is.
+	pub kind: ValidationErrorKind,
+}
+
+impl Display for ValidationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.location.is_empty() {
+			write!(f, "{}", self.kind)
+		} else {
+			write!(f, "at {}: {}", self.location, self.kind)
+		}
+	}
+}
+
+impl std::error::Error for ValidationError {}
+
+/// The different reasons that a [`Value`] may not be compatible with a type.
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+pub enum ValidationErrorKind {
+	/// The type we're validating against cannot be found in the type registry provided.
+	#[error("Cannot find type with ID {0}")]
+	TypeIdNotFound(TypeId),
+	/// The value is not the correct shape for the type we're validating against.
+	#[error("Value shape is wrong; expected {expected_name}, but got {actual}")]
+	WrongShape {
+		/// A short description of the value that was found.
+		actual: String,
+		/// The type we're validating against.
+		expected: TypeId,
+		/// The name of the type we're validating against.
+		expected_name: String,
+	},
+	/// The composite value has the wrong number of fields.
+	#[error("Composite type is the wrong length; expected length is {expected_len}, but got {actual_len}")]
+	CompositeIsWrongLength {
+		/// The number of fields that the composite value has.
+		actual_len: usize,
+		/// The type we're validating against.
+		expected: TypeId,
+		/// The number of fields that the type expects.
+		expected_len: usize,
+	},
+	/// The composite value has named fields and the type expects unnamed ones, or vice versa.
+	#[error("The composite is not the same shape as the type we're validating against ({expected_name})")]
+	CompositeIsWrongShape {
+		/// The type we're validating against.
+		expected: TypeId,
+		/// The name of the type we're validating against.
+		expected_name: String,
+	},
+	/// A field is present on the type we're validating against, but not on the value.
+	#[error("The field {missing_field_name} is present on the type we're validating against but hasn't been provided")]
+	CompositeFieldIsMissing {
+		/// The name of the missing field.
+		missing_field_name: String,
+		/// The type we're validating against.
+		expected: TypeId,
+	},
+	/// A field is present on the value, but not on the type we're validating against.
+	#[error(
+		"The field {unexpected_field_name} is not present on the type we're validating against"
+	)]
+	CompositeFieldIsUnexpected {
+		/// The name of the unexpected field.
+		unexpected_field_name: String,
+		/// The type we're validating against.
+		expected: TypeId,
+	},
+	/// The variant was not found in the type we're validating against.
+	#[error("Variant {name} was not found in {expected_name}")]
+	VariantNotFound {
+		/// The name of the variant that was provided.
+		name: String,
+		/// The type we're validating against.
+		expected: TypeId,
+		/// The name of the type we're validating against.
+		expected_name: String,
+	},
+	/// There was an error working with the bit sequence type.
+	#[error("Cannot validate bit sequence: {0}")]
+	BitSequenceError(BitSequenceError),
+	/// The type is supposed to be compact encoded, but this is not possible to do automatically.
+	#[error("The type {1} cannot be compact encoded")]
+	CannotCompactEncode(TypeId, String),
+}
+
+/// Check that a [`Value`] is compatible with the [`TypeId`] and [`PortableRegistry`]
+/// provided, such that encoding it would succeed. Every problem that's found is returned.
+pub fn validate_value_as_type<T, Id: Into<TypeId>>(
+	value: &Value<T>,
+	ty_id: Id,
+	types: &PortableRegistry,
+) -> Result<(), Vec<ValidationError>> {
+	let mut validator = Validator { types, location: Location::new(), errors: Vec::new() };
+	validator.validate(value, ty_id.into());
+
+	if validator.errors.is_empty() {
+		Ok(())
+	} else {
+		Err(validator.errors)
+	}
+}
+
+struct Validator<'a> {
+	types: &'a PortableRegistry,
+	location: Location,
+	errors: Vec<ValidationError>,
+}
+
+impl<'a> Validator<'a> {
+	fn err(&mut self, kind: ValidationErrorKind) {
+		self.errors.push(ValidationError { location: self.location.clone(), kind });
+	}
+
+	fn wrong_shape<T>(&mut self, value: &Value<T>, expected: TypeId) {
+		let expected_name = type_name(expected, self.types);
+		self.err(ValidationErrorKind::WrongShape {
+			actual: describe(value),
+			expected,
+			expected_name,
+		});
+	}
+
+	/// Validate some value at the given location segment.
+	fn validate_at<T>(
+		&mut self,
+		segment: impl Into<Option<LocationSegment>>,
+		value: &Value<T>,
+		type_id: TypeId,
+	) {
+		match segment.into() {
+			Some(segment) => {
+				self.location.push(segment);
+				self.validate(value, type_id);
+				self.location.pop();
+			}
+			None => self.validate(value, type_id),
+		}
+	}
+
+	/// Does some value validate against the given type? Errors aren't kept.
+	fn is_valid<T>(&self, value: &Value<T>, type_id: TypeId) -> bool {
+		validate_value_as_type(value, type_id, self.types).is_ok()
+	}
+
+	fn validate<T>(&mut self, value: &Value<T>, type_id: TypeId) {
+		let ty = match self.types.resolve(type_id.id()) {
+			Some(ty) => ty,
+			None => return self.err(ValidationErrorKind::TypeIdNotFound(type_id)),
+		};
+
+		match &ty.type_def {
+			TypeDef::Composite(inner) => match &value.value {
+				ValueDef::Composite(composite) => {
+					self.validate_composite_fields(composite, &inner.fields, type_id)
+				}
+				// A 1-field composite type? try validating against the inner content then.
+				_ if inner.fields.len() == 1 => self.validate(value, inner.fields[0].ty.into()),
+				_ => self.wrong_shape(value, type_id),
+			},
+			TypeDef::Sequence(inner) => {
+				self.validate_sequence(value, None, inner.type_param.into(), type_id)
+			}
+			TypeDef::Array(inner) => self.validate_sequence(
+				value,
+				Some(inner.len as usize),
+				inner.type_param.into(),
+				type_id,
+			),
+			TypeDef::Tuple(inner) => match &value.value {
+				ValueDef::Composite(composite) => {
+					if composite.len() != inner.fields.len() {
+						self.err(ValidationErrorKind::CompositeIsWrongLength {
+							actual_len: composite.len(),
+							expected: type_id,
+							expected_len: inner.fields.len(),
+						});
+					}
+					// We don't care whether the fields are named or unnamed
+					// as long as we have the number of them that we expect..
+					for (idx, (ty, value)) in
+						inner.fields.iter().zip(composite.values()).enumerate()
+					{
+						let segment = LocationSegment::unnamed_field(idx, inner.fields.len());
+						self.validate_at(segment, value, ty.into());
+					}
+				}
+				// A 1-field tuple? try validating against the inner content then.
+				_ if inner.fields.len() == 1 => self.validate(value, inner.fields[0].into()),
+				_ => self.wrong_shape(value, type_id),
+			},
+			TypeDef::Variant(inner) => self.validate_variant(value, inner, type_id),
+			TypeDef::Primitive(inner) => match &value.value {
+				ValueDef::Primitive(primitive) if primitive_fits(primitive, inner) => {}
+				_ => self.wrong_shape(value, type_id),
+			},
+			TypeDef::Compact(inner) => self.validate_compact(value, inner, type_id),
+			TypeDef::BitSequence(inner) => {
+				if let Err(e) = get_bitsequence_details(inner, self.types) {
+					return self.err(ValidationErrorKind::BitSequenceError(e));
+				}
+				match &value.value {
+					ValueDef::BitSequence(_) => {}
+					ValueDef::Composite(Composite::Unnamed(vals)) => {
+						for (idx, val) in vals.iter().enumerate() {
+							if !matches!(val.value, ValueDef::Primitive(Primitive::Bool(_))) {
+								self.location.push(LocationSegment::Index(idx));
+								self.wrong_shape(val, type_id);
+								self.location.pop();
+							}
+						}
+					}
+					_ => self.wrong_shape(value, type_id),
+				}
+			}
+		}
+	}
+
+	fn validate_sequence<T>(
+		&mut self,
+		value: &Value<T>,
+		expected_len: Option<usize>,
+		item_ty: TypeId,
+		type_id: TypeId,
+	) {
+		match &value.value {
+			ValueDef::Composite(c) => {
+				if let Some(expected_len) = expected_len.filter(|&len| len != c.len()) {
+					return self.err(ValidationErrorKind::CompositeIsWrongLength {
+						actual_len: c.len(),
+						expected: type_id,
+						expected_len,
+					});
+				}
+				for (idx, value) in c.values().enumerate() {
+					self.validate_at(LocationSegment::Index(idx), value, item_ty);
+				}
+			}
+			// As a special case, some primitives stand for bytes, and may be compatible
+			// with the sequence or array type being asked for, too (arrays are padded
+			// with leading zeros, which must also be valid items).
+			ValueDef::Primitive(p) if primitive_as_bytes(p).is_some() => {
+				let a = primitive_as_bytes(p).expect("checked above");
+				let len_ok = expected_len.map(|len| len >= a.len()).unwrap_or(true);
+				let padded = expected_len.is_some_and(|len| len > a.len());
+				let mut items = a.into_iter().chain(padded.then_some(0));
+				if !len_ok || !items.all(|b| self.is_valid(&Value::uint(b), item_ty)) {
+					self.wrong_shape(value, type_id);
+				}
+			}
+			_ => self.wrong_shape(value, type_id),
+		}
+	}
+
+	fn validate_variant<T>(
+		&mut self,
+		value: &Value<T>,
+		ty: &TypeDefVariant<PortableForm>,
+		type_id: TypeId,
+	) {
+		let variant = match &value.value {
+			ValueDef::Variant(variant) => variant,
+			_ => return self.wrong_shape(value, type_id),
+		};
+
+		let variant_type = match ty.variants.iter().find(|v| v.name == variant.name) {
+			Some(v) => v,
+			None => {
+				return self.err(ValidationErrorKind::VariantNotFound {
+					name: variant.name.clone(),
+					expected: type_id,
+					expected_name: type_name(type_id, self.types),
+				})
+			}
+		};
+
+		self.location.push(LocationSegment::Variant(variant.name.clone()));
+		self.validate_composite_fields(&variant.values, &variant_type.fields, type_id);
+		self.location.pop();
+	}
+
+	fn validate_composite_fields<T>(
+		&mut self,
+		composite: &Composite<T>,
+		fields: &[Field<PortableForm>],
+		type_id: TypeId,
+	) {
+		// Does the type we're validating against have named fields or not?
+		let is_named = fields.first().map(|f| f.name.is_some());
+
+		match (composite, is_named) {
+			// Empty composites are compatible with whatever fields we expect, if there are none.
+			(c, None) => {
+				if !c.is_empty() {
+					self.err(ValidationErrorKind::CompositeIsWrongLength {
+						actual_len: c.len(),
+						expected: type_id,
+						expected_len: 0,
+					});
+				}
+			}
+			(Composite::Named(values), Some(true)) => {
+				// Match up named values with those of the type, noting which we've used
+				// so that we can report any that are left over.
+				let mut used = vec![false; values.len()];
+				for field in fields {
+					let field_name =
+						field.name.as_ref().expect("field should be named; checked above");
+					let idx = values
+						.iter()
+						.enumerate()
+						.position(|(idx, (n, _))| !used[idx] && n == field_name);
+
+					match idx {
+						Some(idx) => {
+							used[idx] = true;
+							let segment = LocationSegment::Field(field_name.clone());
+							self.validate_at(segment, &values[idx].1, field.ty.into());
+						}
+						None => self.err(ValidationErrorKind::CompositeFieldIsMissing {
+							missing_field_name: field_name.clone(),
+							expected: type_id,
+						}),
+					}
+				}
+				for ((name, _), _) in values.iter().zip(used).filter(|(_, used)| !used) {
+					self.err(ValidationErrorKind::CompositeFieldIsUnexpected {
+						unexpected_field_name: name.clone(),
+						expected: type_id,
+					});
+				}
+			}
+			(Composite::Unnamed(values), Some(false)) => {
+				if values.len() != fields.len() {
+					self.err(ValidationErrorKind::CompositeIsWrongLength {
+						actual_len: values.len(),
+						expected: type_id,
+						expected_len: fields.len(),
+					});
+				}
+				for (idx, (field, value)) in fields.iter().zip(values).enumerate() {
+					let segment = LocationSegment::unnamed_field(idx, fields.len());
+					self.validate_at(segment, value, field.ty.into());
+				}
+			}
+			// An empty composite with named fields expected is just missing them all.
+			(c, Some(true)) if c.is_empty() => {
+				for field in fields {
+					let field_name =
+						field.name.as_ref().expect("field should be named; checked above");
+					self.err(ValidationErrorKind::CompositeFieldIsMissing {
+						missing_field_name: field_name.clone(),
+						expected: type_id,
+					});
+				}
+			}
+			(c, Some(false)) if c.is_empty() => {
+				self.err(ValidationErrorKind::CompositeIsWrongLength {
+					actual_len: 0,
+					expected: type_id,
+					expected_len: fields.len(),
+				});
+			}
+			// We expect named/unnamed fields and have the opposite.
+			(_, Some(_)) => self.err(ValidationErrorKind::CompositeIsWrongShape {
+				expected: type_id,
+				expected_name: type_name(type_id, self.types),
+			}),
+		}
+	}
+
+	fn validate_compact<T>(
+		&mut self,
+		value: &Value<T>,
+		ty: &TypeDefCompact<PortableForm>,
+		type_id: TypeId,
+	) {
+		// Resolve to a primitive type inside the compact encoded type (or fail if
+		// we hit some type we wouldn't know how to work with).
+		let mut inner_ty_id = ty.type_param.id;
+		let inner_ty = loop {
+			let inner_ty = match self.types.resolve(inner_ty_id) {
+				Some(ty) => &ty.type_def,
+				None => return self.err(ValidationErrorKind::TypeIdNotFound(inner_ty_id.into())),
+			};
+
+			match inner_ty {
+				TypeDef::Composite(c) if c.fields.len() == 1 => inner_ty_id = c.fields[0].ty.id,
+				TypeDef::Tuple(t) if t.fields.len() == 1 => inner_ty_id = t.fields[0].id,
+				TypeDef::Primitive(
+					primitive @ (TypeDefPrimitive::U8
+					| TypeDefPrimitive::U16
+					| TypeDefPrimitive::U32
+					| TypeDefPrimitive::U64
+					| TypeDefPrimitive::U128),
+				) => break primitive,
+				_ => {
+					let name = type_name(inner_ty_id, self.types);
+					return self
+						.err(ValidationErrorKind::CannotCompactEncode(inner_ty_id.into(), name));
+				}
+			}
+		};
+
+		// Resolve to the innermost value that we have in the same way, expecting to get out
+		// a single primitive value which fits into the type.
+		let mut value = value;
+		loop {
+			match &value.value {
+				ValueDef::Composite(c) if c.len() == 1 => {
+					value = c.values().next().expect("length of 1; value should exist");
+				}
+				ValueDef::Primitive(primitive) => {
+					if !primitive_fits(primitive, inner_ty) {
+						self.wrong_shape(value, type_id);
+					}
+					break;
+				}
+				_ => break self.wrong_shape(value, inner_ty_id.into()),
+			}
+		}
+	}
+}
+
+/// Can the primitive value given be encoded into the primitive type given?
+fn primitive_fits(primitive: &Primitive, ty: &TypeDefPrimitive) -> bool {
+	// Chars are treated as u32s to mirror what we do for decoding.
+	fn fits<N: TryFrom<u128> + TryFrom<i128>>(primitive: &Primitive) -> bool {
+		match *primitive {
+			Primitive::U128(v) => N::try_from(v).is_ok(),
+			Primitive::I128(v) => N::try_from(v).is_ok(),
+			Primitive::Char(v) => N::try_from(v as u32 as u128).is_ok(),
+			_ => false,
+		}
+	}
+
+	match (ty, primitive) {
+		(TypeDefPrimitive::Bool, Primitive::Bool(_))
+		| (TypeDefPrimitive::Char, Primitive::Char(_))
+		| (TypeDefPrimitive::Str, Primitive::String(_))
+		| (TypeDefPrimitive::I256, Primitive::I256(_))
+		| (TypeDefPrimitive::U256, Primitive::U256(_)) => true,
+		(TypeDefPrimitive::U8, p) => fits::<u8>(p),
+		(TypeDefPrimitive::U16, p) => fits::<u16>(p),
+		(TypeDefPrimitive::U32, p) => fits::<u32>(p),
+		(TypeDefPrimitive::U64, p) => fits::<u64>(p),
+		(TypeDefPrimitive::U128, p) => fits::<u128>(p),
+		(TypeDefPrimitive::I8, p) => fits::<i8>(p),
+		(TypeDefPrimitive::I16, p) => fits::<i16>(p),
+		(TypeDefPrimitive::I32, p) => fits::<i32>(p),
+		(TypeDefPrimitive::I64, p) => fits::<i64>(p),
+		(TypeDefPrimitive::I128, p) => fits::<i128>(p),
+		_ => false,
+	}
+}
+
+/// A short description of some value, for use in error messages.
+fn describe<T>(value: &Value<T>) -> String {
+	match &value.value {
+		ValueDef::Composite(Composite::Named(_)) => "a composite with named fields".into(),
+		ValueDef::Composite(Composite::Unnamed(_)) => "a composite with unnamed fields".into(),
+		ValueDef::Variant(v) => format!("the variant {}", v.name),
+		ValueDef::BitSequence(_) => "a bit sequence".into(),
+		ValueDef::Primitive(Primitive::Bool(b)) => format!("the bool {b}"),
+		ValueDef::Primitive(Primitive::Char(c)) => format!("the char {c:?}"),
+		ValueDef::Primitive(Primitive::String(s)) => format!("the string {s:?}"),
+		ValueDef::Primitive(Primitive::U128(n)) => format!("the number {n}"),
+		ValueDef::Primitive(Primitive::I128(n)) => format!("the number {n}"),
+		ValueDef::Primitive(Primitive::U256(_)) => "a u256".into(),
+		ValueDef::Primitive(Primitive::I256(_)) => "an i256".into(),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::scale_impls::encode_value_as_type;
+
+	/// Given a type definition, return the PortableType and PortableRegistry
+	/// that our validate functions expect.
+	fn make_type<T: scale_info::TypeInfo + 'static>() -> (TypeId, PortableRegistry) {
+		let m = scale_info::MetaType::new::<T>();
+		let mut types = scale_info::Registry::new();
+		let id = types.register_type(&m);
+		let portable_registry: PortableRegistry = types.into();
+
+		(id.into(), portable_registry)
+	}
+
+	/// Validate the value against the type, and check that this agrees with
+	/// whether encoding works. Hand back the validation errors as strings.
+	fn validate<T: scale_info::TypeInfo + 'static>(value: Value<()>) -> Vec<String> {
+		let (id, types) = make_type::<T>();
+		let res = validate_value_as_type(&value, id, &types);
+		let encode_res = encode_value_as_type(value, id, &types, &mut Vec::new());
+
+		assert_eq!(res.is_ok(), encode_res.is_ok(), "validation and encoding should agree");
+		res.err().unwrap_or_default().iter().map(|e| e.to_string()).collect()
+	}
+
+	#[allow(dead_code)]
+	#[derive(scale_info::TypeInfo)]
+	enum MultiAddress {
+		Id([u8; 4]),
+		Index(#[codec(compact)] u32),
+	}
+
+	#[allow(dead_code)]
+	#[derive(scale_info::TypeInfo)]
+	struct Transfer {
+		dest: MultiAddress,
+		value: u128,
+	}
+
+	#[allow(dead_code)]
+	#[derive(scale_info::TypeInfo)]
+	struct Call {
+		args: Transfer,
+		tip: Option<u8>,
+	}
+
+	fn transfer(dest: Value<()>, value: Value<()>) -> Value<()> {
+		Value::named_composite(vec![
+			(
+				"args".into(),
+				Value::named_composite(vec![("dest".into(), dest), ("value".into(), value)]),
+			),
+			("tip".into(), Value::unnamed_variant("None", vec![])),
+		])
+	}
+
+	#[test]
+	fn valid_values_pass() {
+		let id = Value::unnamed_variant(
+			"Id",
+			vec![Value::unnamed_composite(vec![
+				Value::uint(1u8),
+				Value::uint(2u8),
+				Value::uint(3u8),
+				Value::uint(4u8),
+			])],
+		);
+		assert!(validate::<Call>(transfer(id, Value::uint(100u8))).is_empty());
+
+		let index = Value::unnamed_variant("Index", vec![Value::uint(1u8)]);
+		assert!(validate::<Call>(transfer(index, Value::uint(100u8))).is_empty());
+
+		assert!(validate::<(bool, char, String)>(Value::unnamed_composite(vec![
+			Value::bool(true),
+			Value::char('a'),
+			Value::string("hi")
+		]))
+		.is_empty());
+		assert!(validate::<Vec<bool>>(Value::unnamed_composite(vec![])).is_empty());
+
+		// Unsigned numbers stand for their big endian bytes, padded to fill arrays:
+		assert!(validate::<[u8; 4]>(Value::uint(0xbeefu16)).is_empty());
+		assert!(validate::<Vec<u8>>(Value::uint(0xbeefu16)).is_empty());
+		assert!(validate::<bitvec::vec::BitVec<u8, bitvec::order::Lsb0>>(
+			Value::unnamed_composite(vec![Value::bool(true), Value::bool(false)])
+		)
+		.is_empty());
+	}
+
+	#[test]
+	fn reports_path_to_error() {
+		let id = Value::unnamed_variant(
+			"Id",
+			vec![Value::unnamed_composite(vec![
+				Value::uint(1u8),
+				Value::uint(2u8),
+				Value::uint(3u8),
+				Value::uint(256u16),
+			])],
+		);
+		let errs = validate::<Call>(transfer(id, Value::uint(100u8)));
+
+		assert_eq!(errs.len(), 1);
+		assert!(errs[0].starts_with("at args.dest.Id[3]: Value shape is wrong"));
+		assert!(errs[0].ends_with("but got the number 256"));
+	}
+
+	#[test]
+	fn reports_every_error() {
+		let dest = Value::unnamed_variant("Foo", vec![]);
+		let value = Value::string("lots");
+		let errs = validate::<Call>(transfer(dest, value));
+
+		assert_eq!(errs.len(), 2);
+		assert!(errs[0].starts_with("at args.dest: Variant Foo was not found in "));
+		assert!(errs[1].starts_with("at args.value: Value shape is wrong"));
+		assert!(errs[1].ends_with("but got the string \"lots\""));
+	}
+
+	#[test]
+	fn reports_missing_and_unexpected_fields() {
+		let value = Value::named_composite(vec![
+			("value".into(), Value::uint(1u8)),
+			("destination".into(), Value::unnamed_variant("Index", vec![Value::uint(1u8)])),
+		]);
+		let errs = validate::<Transfer>(value);
+
+		assert_eq!(
+			errs,
+			vec![
+				"The field dest is present on the type we're validating against but hasn't been provided",
+				"The field destination is not present on the type we're validating against",
+			]
+		);
+	}
+
+	#[test]
+	fn reports_wrong_lengths_and_shapes() {
+		let errs = validate::<[u8; 2]>(Value::unnamed_composite(vec![Value::uint(1u8)]));
+		assert_eq!(
+			errs,
+			vec!["Composite type is the wrong length; expected length is 2, but got 1"]
+		);
+
+		let errs = validate::<(u8, bool)>(Value::unnamed_composite(vec![
+			Value::bool(true),
+			Value::bool(true),
+			Value::bool(true),
+		]));
+		assert_eq!(errs.len(), 2);
+		assert_eq!(errs[0], "Composite type is the wrong length; expected length is 2, but got 3");
+		assert!(errs[1].starts_with("at [0]: Value shape is wrong"));
+
+		let errs = validate::<Transfer>(Value::unnamed_composite(vec![
+			Value::unnamed_variant("Index", vec![Value::uint(1u8)]),
+			Value::uint(1u8),
+		]));
+		assert!(errs[0].starts_with("The composite is not the same shape"));
+
+		let errs = validate::<[u8; 1]>(Value::uint(0xbeefu16));
+		assert!(errs[0].starts_with("Value shape is wrong"));
+	}
+
+	#[test]
+	fn validates_compact_values() {
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		struct Foo {
+			#[codec(compact)]
+			a: u16,
+		}
+
+		assert!(validate::<Foo>(Value::named_composite(vec![("a".into(), Value::uint(1u8))]))
+			.is_empty());
+		assert!(validate::<Foo>(Value::named_composite(vec![(
+			"a".into(),
+			Value::unnamed_composite(vec![Value::uint(1u8)])
+		)]))
+		.is_empty());
+
+		let errs =
+			validate::<Foo>(Value::named_composite(vec![("a".into(), Value::uint(100_000u32))]));
+		assert!(errs[0].starts_with("at a: Value shape is wrong"));
+
+		let errs = validate::<Foo>(Value::named_composite(vec![("a".into(), Value::bool(true))]));
+		assert!(errs[0].starts_with("at a: Value shape is wrong"));
+	}
+
+	#[test]
+	fn validates_bit_sequences() {
+		let errs = validate::<bitvec::vec::BitVec<u8, bitvec::order::Lsb0>>(
+			Value::unnamed_composite(vec![Value::bool(true), Value::uint(1u8)]),
+		);
+		assert_eq!(errs.len(), 1);
+		assert!(errs[0].starts_with("at [1]: Value shape is wrong"));
+	}
+}
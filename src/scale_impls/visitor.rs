@@ -0,0 +1,915 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module exposes a [`Visitor`] trait, which is handed the values found while decoding
+//! some SCALE bytes, and is free to do whatever it likes with them. Decoding into a [`crate::Value`]
+//! is just one implementation of this.
+
+use super::{
+	bit_sequence::{get_bitsequence_details, BitOrderTy, BitStoreTy},
+	decode::{DecodeError, DecodeErrorKind},
+	type_id::TypeId,
+	type_name::type_name,
+	ScaleTypeDef as TypeDef, ScaleTypeId,
+};
+use bitvec::{
+	order::{BitOrder, Lsb0, Msb0},
+	store::BitStore,
+	vec::BitVec,
+};
+use codec::{Compact as CodecCompact, Decode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDefPrimitive};
+
+/// An implementation of this trait is handed each of the values found while decoding
+/// some SCALE bytes via [`crate::scale::decode_with_visitor`], and decides what to do with them.
+///
+/// Integers are handed over as `u128`s and `i128`s regardless of their size on the
+/// wire (mirroring [`crate::Primitive`]); the [`TypeId`] passed alongside each value
+/// can be used to find out more about it if needed.
+///
+/// Each method has a default implementation which returns a
+/// [`DecodeErrorKind::UnexpectedValue`] error, so only the methods for the shapes of data
+/// that you expect to see need implementing.
+pub trait Visitor: Sized {
+	/// The type of value that this visitor produces.
+	type Value;
+	/// The error that this visitor can return. Errors that occur while decoding
+	/// need to be convertible into it.
+	type Error: From<DecodeError>;
+
+	/// Called when a bool is found.
+	fn visit_bool(self, _value: bool, type_id: TypeId) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("bool", type_id))
+	}
+	/// Called when a char is found.
+	fn visit_char(self, _value: char, type_id: TypeId) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("char", type_id))
+	}
+	/// Called when a string is found. The string is borrowed from the bytes being decoded.
+	fn visit_str(self, _value: &str, type_id: TypeId) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("str", type_id))
+	}
+	/// Called when any unsigned integer up to 128 bits in size is found.
+	fn visit_u128(self, _value: u128, type_id: TypeId) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("unsigned integer", type_id))
+	}
+	/// Called when any signed integer up to 128 bits in size is found.
+	fn visit_i128(self, _value: i128, type_id: TypeId) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("signed integer", type_id))
+	}
+	/// Called when an unsigned 256 bit integer is found.
+	fn visit_u256(self, _value: &[u8; 32], type_id: TypeId) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("u256", type_id))
+	}
+	/// Called when a signed 256 bit integer is found.
+	fn visit_i256(self, _value: &[u8; 32], type_id: TypeId) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("i256", type_id))
+	}
+	/// Called when a compact encoded integer is found.
+	fn visit_compact(
+		self,
+		_value: Compact<'_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("compact", type_id))
+	}
+	/// Called when a sequence of values is found. Any values not decoded by the visitor
+	/// are skipped over once it returns.
+	fn visit_sequence(
+		self,
+		_value: &mut Sequence<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("sequence", type_id))
+	}
+	/// Called when a fixed length array of values is found. Any values not decoded by
+	/// the visitor are skipped over once it returns.
+	fn visit_array(
+		self,
+		_value: &mut Sequence<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("array", type_id))
+	}
+	/// Called when a tuple is found. Any values not decoded by the visitor are skipped
+	/// over once it returns.
+	fn visit_tuple(
+		self,
+		_value: &mut Composite<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("tuple", type_id))
+	}
+	/// Called when a struct-like composite value is found. Any fields not decoded by the
+	/// visitor are skipped over once it returns.
+	fn visit_composite(
+		self,
+		_value: &mut Composite<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("composite", type_id))
+	}
+	/// Called when an enum variant is found. Any fields not decoded by the visitor are
+	/// skipped over once it returns.
+	fn visit_variant(
+		self,
+		_value: &mut Variant<'_, '_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("variant", type_id))
+	}
+	/// Called when a bit sequence is found.
+	fn visit_bit_sequence(
+		self,
+		_value: BitSequence<'_>,
+		type_id: TypeId,
+	) -> Result<Self::Value, Self::Error> {
+		Err(unexpected("bit sequence", type_id))
+	}
+}
+
+/// The error handed back by default when a [`Visitor`] is given a value it doesn't expect.
+fn unexpected<E: From<DecodeError>>(kind: &'static str, type_id: TypeId) -> E {
+	DecodeError::from(DecodeErrorKind::UnexpectedValue(kind, type_id.id())).into()
+}
+
+/// Decode data according to the [`TypeId`] provided, handing the values found to
+/// the [`Visitor`] given. The provided pointer to the data slice will be moved
+/// forwards past the decoded value.
+pub fn decode_with_visitor<V: Visitor, Id: Into<TypeId>>(
+	data: &mut &[u8],
+	ty_id: Id,
+	types: &PortableRegistry,
+	visitor: V,
+) -> Result<V::Value, V::Error> {
+	let ty_id = ty_id.into();
+	let ty = types
+		.resolve(ty_id.id())
+		.ok_or(DecodeError::from(DecodeErrorKind::TypeIdNotFound(ty_id.id())))?;
+
+	match &ty.type_def {
+		TypeDef::Composite(inner) => {
+			let mut composite = Composite::new(data, Fields::Named(&inner.fields), types);
+			let res = visitor.visit_composite(&mut composite, ty_id)?;
+			*data = composite.finish()?;
+			Ok(res)
+		}
+		TypeDef::Sequence(inner) => {
+			// We assume that the sequence is preceeded by a compact encoded length, so that
+			// we know how many values to try pulling out of the data.
+			let len = CodecCompact::<u64>::decode(data).map_err(DecodeError::from)?.0;
+			let len = usize::try_from(len).map_err(|_| {
+				DecodeError::from(codec::Error::from("Sequence length does not fit into a usize"))
+			})?;
+			let mut sequence = Sequence::new(data, len, &inner.type_param, types);
+			let res = visitor.visit_sequence(&mut sequence, ty_id)?;
+			*data = sequence.finish()?;
+			Ok(res)
+		}
+		TypeDef::Array(inner) => {
+			// The length is known based on the type we want to decode into, so we pull out the number of items according
+			// to that, and don't need a length to exist in the SCALE encoded bytes
+			let mut sequence = Sequence::new(data, inner.len as usize, &inner.type_param, types);
+			let res = visitor.visit_array(&mut sequence, ty_id)?;
+			*data = sequence.finish()?;
+			Ok(res)
+		}
+		TypeDef::Tuple(inner) => {
+			let mut composite = Composite::new(data, Fields::Unnamed(&inner.fields), types);
+			let res = visitor.visit_tuple(&mut composite, ty_id)?;
+			*data = composite.finish()?;
+			Ok(res)
+		}
+		TypeDef::Variant(inner) => {
+			let index = *data.first().ok_or(DecodeError::from(DecodeErrorKind::Eof))?;
+			*data = &data[1..];
+
+			// Does a variant exist with the index we're looking for?
+			let variant = inner
+				.variants
+				.iter()
+				.find(|v| v.index == index)
+				.ok_or_else(|| DecodeErrorKind::VariantNotFound(index, type_name(ty_id, types)))
+				.map_err(DecodeError::from)?;
+
+			let mut variant = Variant {
+				name: &variant.name,
+				index,
+				fields: Composite::new(data, Fields::Named(&variant.fields), types),
+			};
+			let res = visitor.visit_variant(&mut variant, ty_id)?;
+			*data = variant.fields.finish()?;
+			Ok(res)
+		}
+		TypeDef::Primitive(inner) => decode_primitive(data, inner, ty_id, visitor),
+		TypeDef::Compact(inner) => {
+			let compact = Compact::decode(data, &inner.type_param, types)?;
+			visitor.visit_compact(compact, ty_id)
+		}
+		TypeDef::BitSequence(inner) => {
+			let details = get_bitsequence_details(inner, types)
+				.map_err(|e| DecodeError::from(DecodeErrorKind::BitSequenceError(e)))?;
+			let bits = BitSequence::decode_bytes(data, details)?;
+			visitor.visit_bit_sequence(bits, ty_id)
+		}
+	}
+}
+
+/// Move the provided pointer to the data slice forwards past a value of the type
+/// given, without decoding it into anything.
+pub fn skip_type<Id: Into<TypeId>>(
+	data: &mut &[u8],
+	ty_id: Id,
+	types: &PortableRegistry,
+) -> Result<(), DecodeError> {
+	decode_with_visitor(data, ty_id, types, IgnoreVisitor)
+}
+
+fn decode_primitive<V: Visitor>(
+	data: &mut &[u8],
+	ty: &TypeDefPrimitive,
+	ty_id: TypeId,
+	visitor: V,
+) -> Result<V::Value, V::Error> {
+	fn decode<T: Decode>(data: &mut &[u8]) -> Result<T, DecodeError> {
+		T::decode(data).map_err(DecodeError::from)
+	}
+
+	match ty {
+		TypeDefPrimitive::Bool => visitor.visit_bool(decode(data)?, ty_id),
+		TypeDefPrimitive::Char => {
+			// Treat chars as u32's
+			let val: u32 = decode(data)?;
+			let c =
+				char::from_u32(val).ok_or(DecodeError::from(DecodeErrorKind::InvalidChar(val)))?;
+			visitor.visit_char(c, ty_id)
+		}
+		TypeDefPrimitive::Str => {
+			// Strings are a compact encoded length followed by that many bytes of UTF-8.
+			let len = decode::<CodecCompact<u32>>(data)?.0 as usize;
+			if data.len() < len {
+				return Err(DecodeError::from(DecodeErrorKind::Eof).into());
+			}
+			let s = std::str::from_utf8(&data[..len])
+				.map_err(|_| DecodeError::from(codec::Error::from("Invalid UTF-8 in string")))?;
+			*data = &data[len..];
+			visitor.visit_str(s, ty_id)
+		}
+		TypeDefPrimitive::U8 => visitor.visit_u128(decode::<u8>(data)?.into(), ty_id),
+		TypeDefPrimitive::U16 => visitor.visit_u128(decode::<u16>(data)?.into(), ty_id),
+		TypeDefPrimitive::U32 => visitor.visit_u128(decode::<u32>(data)?.into(), ty_id),
+		TypeDefPrimitive::U64 => visitor.visit_u128(decode::<u64>(data)?.into(), ty_id),
+		TypeDefPrimitive::U128 => visitor.visit_u128(decode(data)?, ty_id),
+		TypeDefPrimitive::U256 => visitor.visit_u256(&decode(data)?, ty_id),
+		TypeDefPrimitive::I8 => visitor.visit_i128(decode::<i8>(data)?.into(), ty_id),
+		TypeDefPrimitive::I16 => visitor.visit_i128(decode::<i16>(data)?.into(), ty_id),
+		TypeDefPrimitive::I32 => visitor.visit_i128(decode::<i32>(data)?.into(), ty_id),
+		TypeDefPrimitive::I64 => visitor.visit_i128(decode::<i64>(data)?.into(), ty_id),
+		TypeDefPrimitive::I128 => visitor.visit_i128(decode(data)?, ty_id),
+		TypeDefPrimitive::I256 => visitor.visit_i256(&decode(data)?, ty_id),
+	}
+}
+
+/// The fields of a composite, variant or tuple type.
+#[derive(Copy, Clone)]
+enum Fields<'info> {
+	Named(&'info [Field<PortableForm>]),
+	Unnamed(&'info [ScaleTypeId]),
+}
+
+/// The fields of a composite, tuple or variant value, which can be decoded one at a
+/// time by handing a [`Visitor`] to [`Composite::decode_item`].
+pub struct Composite<'scale, 'info> {
+	bytes: &'scale [u8],
+	fields: Fields<'info>,
+	types: &'info PortableRegistry,
+	next: usize,
+}
+
+impl<'scale, 'info> Composite<'scale, 'info> {
+	fn new(bytes: &'scale [u8], fields: Fields<'info>, types: &'info PortableRegistry) -> Self {
+		Composite { bytes, fields, types, next: 0 }
+	}
+
+	/// The total number of fields.
+	pub fn len(&self) -> usize {
+		match self.fields {
+			Fields::Named(fields) => fields.len(),
+			Fields::Unnamed(fields) => fields.len(),
+		}
+	}
+
+	/// Are there no fields at all?
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The number of fields that have not been decoded yet.
+	pub fn remaining(&self) -> usize {
+		self.len() - self.next
+	}
+
+	/// Do the fields have names? Tuples never have named fields.
+	pub fn is_named(&self) -> bool {
+		match self.fields {
+			Fields::Named(fields) => fields.iter().any(|f| f.name.is_some()),
+			Fields::Unnamed(_) => false,
+		}
+	}
+
+	/// The name of the next field to be decoded, if it has one.
+	pub fn next_field_name(&self) -> Option<&'info str> {
+		match self.fields {
+			Fields::Named(fields) => fields.get(self.next)?.name.as_deref(),
+			Fields::Unnamed(_) => None,
+		}
+	}
+
+	/// Decode the next field using the visitor provided, or return `None` if there
+	/// are no fields left to decode.
+	pub fn decode_item<V: Visitor>(&mut self, visitor: V) -> Option<Result<V::Value, V::Error>> {
+		let ty = match self.fields {
+			Fields::Named(fields) => &fields.get(self.next)?.ty,
+			Fields::Unnamed(fields) => fields.get(self.next)?,
+		};
+		self.next += 1;
+		Some(decode_with_visitor(&mut self.bytes, ty, self.types, visitor))
+	}
+
+	/// Skip over any fields that have not been decoded, returning the bytes that remain.
+	fn finish(mut self) -> Result<&'scale [u8], DecodeError> {
+		while let Some(res) = self.decode_item(IgnoreVisitor) {
+			res?;
+		}
+		Ok(self.bytes)
+	}
+}
+
+/// The items in a sequence or array, which can be decoded one at a time by handing
+/// a [`Visitor`] to [`Sequence::decode_item`].
+pub struct Sequence<'scale, 'info> {
+	bytes: &'scale [u8],
+	len: usize,
+	ty: &'info ScaleTypeId,
+	types: &'info PortableRegistry,
+	next: usize,
+}
+
+impl<'scale, 'info> Sequence<'scale, 'info> {
+	fn new(
+		bytes: &'scale [u8],
+		len: usize,
+		ty: &'info ScaleTypeId,
+		types: &'info PortableRegistry,
+	) -> Self {
+		Sequence { bytes, len, ty, types, next: 0 }
+	}
+
+	/// The total number of items.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Are there no items at all?
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// The number of items that have not been decoded yet.
+	pub fn remaining(&self) -> usize {
+		self.len - self.next
+	}
+
+	/// The type ID of each item.
+	pub fn item_type_id(&self) -> TypeId {
+		self.ty.into()
+	}
+
+	/// Decode the next item using the visitor provided, or return `None` if there
+	/// are no items left to decode.
+	pub fn decode_item<V: Visitor>(&mut self, visitor: V) -> Option<Result<V::Value, V::Error>> {
+		if self.next == self.len {
+			return None;
+		}
+		self.next += 1;
+		Some(decode_with_visitor(&mut self.bytes, self.ty, self.types, visitor))
+	}
+
+	/// Skip over any items that have not been decoded, returning the bytes that remain.
+	fn finish(mut self) -> Result<&'scale [u8], DecodeError> {
+		while let Some(res) = self.decode_item(IgnoreVisitor) {
+			res?;
+		}
+		Ok(self.bytes)
+	}
+}
+
+/// An enum variant, whose fields can be decoded via [`Variant::fields`].
+pub struct Variant<'scale, 'info> {
+	name: &'info str,
+	index: u8,
+	fields: Composite<'scale, 'info>,
+}
+
+impl<'scale, 'info> Variant<'scale, 'info> {
+	/// The name of the variant.
+	pub fn name(&self) -> &'info str {
+		self.name
+	}
+
+	/// The index of the variant.
+	pub fn index(&self) -> u8 {
+		self.index
+	}
+
+	/// The fields of the variant.
+	pub fn fields(&mut self) -> &mut Composite<'scale, 'info> {
+		&mut self.fields
+	}
+}
+
+/// A compact encoded integer. The integer may be wrapped in one or more single-field
+/// structs; [`Compact::locations`] describes these.
+pub struct Compact<'info> {
+	value: u128,
+	ty: &'info ScaleTypeId,
+	types: &'info PortableRegistry,
+}
+
+/// One step in the path from a compact encoded type to the integer inside it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CompactLocation<'info> {
+	/// A struct with a single named field, which contains the next location.
+	Named(&'info str, TypeId),
+	/// A struct with a single unnamed field, which contains the next location.
+	Unnamed(TypeId),
+	/// The primitive integer type at the end of the path.
+	Primitive(TypeId),
+}
+
+impl<'info> Compact<'info> {
+	fn decode(
+		data: &mut &[u8],
+		ty: &'info ScaleTypeId,
+		types: &'info PortableRegistry,
+	) -> Result<Self, DecodeError> {
+		use TypeDefPrimitive::*;
+
+		// Find the primitive type inside the compact encoded type (or fail if
+		// we hit some type we wouldn't know how to decode).
+		let mut inner_id = ty.id;
+		let value = loop {
+			let inner = types.resolve(inner_id).ok_or(DecodeErrorKind::TypeIdNotFound(inner_id))?;
+			break match &inner.type_def {
+				// It's obvious how to decode basic primitive unsigned types, since we have impls for them.
+				TypeDef::Primitive(U8) => CodecCompact::<u8>::decode(data)?.0.into(),
+				TypeDef::Primitive(U16) => CodecCompact::<u16>::decode(data)?.0.into(),
+				TypeDef::Primitive(U32) => CodecCompact::<u32>::decode(data)?.0.into(),
+				TypeDef::Primitive(U64) => CodecCompact::<u64>::decode(data)?.0.into(),
+				TypeDef::Primitive(U128) => CodecCompact::<u128>::decode(data)?.0,
+				// A struct with exactly 1 field containing one of the above types can be sensibly compact encoded/decoded.
+				TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+					inner_id = composite.fields[0].ty.id;
+					continue;
+				}
+				// For now, we give up if we have been asked for any other type:
+				_cannot_decode_from => {
+					let name = type_name(inner_id, types);
+					return Err(DecodeErrorKind::CannotDecodeCompactIntoType(name).into());
+				}
+			};
+		};
+
+		Ok(Compact { value, ty, types })
+	}
+
+	/// The integer value.
+	pub fn value(&self) -> u128 {
+		self.value
+	}
+
+	/// Iterate over the types between the compact encoded type and the integer value,
+	/// starting from the outermost type.
+	pub fn locations(&self) -> impl Iterator<Item = CompactLocation<'info>> {
+		let types = self.types;
+		let mut next_id = Some(self.ty.id);
+		std::iter::from_fn(move || {
+			let id = next_id.take()?;
+			let location = match &types.resolve(id)?.type_def {
+				TypeDef::Composite(composite) => {
+					let field = composite.fields.first()?;
+					next_id = Some(field.ty.id);
+					match &field.name {
+						Some(name) => CompactLocation::Named(name, id.into()),
+						None => CompactLocation::Unnamed(id.into()),
+					}
+				}
+				_ => CompactLocation::Primitive(id.into()),
+			};
+			Some(location)
+		})
+	}
+}
+
+/// A SCALE encoded bit sequence, which can be decoded into a [`crate::BitSequence`]
+/// via [`BitSequence::decode`] if needed.
+pub struct BitSequence<'scale> {
+	bytes: &'scale [u8],
+	len: usize,
+	details: (BitStoreTy, BitOrderTy),
+}
+
+impl<'scale> BitSequence<'scale> {
+	fn decode_bytes(
+		data: &mut &'scale [u8],
+		details: (BitStoreTy, BitOrderTy),
+	) -> Result<Self, DecodeError> {
+		// A compact encoded number of bits comes first, followed by the
+		// store elements needed to hold them.
+		let mut cursor = *data;
+		let len = CodecCompact::<u32>::decode(&mut cursor)?.0 as usize;
+		let store_bytes = match details.0 {
+			BitStoreTy::U8 => 1,
+			BitStoreTy::U16 => 2,
+			BitStoreTy::U32 => 4,
+			BitStoreTy::U64 => 8,
+		};
+		let num_bytes = (data.len() - cursor.len()) + len.div_ceil(store_bytes * 8) * store_bytes;
+		if data.len() < num_bytes {
+			return Err(DecodeErrorKind::Eof.into());
+		}
+
+		let bytes = &data[..num_bytes];
+		*data = &data[num_bytes..];
+		Ok(BitSequence { bytes, len, details })
+	}
+
+	/// The number of bits in the sequence.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Is the sequence empty?
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// The raw SCALE encoded bytes of the bit sequence.
+	pub fn bytes(&self) -> &'scale [u8] {
+		self.bytes
+	}
+
+	/// Decode the bits into a [`crate::BitSequence`].
+	pub fn decode(&self) -> Result<crate::BitSequence, DecodeError> {
+		fn to_bit_sequence<S: BitStore, O: BitOrder>(bits: BitVec<S, O>) -> crate::BitSequence {
+			bits.iter().by_vals().collect()
+		}
+
+		let data = &mut &*self.bytes;
+
+		// Decode the native BitSequence type easily, or else convert to it from the type given.
+		let bits = match self.details {
+			(BitStoreTy::U8, BitOrderTy::Lsb0) => BitVec::<u8, Lsb0>::decode(data)?,
+			(BitStoreTy::U8, BitOrderTy::Msb0) => {
+				to_bit_sequence(BitVec::<u8, Msb0>::decode(data)?)
+			}
+			(BitStoreTy::U16, BitOrderTy::Lsb0) => {
+				to_bit_sequence(BitVec::<u16, Lsb0>::decode(data)?)
+			}
+			(BitStoreTy::U16, BitOrderTy::Msb0) => {
+				to_bit_sequence(BitVec::<u16, Msb0>::decode(data)?)
+			}
+			(BitStoreTy::U32, BitOrderTy::Lsb0) => {
+				to_bit_sequence(BitVec::<u32, Lsb0>::decode(data)?)
+			}
+			(BitStoreTy::U32, BitOrderTy::Msb0) => {
+				to_bit_sequence(BitVec::<u32, Msb0>::decode(data)?)
+			}
+			// BitVec doesn't impl BitStore on u64 if pointer width isn't 64 bit, avoid using this store type here
+			// in that case to avoid compile errors (see https://docs.rs/bitvec/1.0.0/src/bitvec/store.rs.html#184)
+			#[cfg(not(feature = "32bit_target"))]
+			(BitStoreTy::U64, BitOrderTy::Lsb0) => to_bit_sequence(BitVec::<u64, Lsb0>::decode(data)?),
+			#[cfg(not(feature = "32bit_target"))]
+			(BitStoreTy::U64, BitOrderTy::Msb0) => to_bit_sequence(BitVec::<u64, Msb0>::decode(data)?),
+			#[cfg(feature = "32bit_target")]
+			(BitStoreTy::U64, _) => {
+				return Err(DecodeErrorKind::BitSequenceError(
+					super::bit_sequence::BitSequenceError::StoreTypeNotSupported(
+						"u64 (pointer-width on this compile target is not 64)".into(),
+					),
+				)
+				.into())
+			}
+		};
+
+		Ok(bits)
+	}
+}
+
+/// A visitor which accepts anything and does nothing with it. This is used
+/// to skip over values that visitors don't decode themselves.
+struct IgnoreVisitor;
+
+impl Visitor for IgnoreVisitor {
+	type Value = ();
+	type Error = DecodeError;
+
+	fn visit_bool(self, _value: bool, _type_id: TypeId) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_char(self, _value: char, _type_id: TypeId) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_str(self, _value: &str, _type_id: TypeId) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_u128(self, _value: u128, _type_id: TypeId) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_i128(self, _value: i128, _type_id: TypeId) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_u256(self, _value: &[u8; 32], _type_id: TypeId) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_i256(self, _value: &[u8; 32], _type_id: TypeId) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_compact(self, _value: Compact<'_>, _type_id: TypeId) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_sequence(
+		self,
+		_value: &mut Sequence<'_, '_>,
+		_type_id: TypeId,
+	) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_array(
+		self,
+		_value: &mut Sequence<'_, '_>,
+		_type_id: TypeId,
+	) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_tuple(
+		self,
+		_value: &mut Composite<'_, '_>,
+		_type_id: TypeId,
+	) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_composite(
+		self,
+		_value: &mut Composite<'_, '_>,
+		_type_id: TypeId,
+	) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_variant(
+		self,
+		_value: &mut Variant<'_, '_>,
+		_type_id: TypeId,
+	) -> Result<(), DecodeError> {
+		Ok(())
+	}
+	fn visit_bit_sequence(
+		self,
+		_value: BitSequence<'_>,
+		_type_id: TypeId,
+	) -> Result<(), DecodeError> {
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use codec::Encode;
+
+	/// Given a type definition, return the PortableType and PortableRegistry
+	/// that our decode functions expect.
+	fn make_type<T: scale_info::TypeInfo + 'static>() -> (TypeId, PortableRegistry) {
+		let m = scale_info::MetaType::new::<T>();
+		let mut types = scale_info::Registry::new();
+		let id = types.register_type(&m);
+		let portable_registry: PortableRegistry = types.into();
+
+		(id.into(), portable_registry)
+	}
+
+	/// A visitor which sums up any unsigned integers it finds, decoding into
+	/// composites, sequences and variants to find them.
+	struct SumVisitor;
+
+	impl Visitor for SumVisitor {
+		type Value = u128;
+		type Error = DecodeError;
+
+		fn visit_u128(self, value: u128, _type_id: TypeId) -> Result<u128, DecodeError> {
+			Ok(value)
+		}
+		fn visit_compact(self, value: Compact<'_>, _type_id: TypeId) -> Result<u128, DecodeError> {
+			Ok(value.value())
+		}
+		fn visit_sequence(
+			self,
+			value: &mut Sequence<'_, '_>,
+			_type_id: TypeId,
+		) -> Result<u128, DecodeError> {
+			let mut sum = 0;
+			while let Some(n) = value.decode_item(SumVisitor) {
+				sum += n?;
+			}
+			Ok(sum)
+		}
+		fn visit_composite(
+			self,
+			value: &mut Composite<'_, '_>,
+			_type_id: TypeId,
+		) -> Result<u128, DecodeError> {
+			let mut sum = 0;
+			while let Some(n) = value.decode_item(SumVisitor) {
+				sum += n?;
+			}
+			Ok(sum)
+		}
+		fn visit_tuple(
+			self,
+			value: &mut Composite<'_, '_>,
+			type_id: TypeId,
+		) -> Result<u128, DecodeError> {
+			self.visit_composite(value, type_id)
+		}
+		fn visit_variant(
+			self,
+			value: &mut Variant<'_, '_>,
+			type_id: TypeId,
+		) -> Result<u128, DecodeError> {
+			self.visit_composite(value.fields(), type_id)
+		}
+	}
+
+	/// A visitor which only looks at the first field of a composite.
+	struct FirstFieldVisitor;
+
+	impl Visitor for FirstFieldVisitor {
+		type Value = Option<String>;
+		type Error = DecodeError;
+
+		fn visit_str(self, value: &str, _type_id: TypeId) -> Result<Self::Value, DecodeError> {
+			Ok(Some(value.to_string()))
+		}
+		fn visit_composite(
+			self,
+			value: &mut Composite<'_, '_>,
+			_type_id: TypeId,
+		) -> Result<Self::Value, DecodeError> {
+			assert_eq!(value.next_field_name(), Some("name"));
+			value.decode_item(FirstFieldVisitor).transpose().map(Option::flatten)
+		}
+	}
+
+	#[derive(Encode, scale_info::TypeInfo)]
+	enum MyEnum {
+		Foo { a: u8, b: Vec<u64> },
+	}
+
+	#[derive(Encode, scale_info::TypeInfo)]
+	struct MyStruct {
+		name: String,
+		values: Vec<u16>,
+		e: MyEnum,
+		compact: codec::Compact<u32>,
+		bits: bitvec::vec::BitVec<u16, bitvec::order::Msb0>,
+	}
+
+	fn my_struct() -> MyStruct {
+		MyStruct {
+			name: "hello".to_string(),
+			values: vec![1, 2, 3],
+			e: MyEnum::Foo { a: 4, b: vec![5, 6] },
+			compact: codec::Compact(7),
+			bits: bitvec::bitvec![u16, bitvec::order::Msb0; 1; 20],
+		}
+	}
+
+	#[test]
+	fn visitor_can_walk_nested_values() {
+		let value = (vec![1u16, 2, 3], MyEnum::Foo { a: 4, b: vec![5, 6] }, codec::Compact(7u32));
+		let encoded = value.encode();
+		let (id, types) = make_type::<(Vec<u16>, MyEnum, codec::Compact<u32>)>();
+
+		// SumVisitor descends into the sequence, variant and compact value to find numbers:
+		let data = &mut &*encoded;
+		let sum = decode_with_visitor(data, id, &types, SumVisitor).unwrap();
+		assert_eq!(sum, 1 + 2 + 3 + 4 + 5 + 6 + 7);
+		assert!(data.is_empty(), "every byte should be consumed");
+	}
+
+	#[test]
+	fn visitor_can_reject_values() {
+		let encoded = my_struct().encode();
+		let (id, types) = make_type::<MyStruct>();
+
+		// The string isn't something SumVisitor expects:
+		let data = &mut &*encoded;
+		let err = decode_with_visitor(data, id, &types, SumVisitor).unwrap_err();
+		assert!(matches!(err.kind, DecodeErrorKind::UnexpectedValue("str", _)));
+	}
+
+	#[test]
+	fn visitor_skips_undecoded_values() {
+		let mut encoded = my_struct().encode();
+		encoded.push(123);
+		let (id, types) = make_type::<MyStruct>();
+
+		let data = &mut &*encoded;
+		let name = decode_with_visitor(data, id, &types, FirstFieldVisitor).unwrap();
+
+		assert_eq!(name, Some("hello".to_string()));
+		assert_eq!(*data, &[123], "every byte of the struct should be consumed");
+	}
+
+	/// Encode a value, append some trailing bytes, and check that skipping
+	/// over the type leaves exactly those trailing bytes behind.
+	fn assert_skips<T: Encode + scale_info::TypeInfo + 'static>(val: T) {
+		let mut encoded = val.encode();
+		encoded.extend([1, 2, 3]);
+		let (id, types) = make_type::<T>();
+
+		let data = &mut &*encoded;
+		skip_type(data, id, &types).expect("can skip type");
+		assert_eq!(*data, &[1, 2, 3], "only the value's bytes should be skipped");
+	}
+
+	#[test]
+	fn skip_types() {
+		assert_skips(true);
+		assert_skips("hello".to_string());
+		assert_skips(123u8);
+		assert_skips(-123i64);
+		assert_skips(u128::MAX);
+		assert_skips([1u16, 2, 3]);
+		assert_skips(vec![1u32, 2, 3]);
+		assert_skips((1u8, "hi".to_string(), vec![true, false]));
+		assert_skips(Some(vec!["a".to_string()]));
+		assert_skips(None::<u64>);
+		assert_skips(codec::Compact(u64::MAX));
+		assert_skips(bitvec::bitvec![u8, bitvec::order::Lsb0; 1, 0, 1]);
+		assert_skips(bitvec::bitvec![u32, bitvec::order::Msb0; 1; 40]);
+		assert_skips(my_struct());
+	}
+
+	#[test]
+	fn skip_type_fails_on_too_few_bytes() {
+		let encoded = my_struct().encode();
+		let (id, types) = make_type::<MyStruct>();
+
+		let data = &mut &encoded[..encoded.len() - 1];
+		assert!(skip_type(data, id, &types).is_err());
+	}
+
+	#[test]
+	fn visitor_sums_unsigned_integers() {
+		#[derive(Encode, scale_info::TypeInfo)]
+		struct Numbers {
+			values: Vec<u16>,
+			e: MyEnum,
+			compact: codec::Compact<u32>,
+			arr: [u8; 2],
+		}
+
+		let encoded = Numbers {
+			values: vec![1, 2, 3],
+			e: MyEnum::Foo { a: 4, b: vec![5, 6] },
+			compact: codec::Compact(7),
+			arr: [8, 9],
+		}
+		.encode();
+		let (id, types) = make_type::<Numbers>();
+
+		// Arrays aren't expected by SumVisitor:
+		let data = &mut &*encoded;
+		let err = decode_with_visitor(data, id, &types, SumVisitor).unwrap_err();
+		assert!(matches!(err.kind, DecodeErrorKind::UnexpectedValue("array", _)));
+
+		// Until we are only visiting the bits we expect:
+		let data = &mut &encoded[..encoded.len() - 2];
+		let (id, types) = make_type::<(Vec<u16>, MyEnum, codec::Compact<u32>)>();
+		assert_eq!(decode_with_visitor(data, id, &types, SumVisitor), Ok(28));
+		assert!(data.is_empty());
+	}
+}
@@ -107,9 +107,9 @@ impl Serializer for ValueSerializer {
 		Ok(Value::variant("None".to_string(), Composite::Unnamed(Vec::new())))
 	}
 
-	fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		let inner = value.serialize(ValueSerializer)?;
 		Ok(Value::variant("Some".to_string(), Composite::Unnamed(vec![inner])))
@@ -132,19 +132,19 @@ impl Serializer for ValueSerializer {
 		Ok(Value::variant(variant.to_string(), Composite::Unnamed(Vec::new())))
 	}
 
-	fn serialize_newtype_struct<T: ?Sized>(
+	fn serialize_newtype_struct<T>(
 		self,
 		_name: &'static str,
 		value: &T,
 	) -> Result<Self::Ok, Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		let inner = value.serialize(ValueSerializer)?;
 		Ok(Value::unnamed_composite(vec![inner]))
 	}
 
-	fn serialize_newtype_variant<T: ?Sized>(
+	fn serialize_newtype_variant<T>(
 		self,
 		_name: &'static str,
 		_variant_index: u32,
@@ -152,7 +152,7 @@ impl Serializer for ValueSerializer {
 		value: &T,
 	) -> Result<Self::Ok, Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		let inner = value.serialize(ValueSerializer)?;
 		Ok(Value::variant(variant.to_string(), Composite::Unnamed(vec![inner])))
@@ -223,9 +223,9 @@ impl UnnamedCompositeSerializer {
 		UnnamedCompositeSerializer { variant_name: Some(variant_name), values: Vec::new() }
 	}
 
-	fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), SerializerError>
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerializerError>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		let inner = value.serialize(ValueSerializer)?;
 		self.values.push(inner);
@@ -244,9 +244,9 @@ impl SerializeSeq for UnnamedCompositeSerializer {
 	type Ok = Value<()>;
 	type Error = SerializerError;
 
-	fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		self.serialize_element(value)
 	}
@@ -260,9 +260,9 @@ impl SerializeTuple for UnnamedCompositeSerializer {
 	type Ok = Value<()>;
 	type Error = SerializerError;
 
-	fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		self.serialize_element(value)
 	}
@@ -276,9 +276,9 @@ impl SerializeTupleStruct for UnnamedCompositeSerializer {
 	type Ok = Value<()>;
 	type Error = SerializerError;
 
-	fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		self.serialize_element(value)
 	}
@@ -292,9 +292,9 @@ impl SerializeTupleVariant for UnnamedCompositeSerializer {
 	type Ok = Value<()>;
 	type Error = SerializerError;
 
-	fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		self.serialize_element(value)
 	}
@@ -321,9 +321,9 @@ impl NamedCompositeSerializer {
 		NamedCompositeSerializer { variant_name: Some(variant_name), values: Vec::new(), key: None }
 	}
 
-	fn serialize_field<T: ?Sized>(&mut self, key: &str, value: &T) -> Result<(), SerializerError>
+	fn serialize_field<T>(&mut self, key: &str, value: &T) -> Result<(), SerializerError>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		let key = key.to_string();
 		let inner = value.serialize(ValueSerializer)?;
@@ -343,9 +343,9 @@ impl SerializeMap for NamedCompositeSerializer {
 	type Ok = Value<()>;
 	type Error = SerializerError;
 
-	fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+	fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		let inner = key.serialize(ValueSerializer)?;
 		// Map keys must be stringish, because named composite values are strings
@@ -360,9 +360,9 @@ impl SerializeMap for NamedCompositeSerializer {
 		Ok(())
 	}
 
-	fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+	fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		let key = self.key.take().expect("serialize_key must be called prior to serialize_value");
 		self.serialize_field(&key, value)
@@ -377,13 +377,9 @@ impl SerializeStruct for NamedCompositeSerializer {
 	type Ok = Value<()>;
 	type Error = SerializerError;
 
-	fn serialize_field<T: ?Sized>(
-		&mut self,
-		key: &'static str,
-		value: &T,
-	) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		self.serialize_field(key, value)
 	}
@@ -397,13 +393,9 @@ impl SerializeStructVariant for NamedCompositeSerializer {
 	type Ok = Value<()>;
 	type Error = SerializerError;
 
-	fn serialize_field<T: ?Sized>(
-		&mut self,
-		key: &'static str,
-		value: &T,
-	) -> Result<(), Self::Error>
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
 	where
-		T: serde::Serialize,
+		T: ?Sized + serde::Serialize,
 	{
 		self.serialize_field(key, value)
 	}
@@ -573,9 +565,10 @@ mod test {
 
 		let m = {
 			let mut m = HashMap::new();
-			m.insert("a", 1u8);
-			m.insert("b", 2u8);
-			m.insert("c", 3u8);
+			m.insert("a".to_string(), 1u8);
+			m.insert("b".to_string(), 2u8);
+			m.insert("c".to_string(), 3u8);
+			m
 		};
 		assert_ser_de(m);
 
@@ -585,6 +578,7 @@ mod test {
 			m.insert('a', 1u8);
 			m.insert('b', 2u8);
 			m.insert('c', 3u8);
+			m
 		};
 		assert_ser_de(m);
 	}
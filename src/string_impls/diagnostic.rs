@@ -0,0 +1,399 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::from_string::{
+	ParseBitSequenceError, ParseBytesError, ParseCharError, ParseComplexError, ParseError,
+	ParseErrorKind, ParseNumberError, ParseStringError,
+};
+use std::fmt::Write;
+
+/// Tabs in the input are shown as this many spaces, so that we can line up underlines.
+const TAB_WIDTH: usize = 4;
+
+/// A line and column in some string, both counting from 1. Columns count chars rather
+/// than bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColumn {
+	/// The line number, starting from 1.
+	pub line: usize,
+	/// The column number, starting from 1.
+	pub column: usize,
+}
+
+impl LineColumn {
+	/// Find the line and column of the given byte offset into some string.
+	pub fn from_offset(input: &str, offset: usize) -> LineColumn {
+		let before = &input[..floor_char_boundary(input, offset)];
+		let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+		LineColumn {
+			line: before.matches('\n').count() + 1,
+			column: before[line_start..].chars().count() + 1,
+		}
+	}
+}
+
+impl std::fmt::Display for LineColumn {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}", self.line, self.column)
+	}
+}
+
+impl ParseError {
+	/// The line and column in the given input string at which this error begins.
+	pub fn start_line_column(&self, input: &str) -> LineColumn {
+		LineColumn::from_offset(input, self.start_loc)
+	}
+
+	/// The line and column in the given input string at which this error ends, if known.
+	pub fn end_line_column(&self, input: &str) -> Option<LineColumn> {
+		self.end_loc.map(|end| LineColumn::from_offset(input, end))
+	}
+
+	/// Render this error along with the part of the input string that it points to,
+	/// and any hints about how to fix it. The input should be the same string that was
+	/// being parsed when the error occurred. The output looks like:
+	///
+	/// ```text
+	/// error: Missing closing '}'
+	///  --> 3:3
+	///   |
+	/// 1 | {
+	///   | - unclosed `{` opened here
+	/// 2 |   a: 1
+	/// 3 |   b: 2
+	///   |   ^
+	///   = help: did you forget a comma?
+	/// ```
+	pub fn render(&self, input: &str) -> String {
+		let start = self.start_line_column(input);
+		let end = self.end_line_column(input);
+
+		// Underline the span on the line that it starts on, and anything related to it:
+		let underline_len = match end {
+			Some(end) if end.line == start.line => end.column.saturating_sub(start.column).max(1),
+			Some(_) => line_len(input, start.line).saturating_sub(start.column - 1).max(1),
+			None => 1,
+		};
+		let mut annotations =
+			vec![Annotation { at: start, len: underline_len, marker: '^', label: None }];
+		if let Some((loc, label)) = self.related(input) {
+			annotations.push(Annotation { at: loc, len: 1, marker: '-', label: Some(label) });
+		}
+		annotations.sort_by_key(|a| (a.at.line, a.at.column));
+
+		let first_line = annotations[0].at.line;
+		let last_line = annotations[annotations.len() - 1].at.line;
+		let gutter = last_line.to_string().len();
+		let lines: Vec<&str> = input.split('\n').collect();
+
+		let mut out = String::new();
+		let _ = writeln!(out, "error: {}", self.err);
+		let _ = writeln!(out, "{:gutter$}--> {start}", "");
+		let _ = writeln!(out, "{:gutter$} |", "");
+
+		let mut prev_line = None;
+		for line_no in first_line..=last_line {
+			let on_line: Vec<&Annotation> =
+				annotations.iter().filter(|a| a.at.line == line_no).collect();
+			// Show a few lines between annotations for context, but not too many:
+			if on_line.is_empty() && last_line - line_no > 1 {
+				if prev_line == Some(line_no - 1) {
+					out.push_str("...\n");
+				}
+				continue;
+			}
+			prev_line = Some(line_no);
+
+			let line = lines.get(line_no - 1).copied().unwrap_or("").trim_end_matches('\r');
+			let _ = writeln!(out, "{line_no:>gutter$} | {}", expand_tabs(line));
+			if on_line.is_empty() {
+				continue;
+			}
+
+			let mut underline = String::new();
+			let mut label = None;
+			for (idx, annotation) in on_line.iter().enumerate() {
+				let col = display_width(line, annotation.at.column - 1);
+				let width = display_width(line, annotation.at.column - 1 + annotation.len) - col;
+				pad_to(&mut underline, col);
+				underline.extend(std::iter::repeat_n(annotation.marker, width.max(1)));
+				if let Some(l) = &annotation.label {
+					label = Some((col, l, idx == on_line.len() - 1));
+				}
+			}
+			match label {
+				// Labels go after the underline if there's nothing after them on the line..
+				Some((_, label, true)) => {
+					let _ = writeln!(out, "{:gutter$} | {underline} {label}", "");
+				}
+				// ..else they go on the next line, lined up with the thing they're labelling.
+				Some((col, label, false)) => {
+					let _ = writeln!(out, "{:gutter$} | {underline}", "");
+					let mut indent = String::new();
+					pad_to(&mut indent, col);
+					let _ = writeln!(out, "{:gutter$} | {indent}{label}", "");
+				}
+				None => {
+					let _ = writeln!(out, "{:gutter$} | {underline}", "");
+				}
+			}
+		}
+
+		if let Some(hint) = self.hint(input) {
+			let _ = writeln!(out, "{:gutter$} = help: {hint}", "");
+		}
+		out
+	}
+
+	/// Some other location that's relevant to the error, like the opening bracket
+	/// that we expected to find a closing bracket for.
+	fn related(&self, input: &str) -> Option<(LineColumn, String)> {
+		let (opened_at, opener) = match &self.err {
+			ParseErrorKind::Complex(ParseComplexError::ExpectedCloserToMatch(closer, pos)) => {
+				(*pos, if *closer == '}' { '{' } else { '(' })
+			}
+			ParseErrorKind::String(ParseStringError::ExpectedClosingQuoteToMatch(pos)) => {
+				(*pos, '"')
+			}
+			ParseErrorKind::Char(ParseCharError::ExpectedClosingQuoteToMatch(pos)) => (*pos, '\''),
+			ParseErrorKind::BitSequence(ParseBitSequenceError::ExpectedClosingBracketToMatch(
+				pos,
+			)) => (*pos, '<'),
+			_ => return None,
+		};
+		let label = format!("unclosed `{opener}` opened here");
+		Some((LineColumn::from_offset(input, opened_at), label))
+	}
+
+	/// A suggestion for how to fix the error, if we have one.
+	fn hint(&self, input: &str) -> Option<String> {
+		let rest = input.get(self.start_loc..).unwrap_or("");
+		let found = rest.chars().next();
+		// Block comments that are never closed aren't skipped, so we stop at them.
+		if rest.starts_with("/*") {
+			return Some("this comment is never closed; add a `*/` to end it".to_string());
+		}
+		let hint = match &self.err {
+			ParseErrorKind::Complex(ParseComplexError::ExpectedCloserToMatch(closer, _)) => {
+				match found {
+					None => format!("add a `{closer}` to close this"),
+					Some(c @ ('}' | ')' | '>' | ']')) => {
+						format!("expected a `{closer}` here, but found `{c}`")
+					}
+					Some(_) => "did you forget a comma?".to_string(),
+				}
+			}
+			ParseErrorKind::Complex(ParseComplexError::MissingFieldSeparator(sep)) => {
+				format!("field names should be followed by a `{sep}` and then a value")
+			}
+			ParseErrorKind::Complex(
+				ParseComplexError::InvalidFieldName
+				| ParseComplexError::InvalidStartingCharacterInIdent,
+			) => "field names look like `foo` or `\"foo bar\"`".to_string(),
+			ParseErrorKind::String(ParseStringError::ExpectedValidEscapeCode)
+			| ParseErrorKind::Char(ParseCharError::ExpectedValidEscapeCode) => {
+				"valid escape codes are `\\n`, `\\t`, `\\r`, `\\0`, `\\\\`, `\\\"`, `\\'`, \
+				 `\\x7F` for ASCII chars and `\\u{1F600}` for any char"
+					.to_string()
+			}
+			ParseErrorKind::String(ParseStringError::ExpectedClosingQuoteToMatch(_))
+			| ParseErrorKind::Char(ParseCharError::ExpectedClosingQuoteToMatch(_))
+				if found.is_none() =>
+			{
+				"add a closing quote".to_string()
+			}
+			ParseErrorKind::Char(ParseCharError::ExpectedClosingQuoteToMatch(_)) => {
+				"chars hold a single character; use double quotes for strings".to_string()
+			}
+			ParseErrorKind::BitSequence(_) => {
+				"bit sequences look like `<0110>`, and contain only 0s and 1s".to_string()
+			}
+			ParseErrorKind::Bytes(ParseBytesError::OddNumberOfHexDigits) => {
+				"each byte needs two hex digits; try adding a leading 0".to_string()
+			}
+			ParseErrorKind::Number(ParseNumberError::LosesPrecision) => {
+				"this has more decimal places than the number or unit can represent".to_string()
+			}
+			ParseErrorKind::ExpectedValue if found.is_none() => {
+				"the input ended before a value was found".to_string()
+			}
+			_ => return None,
+		};
+		Some(hint)
+	}
+}
+
+/// Something to underline in the rendered output.
+struct Annotation {
+	at: LineColumn,
+	len: usize,
+	marker: char,
+	label: Option<String>,
+}
+
+/// The number of chars on the given line (counting from 1).
+fn line_len(input: &str, line: usize) -> usize {
+	input.split('\n').nth(line - 1).map(|l| l.chars().count()).unwrap_or(0)
+}
+
+/// How wide the first `chars` chars of the line will be once tabs are expanded.
+fn display_width(line: &str, chars: usize) -> usize {
+	let mut width = 0;
+	for c in line.chars().take(chars) {
+		width += if c == '\t' { TAB_WIDTH } else { 1 };
+	}
+	// Positions past the end of the line (ie at the end of the input) are one char each.
+	width + chars.saturating_sub(line.chars().count())
+}
+
+fn pad_to(s: &mut String, width: usize) {
+	while s.chars().count() < width {
+		s.push(' ');
+	}
+}
+
+fn expand_tabs(line: &str) -> String {
+	line.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// Offsets may land part way through a multi-byte char; round them down to the start of it.
+fn floor_char_boundary(input: &str, offset: usize) -> usize {
+	let mut offset = offset.min(input.len());
+	while !input.is_char_boundary(offset) {
+		offset -= 1;
+	}
+	offset
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::stringify::from_str;
+
+	fn render_err(s: &str) -> String {
+		from_str(s).0.expect_err("should fail to parse").render(s)
+	}
+
+	#[test]
+	fn line_columns() {
+		let s = "{\n  a: 1,\n\tb: 😀,\n}";
+		assert_eq!(LineColumn::from_offset(s, 0), LineColumn { line: 1, column: 1 });
+		assert_eq!(LineColumn::from_offset(s, 1), LineColumn { line: 1, column: 2 });
+		assert_eq!(LineColumn::from_offset(s, 2), LineColumn { line: 2, column: 1 });
+		assert_eq!(LineColumn::from_offset(s, 5), LineColumn { line: 2, column: 4 });
+		// The emoji is 4 bytes long, but one column wide:
+		let after_emoji = s.find('😀').unwrap() + 4;
+		assert_eq!(LineColumn::from_offset(s, after_emoji), LineColumn { line: 3, column: 6 });
+		assert_eq!(LineColumn::from_offset(s, after_emoji - 2), LineColumn { line: 3, column: 5 });
+		assert_eq!(LineColumn::from_offset(s, 1000), LineColumn { line: 4, column: 2 });
+
+		let err = from_str("{\n  a: 1\n  b: 2\n}").0.unwrap_err();
+		assert_eq!(err.start_line_column("{\n  a: 1\n  b: 2\n}").to_string(), "3:3");
+	}
+
+	#[test]
+	fn renders_missing_comma() {
+		assert_eq!(
+			render_err("{\n  a: 1\n  b: 2\n}"),
+			"\
+error: Missing closing '}'
+ --> 3:3
+  |
+1 | {
+  | - unclosed `{` opened here
+2 |   a: 1
+3 |   b: 2
+  |   ^
+  = help: did you forget a comma?
+"
+		);
+	}
+
+	#[test]
+	fn renders_errors_on_one_line() {
+		assert_eq!(
+			render_err("(1, 2 3)"),
+			"\
+error: Missing closing ')'
+ --> 1:7
+  |
+1 | (1, 2 3)
+  | -     ^
+  | unclosed `(` opened here
+  = help: did you forget a comma?
+"
+		);
+		assert_eq!(
+			render_err("\"hello\\q\""),
+			"\
+error: Expected an escape code to follow the '\\'
+ --> 1:8
+  |
+1 | \"hello\\q\"
+  |        ^
+  = help: valid escape codes are `\\n`, `\\t`, `\\r`, `\\0`, `\\\\`, `\\\"`, `\\'`, `\\x7F` for ASCII chars and `\\u{1F600}` for any char
+"
+		);
+	}
+
+	#[test]
+	fn renders_unclosed_things_far_away() {
+		let s = "Foo {\n\ta: 1,\n\tb: 2,\n\tc: 3,\n\td: \"hello";
+		assert_eq!(
+			render_err(s),
+			"\
+error: Expected a closing quote to match the opening quote at position 31
+ --> 5:11
+  |
+5 |     d: \"hello
+  |        -     ^
+  |        unclosed `\"` opened here
+  = help: add a closing quote
+"
+		);
+
+		let s = "Foo {\n\ta: 1,\n\tb: 2,\n\tc: 3,\n\td: 4";
+		assert_eq!(
+			render_err(s),
+			"\
+error: Missing closing '}'
+ --> 5:6
+  |
+1 | Foo {
+  |     - unclosed `{` opened here
+...
+4 |     c: 3,
+5 |     d: 4
+  |         ^
+  = help: add a `}` to close this
+"
+		);
+	}
+
+	#[test]
+	fn renders_unclosed_comments() {
+		assert_eq!(
+			render_err("(1, /* two"),
+			"\
+error: Expected a value
+ --> 1:5
+  |
+1 | (1, /* two
+  |     ^
+  = help: this comment is never closed; add a `*/` to end it
+"
+		);
+	}
+}
@@ -16,17 +16,108 @@
 #![allow(clippy::enum_variant_names)]
 
 use super::string_helpers;
-use crate::value::{BitSequence, Composite, Primitive, Value, Variant};
-use std::num::ParseIntError;
+use crate::value::{BitSequence, Composite, Primitive, Value, ValueDef, Variant};
+use std::num::{IntErrorKind, ParseIntError};
 use yap::{IntoTokens, TokenLocation, Tokens};
 
 pub fn from_str(s: &str) -> (Result<Value<()>, ParseError>, &str) {
+	from_str_with_options(s, &ParseOptions::default())
+}
+
+pub fn from_str_with_options<'a>(
+	s: &'a str,
+	opts: &ParseOptions,
+) -> (Result<Value<()>, ParseError>, &'a str) {
 	let mut toks = s.into_tokens();
-	let res = parse_value(&mut toks);
+	skip_whitespace(&mut toks);
+	let res = parse_value(&mut toks, opts);
 	let remaining = toks.remaining();
 	(res, remaining)
 }
 
+pub fn from_str_recovering(s: &str) -> (Value<Option<usize>>, Vec<ParseError>) {
+	let mut toks = s.into_tokens();
+	let mut errors = Vec::new();
+
+	skip_whitespace(&mut toks);
+	let value = parse_value_recovering(&mut toks, &ParseOptions::default(), &mut errors);
+	skip_whitespace(&mut toks);
+	if toks.peek().is_some() {
+		errors.push(ParseError::new_between(
+			ParseErrorKind::ExpectedEndOfInput,
+			toks.offset(),
+			s.len(),
+		));
+	}
+	(value, errors)
+}
+
+impl std::str::FromStr for Value<()> {
+	type Err = ParseError;
+
+	/// Parse a string into a value, expecting the whole string (ignoring any whitespace
+	/// and comments around the value) to be parsed.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (value, remaining) = from_str(s);
+		let value = value?;
+		let end = s.len() - remaining.len() + whitespace_len(remaining);
+		if end == s.len() {
+			Ok(value)
+		} else {
+			Err(ParseError::new_between(ParseErrorKind::ExpectedEndOfInput, end, s.len()))
+		}
+	}
+}
+
+/// Options to configure how strings are parsed into a [`Value`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+	units: Vec<(String, u32)>,
+	// Can variants with no values be written without brackets, like `None`? Only
+	// when parsing guided by a type, so that typos like `tru` aren't valid values.
+	bare_variants: bool,
+	// Are hex literals with an even number of digits, like `0x00ff`, parsed as bytes
+	// rather than as numbers? Only when parsing guided by a type, which tells us which
+	// one is wanted.
+	hex_bytes: bool,
+}
+
+impl ParseOptions {
+	/// Create the default parsing options.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Allow numbers to be followed by the unit symbol given, like `12.5 DOT`. Such numbers are
+	/// multiplied by `10^decimals` to give the number of the smallest units that they represent,
+	/// and an error is returned if the result isn't a whole number. Any number of units can
+	/// be added, and adding a unit again replaces it. Symbols are case sensitive, and should
+	/// look like identifiers.
+	pub fn unit(mut self, symbol: impl Into<String>, decimals: u32) -> Self {
+		let symbol = symbol.into();
+		self.units.retain(|(s, _)| *s != symbol);
+		self.units.push((symbol, decimals));
+		self
+	}
+
+	/// Allow variants with no values to be written without brackets, like `None`.
+	pub(super) fn bare_variants(mut self, bare_variants: bool) -> Self {
+		self.bare_variants = bare_variants;
+		self
+	}
+
+	/// Parse hex literals with an even number of digits, like `0x00ff`, as bytes.
+	pub(super) fn hex_bytes(mut self, hex_bytes: bool) -> Self {
+		self.hex_bytes = hex_bytes;
+		self
+	}
+
+	/// The number of decimals for the unit symbol given, if it's one that we know about.
+	fn decimals_for_unit(&self, symbol: &str) -> Option<u32> {
+		self.units.iter().find(|(s, _)| s == symbol).map(|(_, decimals)| *decimals)
+	}
+}
+
 /// An error parsing the provided string into a Value
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub struct ParseError {
@@ -46,6 +137,23 @@ impl ParseError {
 	fn new_between(err: ParseErrorKind, start: usize, end: usize) -> Self {
 		Self { start_loc: start, end_loc: Some(end), err }
 	}
+
+	/// Move every location in the error along by the number of bytes given, for errors
+	/// found in part of some larger input.
+	pub(super) fn offset_by(mut self, n: usize) -> Self {
+		self.start_loc += n;
+		self.end_loc = self.end_loc.map(|loc| loc + n);
+		match &mut self.err {
+			ParseErrorKind::Complex(ParseComplexError::ExpectedCloserToMatch(_, loc))
+			| ParseErrorKind::Char(ParseCharError::ExpectedClosingQuoteToMatch(loc))
+			| ParseErrorKind::String(ParseStringError::ExpectedClosingQuoteToMatch(loc))
+			| ParseErrorKind::BitSequence(ParseBitSequenceError::ExpectedClosingBracketToMatch(
+				loc,
+			)) => *loc += n,
+			_ => {}
+		}
+		self
+	}
 }
 
 impl std::fmt::Display for ParseError {
@@ -63,6 +171,8 @@ impl std::fmt::Display for ParseError {
 pub enum ParseErrorKind {
 	#[error("Expected a value")]
 	ExpectedValue,
+	#[error("Expected the end of the input")]
+	ExpectedEndOfInput,
 	#[error("{0}")]
 	Complex(#[from] ParseComplexError),
 	#[error("{0}")]
@@ -73,6 +183,8 @@ pub enum ParseErrorKind {
 	Number(#[from] ParseNumberError),
 	#[error("{0}")]
 	BitSequence(#[from] ParseBitSequenceError),
+	#[error("{0}")]
+	Bytes(#[from] ParseBytesError),
 }
 
 // Add handy helper methods to sub-error-kinds
@@ -140,6 +252,10 @@ pub enum ParseNumberError {
 	ExpectedDigit,
 	#[error("Failed to parse digits into an integer: {0}")]
 	ParsingFailed(ParseIntError),
+	#[error("Number is out of range for a {0}")]
+	OutOfRange(&'static str),
+	#[error("Number is not a whole number of the smallest unit, so can't be represented exactly")]
+	LosesPrecision,
 }
 at_between!(ParseNumberError);
 
@@ -152,8 +268,18 @@ pub enum ParseBitSequenceError {
 }
 at_between!(ParseBitSequenceError);
 
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ParseBytesError {
+	#[error("Expected an even number of hex digits")]
+	OddNumberOfHexDigits,
+}
+at_between!(ParseBytesError);
+
 // Parse a value.
-fn parse_value(t: &mut impl Tokens<Item = char>) -> Result<Value<()>, ParseError> {
+fn parse_value(
+	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
+) -> Result<Value<()>, ParseError> {
 	// Our parsers return `Result<Thing, Option<ParseError>>`, but in order to know
 	// whether to try the next item, `one_of` expects `Option<T>`, so we transpose_err
 	// to convert to the right shape.
@@ -161,11 +287,14 @@ fn parse_value(t: &mut impl Tokens<Item = char>) -> Result<Value<()>, ParseError
 		transpose_err(parse_bool(t).map(Value::bool).ok_or(None)),
 		transpose_err(parse_char(t).map(Value::char)),
 		transpose_err(parse_string(t).map(Value::string)),
-		transpose_err(parse_number(t).map(Value::primitive)),
-		transpose_err(parse_named_composite(t).map(|v| v.into())),
-		transpose_err(parse_unnamed_composite(t).map(|v| v.into())),
+		transpose_err(parse_raw_string(t).map(Value::string)),
+		transpose_err(parse_byte_string(t).map(bytes_to_value)),
+		transpose_err(parse_hex_bytes(t, opts).map(bytes_to_value)),
+		transpose_err(parse_number(t, opts).map(Value::primitive)),
+		transpose_err(parse_named_composite(t, opts).map(|v| v.into())),
+		transpose_err(parse_unnamed_composite(t, opts).map(|v| v.into())),
 		transpose_err(parse_bit_sequence(t).map(Value::bit_sequence)),
-		transpose_err(parse_variant(t).map(|v| v.into())),
+		transpose_err(parse_variant(t, opts).map(|v| v.into())),
 	);
 
 	match val {
@@ -175,6 +304,145 @@ fn parse_value(t: &mut impl Tokens<Item = char>) -> Result<Value<()>, ParseError
 	}
 }
 
+// Parse a value, carrying on past any errors in composites and variants. Errors are added to
+// the list given, and values that can't be parsed are replaced with an empty composite whose
+// context is the index of the error. Everything else has a context of `None`.
+fn parse_value_recovering(
+	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
+	errors: &mut Vec<ParseError>,
+) -> Value<Option<usize>> {
+	let start = t.location();
+
+	// Variants with values; `true` and `false` are bools and left for `parse_value`.
+	if let Some(name) = parse_optional_variant_ident(t) {
+		skip_whitespace(t);
+		if name != "true" && name != "false" && matches!(t.peek(), Some('{' | '(')) {
+			let values = parse_composite_recovering(t, opts, errors);
+			return Value { value: ValueDef::Variant(Variant { name, values }), context: None };
+		}
+		t.set_location(start);
+	}
+
+	if matches!(t.peek(), Some('{' | '(')) {
+		let composite = parse_composite_recovering(t, opts, errors);
+		return Value { value: ValueDef::Composite(composite), context: None };
+	}
+
+	// Anything else can't contain other values, so we parse it as normal.
+	match parse_value(t, opts) {
+		Ok(value) => value.map_context(|_| None),
+		Err(e) => {
+			skip_to_next_value(t);
+			error_placeholder(e, errors)
+		}
+	}
+}
+
+// Parse a named or unnamed composite (whichever is next), carrying on past any errors.
+fn parse_composite_recovering(
+	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
+	errors: &mut Vec<ParseError>,
+) -> Composite<Option<usize>> {
+	let start = t.offset();
+	let (is_named, closer) = match t.next() {
+		Some('{') => (true, '}'),
+		_ => (false, ')'),
+	};
+	let mut named = Vec::new();
+	let mut unnamed = Vec::new();
+
+	loop {
+		skip_whitespace(t);
+		if t.token(closer) {
+			break;
+		}
+		if t.peek().is_none() {
+			errors.push(ParseComplexError::ExpectedCloserToMatch(closer, start).at_one(t.offset()));
+			break;
+		}
+
+		if is_named {
+			let field = parse_field_name(t).and_then(|name| {
+				if skip_spaced_separator(t, ':') {
+					Ok(name)
+				} else {
+					Err(ParseComplexError::MissingFieldSeparator(':').at_one(t.offset()))
+				}
+			});
+			match field {
+				Ok(name) => named.push((name, parse_value_recovering(t, opts, errors))),
+				Err(e) => {
+					skip_to_next_value(t);
+					named.push((String::new(), error_placeholder(e, errors)));
+				}
+			}
+		} else {
+			unnamed.push(parse_value_recovering(t, opts, errors));
+		}
+
+		skip_whitespace(t);
+		if t.token(',') || t.peek() == Some(closer) || t.peek().is_none() {
+			continue;
+		}
+
+		// Something other than a separator or our closer follows the value. If it's some other
+		// closer, we assume it's a typo for ours, else we assume that a comma is missing.
+		errors.push(ParseComplexError::ExpectedCloserToMatch(closer, start).at_one(t.offset()));
+		if matches!(t.peek(), Some(')' | '}' | '>' | ']')) {
+			t.next();
+			break;
+		}
+	}
+
+	if is_named {
+		Composite::Named(named)
+	} else {
+		Composite::Unnamed(unnamed)
+	}
+}
+
+// Skip over whatever is left of a value that we failed to parse, stopping at the
+// next separator or closing bracket that isn't nested inside it.
+fn skip_to_next_value(t: &mut impl Tokens<Item = char>) {
+	let mut depth = 0usize;
+	while let Some(c) = t.peek() {
+		match c {
+			',' | ')' | '}' if depth == 0 => break,
+			'(' | '{' => depth += 1,
+			')' | '}' => depth -= 1,
+			// Skip comments so that we don't stop at any separators inside them.
+			'/' if skip_comment(t) => continue,
+			'"' => {
+				// Skip strings so that we don't stop at any separators inside them.
+				t.next();
+				let mut is_escaped = false;
+				for c in t.by_ref() {
+					match c {
+						_ if is_escaped => is_escaped = false,
+						'\\' => is_escaped = true,
+						'"' => break,
+						_ => {}
+					}
+				}
+				continue;
+			}
+			_ => {}
+		}
+		t.next();
+	}
+}
+
+// Record an error, and hand back a value to put in place of the one that couldn't be parsed.
+fn error_placeholder(e: ParseError, errors: &mut Vec<ParseError>) -> Value<Option<usize>> {
+	errors.push(e);
+	Value {
+		value: ValueDef::Composite(Composite::Unnamed(vec![])),
+		context: Some(errors.len() - 1),
+	}
+}
+
 // Parse a named composite value like `{ foo: 123 }`.
 //
 // As with most of the parsers here, the error is optional. A `Some` error indicates that
@@ -183,6 +451,7 @@ fn parse_value(t: &mut impl Tokens<Item = char>) -> Result<Value<()>, ParseError
 // and can attempt to parse the characters into a different thing if we wish.
 fn parse_named_composite(
 	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
 ) -> Result<Composite<()>, Option<ParseError>> {
 	let start = t.offset();
 	if !t.token('{') {
@@ -195,9 +464,14 @@ fn parse_named_composite(
 		return Ok(Composite::Named(vec![]));
 	}
 
-	let vals = t
-		.sep_by_err(|t| parse_field_name_and_value(t), |t| skip_spaced_separator(t, ','))
-		.collect::<Result<_, _>>()?;
+	let mut vals = Vec::new();
+	loop {
+		vals.push(parse_field_name_and_value(t, opts)?);
+		// Stop if there's no separator, or the separator is a trailing one.
+		if !skip_spaced_separator(t, ',') || t.peek() == Some('}') {
+			break;
+		}
+	}
 
 	skip_whitespace(t);
 	if !t.token('}') {
@@ -209,6 +483,7 @@ fn parse_named_composite(
 // Parse an unnamed composite value like `(true, 123)`
 fn parse_unnamed_composite(
 	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
 ) -> Result<Composite<()>, Option<ParseError>> {
 	let start = t.offset();
 	if !t.token('(') {
@@ -221,9 +496,13 @@ fn parse_unnamed_composite(
 		return Ok(Composite::Unnamed(vec![]));
 	}
 
-	let vals = t
-		.sep_by_err(|t| parse_value(t), |t| skip_spaced_separator(t, ','))
-		.collect::<Result<_, _>>()?;
+	let mut vals = Vec::new();
+	loop {
+		vals.push(parse_value(t, opts)?);
+		if !skip_spaced_separator(t, ',') || t.peek() == Some(')') {
+			break;
+		}
+	}
 
 	skip_whitespace(t);
 	if !t.token(')') {
@@ -232,23 +511,33 @@ fn parse_unnamed_composite(
 	Ok(Composite::Unnamed(vals))
 }
 
-// Parse a variant like `Variant { hello: "there" }` or `Foo (123, true)`
-fn parse_variant(t: &mut impl Tokens<Item = char>) -> Result<Variant<()>, Option<ParseError>> {
+// Parse a variant like `Variant { hello: "there" }` or `Foo (123, true)`, or `None`
+// if bare variants are allowed
+fn parse_variant(
+	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
+) -> Result<Variant<()>, Option<ParseError>> {
 	let ident = match parse_optional_variant_ident(t) {
 		Some(ident) => ident,
 		None => return Err(None),
 	};
 
+	let after_ident = t.location();
 	skip_whitespace(t);
 
 	let composite = yap::one_of!(t;
-		transpose_err(parse_named_composite(t)),
-		transpose_err(parse_unnamed_composite(t))
+		transpose_err(parse_named_composite(t, opts)),
+		transpose_err(parse_unnamed_composite(t, opts))
 	);
 
 	match composite {
 		Some(Ok(values)) => Ok(Variant { name: ident, values }),
 		Some(Err(e)) => Err(Some(e)),
+		// A variant with no values, like `None`, can be written without brackets.
+		None if opts.bare_variants => {
+			t.set_location(after_ident);
+			Ok(Variant { name: ident, values: Composite::Unnamed(vec![]) })
+		}
 		None => Err(None),
 	}
 }
@@ -275,15 +564,19 @@ fn parse_bit_sequence(t: &mut impl Tokens<Item = char>) -> Result<BitSequence, O
 
 // Parse a bool (`true` or `false`)
 fn parse_bool(t: &mut impl Tokens<Item = char>) -> Option<bool> {
-	let bool = if t.tokens("true".chars()) {
-		Some(true)
-	} else if t.tokens("false".chars()) {
-		Some(false)
+	let bool = if parse_exact(t, "true") {
+		true
+	} else if parse_exact(t, "false") {
+		false
 	} else {
-		None
+		return None;
 	};
 
-	bool
+	// Something like `trueish` isn't a bool, but it might be a variant name:
+	match t.peek() {
+		Some(c) if c.is_alphanumeric() || c == '_' => None,
+		_ => Some(bool),
+	}
 }
 
 // Parse a char like `'a'`
@@ -300,12 +593,12 @@ fn parse_char(t: &mut impl Tokens<Item = char>) -> Result<char, Option<ParseErro
 	// If char is a backslash, it's an escape code and we
 	// need to unescape it to find our inner char:
 	let char = if char == '\\' {
-		let escape_code = match t.next() {
-			None => return Err(Some(ParseCharError::ExpectedValidEscapeCode.at_one(t.offset()))),
-			Some(c) => c,
-		};
-		match string_helpers::from_escape_code(escape_code) {
-			None => return Err(Some(ParseCharError::ExpectedValidEscapeCode.at_one(t.offset()))),
+		let pos = t.offset();
+		match parse_escape_code(t) {
+			None => {
+				let end = t.offset().max(pos + 1);
+				return Err(Some(ParseCharError::ExpectedValidEscapeCode.between(pos, end)));
+			}
 			Some(c) => c,
 		}
 	} else {
@@ -318,29 +611,40 @@ fn parse_char(t: &mut impl Tokens<Item = char>) -> Result<char, Option<ParseErro
 	Ok(char)
 }
 
-// Parse a number like `-123_456` or `234` or `+1234_5`
-fn parse_number(t: &mut impl Tokens<Item = char>) -> Result<Primitive, Option<ParseError>> {
+// Parse a number like `-123_456`, `234`, `+1234_5`, `0b1010`, `-0o17`, `0xdead_beef`,
+// `1.5e12` or `12.5 DOT`
+fn parse_number(
+	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
+) -> Result<Primitive, Option<ParseError>> {
 	let start_loc = t.offset();
 	let is_positive = t.token('+') || !t.token('-');
 
-	// When we iterate numeric digits, prefix a sign as needed:
-	let sign = if is_positive { "".chars() } else { "-".chars() };
+	// A prefix tells us which base the digits are in:
+	let radix = if parse_exact(t, "0x") {
+		16
+	} else if parse_exact(t, "0o") {
+		8
+	} else if parse_exact(t, "0b") {
+		2
+	} else {
+		10
+	};
 
-	// Now, we expect a digit and then a mix of digits and underscores:
+	// Now, we expect digits and underscores. Decimal numbers must start with a digit,
+	// but like Rust, we allow an underscore straight after a prefix.
 	let mut seen_n = false;
-	let digits = t
+	let digits: String = t
 		.tokens_while(|c| {
-			if c.is_digit(10) {
+			if c.is_digit(radix) {
 				seen_n = true;
 				true
 			} else {
-				seen_n && *c == '_'
+				(seen_n || radix != 10) && *c == '_'
 			}
 		})
-		.filter(|c| c.is_digit(10));
-
-	// Chain sign to digits and attempt to parse into a number.
-	let n_str: String = sign.chain(digits).collect();
+		.filter(|c| *c != '_')
+		.collect();
 	let end_loc = t.offset();
 
 	// Nothing was parsed; Return None.
@@ -348,36 +652,230 @@ fn parse_number(t: &mut impl Tokens<Item = char>) -> Result<Primitive, Option<Pa
 		return Err(None);
 	}
 
-	// No digits were parsed but a sign was; err.
+	// No digits were parsed but a sign or prefix was; err.
 	if !seen_n {
 		return Err(Some(ParseNumberError::ExpectedDigit.between(end_loc, end_loc + 1)));
 	}
 
-	// Parse into a number as best we can:
+	// Decimal numbers might have a fractional part, an exponent or a unit:
+	if radix == 10 {
+		if let Some(n) = parse_decimal(t, &digits, is_positive, start_loc, opts)? {
+			return Ok(n);
+		}
+	}
+
+	// A `u256` or `i256` suffix means that we parse into that type, regardless of size:
+	if parse_exact(t, "u256") {
+		let n =
+			if is_positive { string_helpers::u256_from_str_radix(&digits, radix) } else { None };
+		return n.map(Primitive::U256).ok_or_else(|| {
+			Some(ParseNumberError::OutOfRange("u256").between(start_loc, t.offset()))
+		});
+	}
+	if parse_exact(t, "i256") {
+		return string_helpers::i256_from_str_radix(&digits, radix, !is_positive)
+			.map(Primitive::I256)
+			.ok_or_else(|| {
+				Some(ParseNumberError::OutOfRange("i256").between(start_loc, t.offset()))
+			});
+	}
+
+	// Parse into a number as best we can, falling back to 256 bit numbers if they
+	// are too large to fit into 128 bits:
 	if is_positive {
-		n_str
-			.parse::<u128>()
-			.map(Primitive::uint)
-			.map_err(|e| Some(ParseNumberError::ParsingFailed(e).between(start_loc, end_loc)))
+		match u128::from_str_radix(&digits, radix) {
+			Ok(n) => Ok(Primitive::uint(n)),
+			Err(e) if *e.kind() == IntErrorKind::PosOverflow => {
+				string_helpers::u256_from_str_radix(&digits, radix).map(Primitive::U256).ok_or_else(
+					|| Some(ParseNumberError::OutOfRange("u256").between(start_loc, end_loc)),
+				)
+			}
+			Err(e) => Err(Some(ParseNumberError::ParsingFailed(e).between(start_loc, end_loc))),
+		}
 	} else {
-		n_str
-			.parse::<i128>()
-			.map(Primitive::int)
-			.map_err(|e| Some(ParseNumberError::ParsingFailed(e).between(start_loc, end_loc)))
+		match i128::from_str_radix(&format!("-{digits}"), radix) {
+			Ok(n) => Ok(Primitive::int(n)),
+			Err(e) if *e.kind() == IntErrorKind::NegOverflow => {
+				string_helpers::i256_from_str_radix(&digits, radix, true)
+					.map(Primitive::I256)
+					.ok_or_else(|| {
+						Some(ParseNumberError::OutOfRange("i256").between(start_loc, end_loc))
+					})
+			}
+			Err(e) => Err(Some(ParseNumberError::ParsingFailed(e).between(start_loc, end_loc))),
+		}
+	}
+}
+
+// Parse the rest of a decimal number like `1.5e12` or `12.5 DOT`, given the digits before
+// any decimal point. These are always parsed into exact 128 bit integers, and `None` is
+// returned if there's no fractional part, exponent or unit to parse.
+fn parse_decimal(
+	t: &mut impl Tokens<Item = char>,
+	int_digits: &str,
+	is_positive: bool,
+	start_loc: usize,
+	opts: &ParseOptions,
+) -> Result<Option<Primitive>, ParseError> {
+	let mut digits = int_digits.to_owned();
+	let mut has_decimal = false;
+
+	// A fractional part like `.5`:
+	let mut frac_len = 0;
+	if t.token('.') {
+		has_decimal = true;
+		let frac = parse_decimal_digits(t)?;
+		frac_len = frac.len() as i64;
+		digits.push_str(&frac);
+	}
+
+	// An exponent like `e12` or `E-3`. If there are no digits after the `e`, it might be
+	// the start of a unit instead:
+	let mut exponent = 0i64;
+	let before_exponent = t.location();
+	if t.token('e') || t.token('E') {
+		let is_exponent_negative = !t.token('+') && t.token('-');
+		if t.peek().filter(char::is_ascii_digit).is_some() {
+			has_decimal = true;
+			// An exponent too large to parse will always be out of range or lose precision:
+			exponent = parse_decimal_digits(t)?.parse().unwrap_or(i64::MAX);
+			if is_exponent_negative {
+				exponent = -exponent;
+			}
+		} else {
+			t.set_location(before_exponent);
+		}
+	}
+
+	// A unit like `DOT`, if it's one that we've been told about:
+	let mut decimals = 0;
+	let before_unit = t.location();
+	skip_whitespace(t);
+	match parse_ident(t).ok().and_then(|symbol| opts.decimals_for_unit(&symbol)) {
+		Some(d) => {
+			has_decimal = true;
+			decimals = d;
+		}
+		None => t.set_location(before_unit),
+	}
+
+	if !has_decimal {
+		return Ok(None);
+	}
+
+	// The number is `digits * 10^shift`, and must be a whole number:
+	let end_loc = t.offset();
+	let shift = exponent.saturating_add(decimals as i64).saturating_sub(frac_len);
+	let n = if digits.bytes().all(|b| b == b'0') {
+		Some(0)
+	} else if shift >= 0 {
+		let pow = u32::try_from(shift).ok().and_then(|shift| 10u128.checked_pow(shift));
+		digits.parse::<u128>().ok().zip(pow).and_then(|(n, pow)| n.checked_mul(pow))
+	} else {
+		// Any digits that we'd need to drop to get a whole number must be zeros:
+		let keep = usize::try_from((digits.len() as i64).saturating_add(shift)).unwrap_or(0);
+		if !digits[keep..].bytes().all(|b| b == b'0') {
+			return Err(ParseNumberError::LosesPrecision.between(start_loc, end_loc));
+		}
+		digits[..keep].parse::<u128>().ok()
+	};
+
+	let number = if is_positive {
+		n.map(Primitive::U128).ok_or(ParseNumberError::OutOfRange("u128"))
+	} else {
+		n.and_then(|n| 0i128.checked_sub_unsigned(n))
+			.map(Primitive::I128)
+			.ok_or(ParseNumberError::OutOfRange("i128"))
+	};
+	number.map(Some).map_err(|e| e.between(start_loc, end_loc))
+}
+
+// Parse some decimal digits, which may contain underscores after the first digit.
+fn parse_decimal_digits(t: &mut impl Tokens<Item = char>) -> Result<String, ParseError> {
+	if t.peek().filter(char::is_ascii_digit).is_none() {
+		return Err(ParseNumberError::ExpectedDigit.between(t.offset(), t.offset() + 1));
+	}
+	Ok(t.tokens_while(|c| c.is_ascii_digit() || *c == '_').filter(|c| *c != '_').collect())
+}
+
+// Parse a hex literal into bytes if it has too many digits to be a u256, like a 64 byte
+// signature, or if we've been asked to parse hex literals with an even number of digits,
+// like `0x00ff`, as bytes. Anything else that starts with `0x`, like `0xfff`, `0xdead_beef`
+// or `0xffu256`, is left to be parsed as a number instead.
+fn parse_hex_bytes(
+	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
+) -> Result<Vec<u8>, Option<ParseError>> {
+	let start = t.offset();
+	if !parse_exact(t, "0x") {
+		return Err(None);
+	}
+	let digits = parse_hex_digits(t);
+	let too_long_for_u256 = digits.len() > 64;
+	if t.peek().filter(|c| c.is_alphanumeric() || *c == '_').is_some()
+		|| !(opts.hex_bytes || too_long_for_u256)
+	{
+		return Err(None);
+	}
+	match hex_digits_to_bytes(&digits) {
+		Some(bytes) => Ok(bytes),
+		None if too_long_for_u256 => {
+			Err(Some(ParseBytesError::OddNumberOfHexDigits.between(start, t.offset())))
+		}
+		None => Err(None),
+	}
+}
+
+// Parse any number of hex digits, handing back their values.
+fn parse_hex_digits(t: &mut impl Tokens<Item = char>) -> Vec<u8> {
+	t.tokens_while(|c| c.is_ascii_hexdigit())
+		.map(|c| c.to_digit(16).expect("only hex digits are taken") as u8)
+		.collect()
+}
+
+// Pair up hex digits into bytes, if there are an even number of them.
+fn hex_digits_to_bytes(digits: &[u8]) -> Option<Vec<u8>> {
+	if !digits.len().is_multiple_of(2) {
+		return None;
 	}
+	Some(digits.chunks(2).map(|pair| pair[0] << 4 | pair[1]).collect())
+}
+
+// Parse a byte string like `b"hello\xff"` into the UTF-8 bytes of the string, in which
+// `\x` escape codes can be any byte and not just an ASCII char.
+fn parse_byte_string(t: &mut impl Tokens<Item = char>) -> Result<Vec<u8>, Option<ParseError>> {
+	if !t.token('b') || t.peek() != Some('"') {
+		return Err(None);
+	}
+	parse_quoted(t, true)
+}
+
+// Bytes are represented as an unnamed composite of u8 values, which can be
+// encoded into any sequence or array of u8's.
+fn bytes_to_value(bytes: Vec<u8>) -> Value<()> {
+	Value::unnamed_composite(bytes.into_iter().map(Value::uint).collect())
 }
 
 // Parse a string like `"hello\n there"`
 fn parse_string(t: &mut impl Tokens<Item = char>) -> Result<String, Option<ParseError>> {
+	let bytes = parse_quoted(t, false)?;
+	Ok(String::from_utf8(bytes).expect("only whole chars were pushed"))
+}
+
+// Parse the bytes of a string like `"hello\n there"`. Escape codes like `\xff`
+// are only allowed to be non-ASCII bytes if `byte_escapes` is true.
+fn parse_quoted(
+	t: &mut impl Tokens<Item = char>,
+	byte_escapes: bool,
+) -> Result<Vec<u8>, Option<ParseError>> {
 	let start = t.offset();
 	if !t.token('"') {
 		return Err(None);
 	}
 
-	let mut out: String = String::new();
-	let mut next_is_escaped = false;
+	let mut out = Vec::new();
+	let mut buf = [0; 4];
 	loop {
-		let pos = t.offset();
 		let char = match t.next() {
 			Some(c) => c,
 			None => {
@@ -388,52 +886,139 @@ fn parse_string(t: &mut impl Tokens<Item = char>) -> Result<String, Option<Parse
 		};
 
 		match char {
-			// Escape a char:
-			'\\' if !next_is_escaped => {
-				next_is_escaped = true;
-			}
 			// Handle escaped chars:
-			c if next_is_escaped => match string_helpers::from_escape_code(c) {
-				Some(c) => {
-					out.push(c);
-					next_is_escaped = false;
+			'\\' => {
+				let pos = t.offset();
+				let escaped = if byte_escapes && t.token('x') {
+					parse_hex_byte(t).map(|b| out.push(b))
+				} else {
+					parse_escape_code(t)
+						.map(|c| out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes()))
+				};
+				match escaped {
+					Some(()) => {}
+					None => {
+						let end = t.offset().max(pos + 1);
+						return Err(Some(
+							ParseStringError::ExpectedValidEscapeCode.between(pos, end),
+						));
+					}
 				}
-				None => {
-					return Err(Some(
-						ParseStringError::ExpectedValidEscapeCode.between(pos, pos + 1),
-					))
-				}
-			},
+			}
 			// String has closed
 			'"' => {
 				break; // closing quote seen; done!
 			}
 			// All other chars pushed as-is.
 			c => {
-				out.push(c);
+				out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+			}
+		}
+	}
+
+	Ok(out)
+}
+
+// Parse a raw string like `r"C:\path"` or `r#"say "hi""#`, in which backslashes aren't
+// escape codes, and quotes can be used as long as they aren't followed by as many `#`'s
+// as the string was opened with.
+fn parse_raw_string(t: &mut impl Tokens<Item = char>) -> Result<String, Option<ParseError>> {
+	let start = t.offset();
+	if !t.token('r') {
+		return Err(None);
+	}
+	let hashes = t.skip_tokens_while(|c| *c == '#');
+	if !t.token('"') {
+		return Err(None);
+	}
+
+	let mut out = String::new();
+	loop {
+		match t.next() {
+			None => {
+				return Err(Some(
+					ParseStringError::ExpectedClosingQuoteToMatch(start).at_one(t.offset()),
+				))
+			}
+			Some('"') => {
+				let location = t.location();
+				let mut closing_hashes = 0;
+				while closing_hashes < hashes && t.token('#') {
+					closing_hashes += 1;
+				}
+				if closing_hashes == hashes {
+					break;
+				}
+				t.set_location(location);
+				out.push('"');
 			}
+			Some(c) => out.push(c),
 		}
 	}
 
 	Ok(out)
 }
 
+// Parse the part of an escape code that follows the `\`, like `n`, `x7F` or `u{1F600}`,
+// returning the char that it represents.
+fn parse_escape_code(t: &mut impl Tokens<Item = char>) -> Option<char> {
+	match t.next()? {
+		// Exactly two hex digits, for an ASCII char:
+		'x' => {
+			let n = parse_hex_byte(t)?;
+			if n > 0x7F {
+				return None;
+			}
+			Some(n as char)
+		}
+		// One to six hex digits in braces, for any char:
+		'u' => {
+			if !t.token('{') {
+				return None;
+			}
+			let mut n = 0u32;
+			let mut digits = 0;
+			loop {
+				match t.next()? {
+					'}' if digits > 0 => break,
+					c if digits < 6 => {
+						n = n << 4 | c.to_digit(16)?;
+						digits += 1;
+					}
+					_ => return None,
+				}
+			}
+			char::from_u32(n)
+		}
+		c => string_helpers::from_escape_code(c),
+	}
+}
+
+// Parse exactly two hex digits, like the `7F` in the escape code `\x7F`.
+fn parse_hex_byte(t: &mut impl Tokens<Item = char>) -> Option<u8> {
+	let hi = t.next()?.to_digit(16)?;
+	let lo = t.next()?.to_digit(16)?;
+	Some((hi << 4 | lo) as u8)
+}
+
 // Parse a field in a named composite like `foo: 123` or `"hello there": 123`
 fn parse_field_name_and_value(
 	t: &mut impl Tokens<Item = char>,
+	opts: &ParseOptions,
 ) -> Result<(String, Value<()>), ParseError> {
 	let name = parse_field_name(t)?;
 	if !skip_spaced_separator(t, ':') {
 		return Err(ParseComplexError::MissingFieldSeparator(':').at_one(t.offset()));
 	}
-	let value = parse_value(t)?;
+	let value = parse_value(t, opts)?;
 	Ok((name, value))
 }
 
-// Parse a field name in a named composite like `foo` or `"hello there"`
+// Parse a field name in a named composite like `foo`, `"hello there"` or `r"hello there"`
 fn parse_field_name(t: &mut impl Tokens<Item = char>) -> Result<String, ParseError> {
 	let field_name = yap::one_of!(t;
 		transpose_err(parse_string(t)),
+		transpose_err(parse_raw_string(t)),
 		Some(parse_ident(t)),
 	);
 
@@ -475,9 +1060,61 @@ fn parse_ident(t: &mut impl Tokens<Item = char>) -> Result<String, ParseError> {
 	Ok(ident_str)
 }
 
-// Skip any whitespace characters
+// Consume the exact string given if it comes next, returning true if so. Unlike
+// `Tokens::tokens`, nothing is matched if we run out of input part way through.
+fn parse_exact(t: &mut impl Tokens<Item = char>, s: &str) -> bool {
+	let location = t.location();
+	for c in s.chars() {
+		if t.next() != Some(c) {
+			t.set_location(location);
+			return false;
+		}
+	}
+	true
+}
+
+// The length in bytes of any whitespace and comments at the start of the string given.
+pub(super) fn whitespace_len(s: &str) -> usize {
+	let mut toks = s.into_tokens();
+	skip_whitespace(&mut toks);
+	toks.offset()
+}
+
+// Skip any whitespace characters and comments
 fn skip_whitespace(t: &mut impl Tokens<Item = char>) {
-	t.skip_tokens_while(|c| c.is_whitespace());
+	loop {
+		t.skip_tokens_while(|c| c.is_whitespace());
+		if !skip_comment(t) {
+			break;
+		}
+	}
+}
+
+// Skip a `// line comment` or a `/* block comment */` if one comes next, returning true if so.
+// Block comments can be nested, like in Rust. A block comment that's never closed isn't
+// skipped, so that whatever we're parsing fails at the start of it.
+fn skip_comment(t: &mut impl Tokens<Item = char>) -> bool {
+	if parse_exact(t, "//") {
+		t.skip_tokens_while(|&c| c != '\n');
+		return true;
+	}
+
+	let start = t.location();
+	if !parse_exact(t, "/*") {
+		return false;
+	}
+	let mut depth = 1;
+	while depth > 0 {
+		if parse_exact(t, "*/") {
+			depth -= 1;
+		} else if parse_exact(t, "/*") {
+			depth += 1;
+		} else if t.next().is_none() {
+			t.set_location(start);
+			return false;
+		}
+	}
+	true
 }
 
 // Skip a provided separator, with optional spaces on either side
@@ -514,6 +1151,8 @@ mod test {
 	fn parse_bools() {
 		assert_eq!(from("true"), Ok(Value::bool(true)));
 		assert_eq!(from("false"), Ok(Value::bool(false)));
+		assert_eq!(from("tru"), Err(ParseError::new_at(ParseErrorKind::ExpectedValue, 0)));
+		assert_eq!(from("trueish()"), Ok(Value::unnamed_variant("trueish", vec![])));
 	}
 
 	#[test]
@@ -525,6 +1164,172 @@ mod test {
 		assert_eq!(from("-abc"), Err(ParseNumberError::ExpectedDigit.between(1, 2)));
 	}
 
+	#[test]
+	fn parse_non_decimal_numbers() {
+		assert_eq!(from("0b1010"), Ok(Value::uint(10u8)));
+		assert_eq!(from("0b_1111_0000"), Ok(Value::uint(240u8)));
+		assert_eq!(from("-0b1"), Ok(Value::int(-1)));
+		assert_eq!(from("0o777"), Ok(Value::uint(0o777u16)));
+		assert_eq!(from("+0o17"), Ok(Value::uint(15u8)));
+		assert_eq!(from("-0o17"), Ok(Value::int(-15)));
+
+		// Hex literals are always numbers, whatever the number of digits:
+		assert_eq!(from("0x1"), Ok(Value::uint(1u8)));
+		assert_eq!(from("0xf"), Ok(Value::uint(15u8)));
+		assert_eq!(from("0xff"), Ok(Value::uint(255u8)));
+		assert_eq!(from("0x00ff"), Ok(Value::uint(255u8)));
+		assert_eq!(from("+0xff"), Ok(Value::uint(255u8)));
+		assert_eq!(from("-0xff"), Ok(Value::int(-255)));
+		assert_eq!(from("0xdead_beef"), Ok(Value::uint(0xdeadbeefu32)));
+		assert_eq!(from("0x_ff"), Ok(Value::uint(255u8)));
+		assert_eq!(from("0x"), Err(ParseNumberError::ExpectedDigit.between(2, 3)));
+
+		// Numbers too large for 128 bits become 256 bit numbers:
+		let mut n = [0; 32];
+		n[16] = 1;
+		assert_eq!(
+			from(&format!("+0x1{}", "0".repeat(32))),
+			Ok(Value::primitive(Primitive::U256(n)))
+		);
+		assert_eq!(
+			from(&format!("0b1{}", "_0000_0000".repeat(16))),
+			Ok(Value::primitive(Primitive::U256(n)))
+		);
+		let mut n = [255; 32];
+		n[..16].copy_from_slice(&[0; 16]);
+		assert_eq!(
+			from(&format!("-0x1{}", "0".repeat(32))),
+			Ok(Value::primitive(Primitive::I256(n)))
+		);
+		assert_eq!(
+			from("0b11i256"),
+			Ok(Value::primitive(Primitive::I256({
+				let mut n = [0; 32];
+				n[0] = 3;
+				n
+			})))
+		);
+		assert_eq!(
+			from(&format!("+0x1{}", "0".repeat(64))),
+			Err(ParseNumberError::OutOfRange("u256").between(0, 68))
+		);
+
+		// Prefixes need digits after them:
+		assert_eq!(from("0b"), Err(ParseNumberError::ExpectedDigit.between(2, 3)));
+		assert_eq!(from("-0o_"), Err(ParseNumberError::ExpectedDigit.between(4, 5)));
+	}
+
+	#[test]
+	fn parse_decimal_numbers() {
+		assert_eq!(from("1.5e12"), Ok(Value::uint(1_500_000_000_000u128)));
+		assert_eq!(from("1_000.000_5E4"), Ok(Value::uint(10_000_005u128)));
+		assert_eq!(from("1e0"), Ok(Value::uint(1u8)));
+		assert_eq!(from("25e-1_0"), Err(ParseNumberError::LosesPrecision.between(0, 7)));
+		assert_eq!(from("2500e-2"), Ok(Value::uint(25u8)));
+		assert_eq!(from("+2.0"), Ok(Value::uint(2u8)));
+		assert_eq!(from("-0.5e1"), Ok(Value::int(-5)));
+		assert_eq!(from("0.0e99999999999999999999"), Ok(Value::uint(0u8)));
+
+		// They are always exact 128 bit integers:
+		assert_eq!(from("1.5"), Err(ParseNumberError::LosesPrecision.between(0, 3)));
+		assert_eq!(
+			from("1e-99999999999999999999"),
+			Err(ParseNumberError::LosesPrecision.between(0, 23))
+		);
+		assert_eq!(from("3.5e38"), Err(ParseNumberError::OutOfRange("u128").between(0, 6)));
+		assert_eq!(from("-1.8e38"), Err(ParseNumberError::OutOfRange("i128").between(0, 7)));
+		assert_eq!(
+			from("170141183460469231731687303715884105728e0"),
+			Ok(Value::uint(170141183460469231731687303715884105728u128))
+		);
+		assert_eq!(from("-170141183460469231731687303715884105728e0"), Ok(Value::int(i128::MIN)));
+
+		// Digits are needed after the decimal point and in the exponent:
+		assert_eq!(from("1."), Err(ParseNumberError::ExpectedDigit.between(2, 3)));
+		assert_eq!(from("1.e5"), Err(ParseNumberError::ExpectedDigit.between(2, 3)));
+		assert_eq!(from_str("1e").1, "e");
+	}
+
+	#[test]
+	fn parse_numbers_with_units() {
+		let options = ParseOptions::new().unit("DOT", 10).unit("ETH", 18).unit("e", 3);
+		let from = |s| {
+			let (res, remaining) = from_str_with_options(s, &options);
+			assert_eq!(remaining, "", "should be no unparsed input for '{s}'");
+			res
+		};
+
+		assert_eq!(from("12.5 DOT"), Ok(Value::uint(125_000_000_000u128)));
+		assert_eq!(from("12.5DOT"), Ok(Value::uint(125_000_000_000u128)));
+		assert_eq!(from("1e-10 DOT"), Ok(Value::uint(1u8)));
+		assert_eq!(from("1_000 ETH"), Ok(Value::uint(1_000_000_000_000_000_000_000u128)));
+		assert_eq!(from("-1 DOT"), Ok(Value::int(-10_000_000_000i64)));
+		// `e` is a unit here, since no exponent digits follow it:
+		assert_eq!(from("2e"), Ok(Value::uint(2000u16)));
+		assert_eq!(from("2e2e"), Ok(Value::uint(200_000u32)));
+		assert_eq!(
+			from("{ value: 1 DOT, /* a comment */ fee: 0.01 DOT }"),
+			Ok(Value::named_composite(vec![
+				("value".into(), Value::uint(10_000_000_000u128)),
+				("fee".into(), Value::uint(100_000_000u128)),
+			]))
+		);
+
+		assert_eq!(from("0.00000000001 DOT"), Err(ParseNumberError::LosesPrecision.between(0, 17)));
+		assert_eq!(from("1e30 ETH"), Err(ParseNumberError::OutOfRange("u128").between(0, 8)));
+
+		// Unknown units, and units that aren't after a decimal number, are left alone:
+		assert_eq!(from_str_with_options("1 dot", &options).1, " dot");
+		assert_eq!(from_str_with_options("0x01 DOT", &options).1, " DOT");
+		assert_eq!(from_str_with_options("1u256 DOT", &options).1, " DOT");
+	}
+
+	#[test]
+	fn parse_256_bit_numbers() {
+		let mut n = [0; 32];
+		n[0] = 1;
+		n[1] = 1;
+		assert_eq!(from("257u256"), Ok(Value::primitive(Primitive::U256(n))));
+		assert_eq!(from("+2_57i256"), Ok(Value::primitive(Primitive::I256(n))));
+		assert_eq!(from("-1i256"), Ok(Value::primitive(Primitive::I256([255; 32]))));
+
+		// Numbers too big for 128 bits become 256 bit numbers:
+		let mut n = [0; 32];
+		n[16] = 1;
+		assert_eq!(
+			from("340282366920938463463374607431768211456"),
+			Ok(Value::primitive(Primitive::U256(n)))
+		);
+		let mut n = [255; 32];
+		n[..16].copy_from_slice(&[0; 16]);
+		assert_eq!(
+			from("-340282366920938463463374607431768211456"),
+			Ok(Value::primitive(Primitive::I256(n)))
+		);
+
+		// The limits of each type:
+		let max_u256 =
+			"115792089237316195423570985008687907853269984665640564039457584007913129639935";
+		assert_eq!(from(max_u256), Ok(Value::primitive(Primitive::U256([255; 32]))));
+		assert_eq!(
+			from(&format!("{max_u256}6")),
+			Err(ParseNumberError::OutOfRange("u256").between(0, 79))
+		);
+		let mut min_i256 = [0; 32];
+		min_i256[31] = 0x80;
+		let min_i256_str =
+			"-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+		assert_eq!(
+			from(&format!("{min_i256_str}i256")),
+			Ok(Value::primitive(Primitive::I256(min_i256)))
+		);
+		assert_eq!(
+			from(&min_i256_str.replace("68", "69")),
+			Err(ParseNumberError::OutOfRange("i256").between(0, 78))
+		);
+		assert_eq!(from("-1u256"), Err(ParseNumberError::OutOfRange("u256").between(0, 6)));
+	}
+
 	#[test]
 	fn parse_chars() {
 		assert_eq!(from("'a'"), Ok(Value::char('a')));
@@ -552,6 +1357,122 @@ mod test {
 		assert_eq!(from("\"Hi"), Err(ParseStringError::ExpectedClosingQuoteToMatch(0).at_one(3)));
 	}
 
+	#[test]
+	fn parse_bytes() {
+		let bytes =
+			|bs: &[u8]| Value::unnamed_composite(bs.iter().map(|&b| Value::uint(b)).collect());
+
+		// Hex literals are numbers unless they have too many digits to fit into a u256:
+		assert_eq!(from("0xdeadBEEF"), Ok(Value::uint(0xdeadbeefu32)));
+		let signature = format!("0x{}", "ab".repeat(64));
+		assert_eq!(from(&signature), Ok(bytes(&[0xab; 64])));
+		let odd = format!("0x{}", "a".repeat(65));
+		assert_eq!(from(&odd), Err(ParseBytesError::OddNumberOfHexDigits.between(0, 67)));
+
+		// .. or we ask for them to be bytes:
+		let opts = ParseOptions::new().hex_bytes(true);
+		let from_hex = |s| from_str_with_options(s, &opts).0;
+		assert_eq!(from_hex("0x00ff"), Ok(bytes(&[0, 255])));
+		assert_eq!(from_hex("0x"), Ok(bytes(&[])));
+		assert_eq!(from_hex("0xabc"), Ok(Value::uint(0xabcu16)));
+		assert_eq!(from_hex("0xab_cd"), Ok(Value::uint(0xabcdu16)));
+		let mut n = [0; 32];
+		n[0] = 255;
+		assert_eq!(from_hex("0xffu256"), Ok(Value::primitive(Primitive::U256(n))));
+
+		assert_eq!(from("b\"hi\\n\""), Ok(bytes(b"hi\n")));
+		assert_eq!(from("b\"😀\""), Ok(bytes("😀".as_bytes())));
+		assert_eq!(from("b\"\""), Ok(bytes(&[])));
+		assert_eq!(from("b\"\\x00\\x7f\\x80\\xFF\""), Ok(bytes(&[0x00, 0x7f, 0x80, 0xff])));
+		assert_eq!(
+			from("b\"\\xfg\""),
+			Err(ParseStringError::ExpectedValidEscapeCode.between(3, 6))
+		);
+		assert_eq!(from("b\"hi"), Err(ParseStringError::ExpectedClosingQuoteToMatch(1).at_one(4)));
+
+		// Things which start the same way are still parsed as expected:
+		assert_eq!(from("0"), Ok(Value::uint(0u8)));
+		assert_eq!(from("bar(1)"), Ok(Value::unnamed_variant("bar", vec![Value::uint(1u8)])));
+	}
+
+	#[test]
+	fn parsed_bytes_encode_to_u8_sequences() {
+		use crate::scale_impls::encode_value_as_type;
+		use codec::Encode;
+
+		fn encode_as<T: scale_info::TypeInfo + 'static>(s: &str) -> Vec<u8> {
+			let m = scale_info::MetaType::new::<T>();
+			let mut types = scale_info::Registry::new();
+			let id = types.register_type(&m);
+			let types: scale_info::PortableRegistry = types.into();
+
+			let value = from(s).expect("can parse");
+			let mut bytes = Vec::new();
+			encode_value_as_type(value, id.id, &types, &mut bytes).expect("can encode");
+			bytes
+		}
+
+		assert_eq!(encode_as::<Vec<u8>>("0xdeadbeef"), vec![0xdeu8, 0xad, 0xbe, 0xef].encode());
+		assert_eq!(encode_as::<[u8; 4]>("0xdeadbeef"), [0xdeu8, 0xad, 0xbe, 0xef].encode());
+
+		// Hex literals encode to the big endian bytes written, however many there are:
+		let bytes: Vec<u8> = (1..=32).collect();
+		let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+		let hash: [u8; 32] = bytes.clone().try_into().unwrap();
+		assert_eq!(encode_as::<[u8; 32]>(&format!("0x{hex}")), hash.encode());
+		assert_eq!(encode_as::<Vec<u8>>(&format!("0x{hex}")), bytes.encode());
+		assert_eq!(encode_as::<[u8; 64]>(&format!("0x{hex}{hex}")), [hash, hash].encode());
+		assert_eq!(encode_as::<[u8; 4]>("0xbeef"), [0u8, 0, 0xbe, 0xef].encode());
+		assert_eq!(encode_as::<Vec<u8>>("0x0"), vec![0u8].encode());
+
+		assert_eq!(encode_as::<Vec<u8>>("b\"hello\""), b"hello".to_vec().encode());
+		assert_eq!(encode_as::<[u8; 2]>("b\"hi\""), b"hi".encode());
+	}
+
+	#[test]
+	fn parse_unicode_and_hex_escapes() {
+		assert_eq!(from(r"'\u{1F600}'"), Ok(Value::char('😀')));
+		assert_eq!(from(r"'\x7F'"), Ok(Value::char('\x7F')));
+		assert_eq!(from(r#""\u{48}\x69\u{10FFFF}""#), Ok(Value::string("Hi\u{10FFFF}")));
+		assert_eq!(
+			from(r#"b"\x00\x7f""#),
+			Ok(Value::unnamed_composite(vec![Value::uint(0u8), Value::uint(127u8)]))
+		);
+
+		// Invalid escapes:
+		assert_eq!(from(r#""\x80""#), Err(ParseStringError::ExpectedValidEscapeCode.between(2, 5)));
+		assert_eq!(from(r#""\x7""#), Err(ParseStringError::ExpectedValidEscapeCode.between(2, 5)));
+		assert_eq!(from(r#""\u{}""#), Err(ParseStringError::ExpectedValidEscapeCode.between(2, 5)));
+		assert_eq!(
+			from(r#""\u{1234567}""#),
+			Err(ParseStringError::ExpectedValidEscapeCode.between(2, 11))
+		);
+		assert_eq!(
+			from(r#""\u{D800}""#),
+			Err(ParseStringError::ExpectedValidEscapeCode.between(2, 9))
+		);
+		assert_eq!(from(r"'\u1234'"), Err(ParseCharError::ExpectedValidEscapeCode.between(2, 3)));
+	}
+
+	#[test]
+	fn parse_raw_strings() {
+		assert_eq!(from(r#"r"C:\path\n""#), Ok(Value::string(r"C:\path\n")));
+		assert_eq!(from(r##"r#"say "hi""#"##), Ok(Value::string(r#"say "hi""#)));
+		assert_eq!(from(r###"r##"a "# b"##"###), Ok(Value::string(r##"a "# b"##)));
+		assert_eq!(from(r#"r"""#), Ok(Value::string("")));
+		assert_eq!(
+			from(r#"{ r"raw name": r"value" }"#),
+			Ok(Value::named_composite(vec![("raw name".into(), Value::string("value"))]))
+		);
+		assert_eq!(
+			from(r###"r##"unclosed"#"###),
+			Err(ParseStringError::ExpectedClosingQuoteToMatch(0).at_one(14))
+		);
+
+		// Variants called `r` still work:
+		assert_eq!(from("r(1)"), Ok(Value::unnamed_variant("r", vec![Value::uint(1u8)])));
+	}
+
 	#[test]
 	fn parse_unnamed_composites() {
 		assert_eq!(
@@ -617,6 +1538,21 @@ mod test {
 		assert_eq!(from("Foo( \t)"), Ok(Value::unnamed_variant("Foo", vec![])));
 		assert_eq!(from("Foo{  }"), Ok(Value::named_variant("Foo", vec![])));
 
+		// Brackets can only be left off when parsing guided by a type:
+		assert_eq!(from("None"), Err(ParseError::new_at(ParseErrorKind::ExpectedValue, 0)));
+		let opts = ParseOptions::new().bare_variants(true);
+		assert_eq!(
+			from_str_with_options("None", &opts).0,
+			Ok(Value::unnamed_variant("None", vec![]))
+		);
+		assert_eq!(
+			from_str_with_options("(None, Some(None))", &opts).0,
+			Ok(Value::unnamed_composite(vec![
+				Value::unnamed_variant("None", vec![]),
+				Value::unnamed_variant("Some", vec![Value::unnamed_variant("None", vec![])])
+			]))
+		);
+
 		// Parsing special "v" strings:
 		assert_eq!(
 			from("v\"variant name\" {  }"),
@@ -624,6 +1560,195 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn parse_with_from_str_trait() {
+		assert_eq!(
+			"Foo(1, true)".parse(),
+			Ok(Value::unnamed_variant("Foo", vec![Value::uint(1u8), Value::bool(true)]))
+		);
+		assert_eq!(" 1 // one".parse(), Ok(Value::uint(1u8)));
+		assert_eq!(
+			"1 2".parse::<Value<()>>(),
+			Err(ParseError::new_between(ParseErrorKind::ExpectedEndOfInput, 2, 3))
+		);
+		assert_eq!(
+			"(1".parse::<Value<()>>(),
+			Err(ParseComplexError::ExpectedCloserToMatch(')', 0).at_one(2))
+		);
+	}
+
+	#[test]
+	fn parse_comments() {
+		assert_eq!(
+			"// Some leading comments.
+				// Over two lines.
+				Foo {
+					a: 1, // One.
+					/* Two, */ b /* is */ : /* also two */ 2,
+					c: (/* nothing */),
+					d: /* nested /* comments */ are fine */ None(),
+				} // And a trailing comment"
+				.parse(),
+			Ok(Value::named_variant(
+				"Foo",
+				vec![
+					("a".into(), Value::uint(1u8)),
+					("b".into(), Value::uint(2u8)),
+					("c".into(), Value::unnamed_composite(vec![])),
+					("d".into(), Value::unnamed_variant("None", vec![])),
+				]
+			))
+		);
+		assert_eq!("/**/1/**/".parse(), Ok(Value::uint(1u8)));
+		assert_eq!(from("\"//\""), Ok(Value::string("//")));
+
+		// Comments that aren't closed aren't skipped:
+		assert_eq!(from("(1, /* 2)"), Err(ParseError::new_at(ParseErrorKind::ExpectedValue, 4)));
+		assert_eq!(from_str("1 /* 2"), (Ok(Value::uint(1u8)), " /* 2"));
+
+		// Leading comments are skipped, but anything after the value is left alone:
+		assert_eq!(from_str("/**/1 // one"), (Ok(Value::uint(1u8)), " // one"));
+		assert_eq!(from_str("1 "), (Ok(Value::uint(1u8)), " "));
+	}
+
+	#[test]
+	fn parse_trailing_commas() {
+		assert_eq!(
+			from("(1, 2,)"),
+			Ok(Value::unnamed_composite(vec![Value::uint(1u8), Value::uint(2u8)]))
+		);
+		assert_eq!(
+			from("{ a: 1, b: 2, }"),
+			Ok(Value::named_composite(vec![
+				("a".into(), Value::uint(1u8)),
+				("b".into(), Value::uint(2u8))
+			]))
+		);
+		assert_eq!(
+			from("Foo(\n\ttrue,\n)"),
+			Ok(Value::unnamed_variant("Foo", vec![Value::bool(true)]))
+		);
+		assert_eq!(
+			from("Foo { a: (1,), }"),
+			Ok(Value::named_variant(
+				"Foo",
+				vec![("a".into(), Value::unnamed_composite(vec![Value::uint(1u8)]))]
+			))
+		);
+
+		// Only one trailing comma, and only after a value:
+		assert!(from("(1,,)").is_err());
+		assert!(from("(,)").is_err());
+		assert!(from("{,}").is_err());
+	}
+
+	#[test]
+	fn recovering_parse_matches_normal_parse() {
+		for s in [
+			"true",
+			"  (1, \"two\", Foo { a: 0x1234 }, Bar(<0101>), None())  ",
+			"v\"weird name\" { \"a b\": 'c', d: {} }",
+			"(true (1))",
+			"// A comment\n(1, /* a, b */ 2,) // done",
+		] {
+			let (value, errors) = from_str_recovering(s);
+			match s.parse::<Value<()>>() {
+				Ok(expected) => {
+					assert_eq!(errors, vec![], "no errors expected for '{s}'");
+					assert_eq!(value.remove_context(), expected);
+				}
+				Err(_) => assert!(!errors.is_empty(), "errors expected for '{s}'"),
+			}
+		}
+	}
+
+	#[test]
+	fn recovering_parse_finds_every_error() {
+		let s = "{ a: 1 b: tru3, c: (1, 'xy', 3), 4: 5, e: Foo(\"hi, 0x123) }";
+		let (value, errors) = from_str_recovering(s);
+		assert_eq!(
+			errors,
+			vec![
+				ParseComplexError::ExpectedCloserToMatch('}', 0).at_one(7),
+				ParseError::new_at(ParseErrorKind::ExpectedValue, 10),
+				ParseCharError::ExpectedClosingQuoteToMatch(23).at_one(25),
+				ParseComplexError::InvalidStartingCharacterInIdent.at_one(33),
+				// The unclosed string swallows the rest of the input:
+				ParseStringError::ExpectedClosingQuoteToMatch(46).at_one(59),
+				ParseComplexError::ExpectedCloserToMatch(')', 45).at_one(59),
+				ParseComplexError::ExpectedCloserToMatch('}', 0).at_one(59),
+			]
+		);
+
+		let placeholder = |idx| Value {
+			value: ValueDef::Composite(Composite::Unnamed(vec![])),
+			context: Some(idx),
+		};
+		let v = |value: Value<()>| value.map_context(|_| None);
+		assert_eq!(
+			value,
+			Value {
+				value: ValueDef::Composite(Composite::Named(vec![
+					("a".into(), v(Value::uint(1u8))),
+					("b".into(), placeholder(1)),
+					(
+						"c".into(),
+						Value {
+							value: ValueDef::Composite(Composite::Unnamed(vec![
+								v(Value::uint(1u8)),
+								placeholder(2),
+								v(Value::uint(3u8)),
+							])),
+							context: None
+						}
+					),
+					("".into(), placeholder(3)),
+					(
+						"e".into(),
+						Value {
+							value: ValueDef::Variant(Variant {
+								name: "Foo".into(),
+								values: Composite::Unnamed(vec![placeholder(4)])
+							}),
+							context: None
+						}
+					),
+				])),
+				context: None
+			}
+		);
+	}
+
+	#[test]
+	fn recovering_parse_skips_comments_in_bad_values() {
+		let (value, errors) = from_str_recovering("(@ /* a, b */, 2)");
+		assert_eq!(errors, vec![ParseError::new_at(ParseErrorKind::ExpectedValue, 1)]);
+		assert_eq!(
+			value.remove_context(),
+			Value::unnamed_composite(vec![Value::unnamed_composite(vec![]), Value::uint(2u8)])
+		);
+	}
+
+	#[test]
+	fn recovering_parse_handles_mismatched_and_trailing_input() {
+		let (value, errors) = from_str_recovering("(1, 2} 3");
+		assert_eq!(
+			errors,
+			vec![
+				ParseComplexError::ExpectedCloserToMatch(')', 0).at_one(5),
+				ParseError::new_between(ParseErrorKind::ExpectedEndOfInput, 7, 8),
+			]
+		);
+		assert_eq!(
+			value.remove_context(),
+			Value::unnamed_composite(vec![Value::uint(1u8), Value::uint(2u8)])
+		);
+
+		let (value, errors) = from_str_recovering("");
+		assert_eq!(errors, vec![ParseError::new_at(ParseErrorKind::ExpectedValue, 0)]);
+		assert_eq!(value.context, Some(0));
+	}
+
 	#[test]
 	fn parse_bit_sequences() {
 		use bitvec::{bitvec, order::Lsb0};
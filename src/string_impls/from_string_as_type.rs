@@ -0,0 +1,430 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::from_string::{from_str_with_options, ParseError, ParseOptions};
+use crate::scale_impls::TypeId;
+use crate::value::{Composite, Primitive, Value, ValueDef, Variant};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+
+pub fn from_str_as_type<'a, Id: Into<TypeId>>(
+	s: &'a str,
+	ty_id: Id,
+	types: &PortableRegistry,
+) -> (Result<Value<TypeId>, ParseError>, &'a str) {
+	let ty_id = ty_id.into();
+	let opts = ParseOptions::new().bare_variants(true).hex_bytes(true);
+	let (res, remaining) = from_str_with_options(s, &opts);
+	(res.map(|value| coerce(value, ty_id, types, &mut Vec::new())), remaining)
+}
+
+/// Reshape a parsed value to fit the type given where we can, giving each value the ID of the
+/// type that it's for. Anything that we can't make fit is left as it is, so that encoding or
+/// validating the value reports exactly where the problem is.
+///
+/// `wrapping` holds the wrapper types we've descended into without consuming any of the value,
+/// so that we don't go round in circles on self-referential types.
+fn coerce(
+	value: Value<()>,
+	ty_id: TypeId,
+	types: &PortableRegistry,
+	wrapping: &mut Vec<u32>,
+) -> Value<TypeId> {
+	let ty = match types.resolve(ty_id.id()) {
+		Some(ty) if !wrapping.contains(&ty_id.id()) => ty,
+		_ => return with_context(value, ty_id),
+	};
+
+	let value = match (&ty.type_def, value.value) {
+		(TypeDef::Composite(inner), ValueDef::Composite(composite)) => {
+			let composite = coerce_fields(composite, &inner.fields, ty_id, types, wrapping);
+			ValueDef::Composite(composite)
+		}
+		// A value for the only field of a wrapper type like `struct Foo(u32)`:
+		(TypeDef::Composite(inner), value) if inner.fields.len() == 1 => {
+			let composite =
+				wrap(Value { value, context: () }, &inner.fields[0], ty_id, types, wrapping);
+			ValueDef::Composite(composite)
+		}
+		(TypeDef::Tuple(inner), ValueDef::Composite(Composite::Unnamed(values)))
+			if values.len() == inner.fields.len() =>
+		{
+			let values = values
+				.into_iter()
+				.zip(&inner.fields)
+				.map(|(value, field)| coerce(value, field.into(), types, &mut Vec::new()))
+				.collect();
+			ValueDef::Composite(Composite::Unnamed(values))
+		}
+		// A value for the only item in a tuple like `(u32,)`:
+		(TypeDef::Tuple(inner), value) if inner.fields.len() == 1 => {
+			wrapping.push(ty_id.id());
+			let value =
+				coerce(Value { value, context: () }, inner.fields[0].into(), types, wrapping);
+			wrapping.pop();
+			ValueDef::Composite(Composite::Unnamed(vec![value]))
+		}
+		(TypeDef::Variant(inner), ValueDef::Variant(variant)) => {
+			match inner.variants.iter().find(|v| v.name == variant.name) {
+				Some(ty_variant) => ValueDef::Variant(Variant {
+					name: variant.name,
+					values: coerce_fields(
+						variant.values,
+						&ty_variant.fields,
+						ty_id,
+						types,
+						&mut Vec::new(),
+					),
+				}),
+				None => ValueDef::Variant(variant.map_context(|_| ty_id)),
+			}
+		}
+		(TypeDef::Sequence(inner), value) => {
+			coerce_items(value, inner.type_param.into(), ty_id, types)
+		}
+		(TypeDef::Array(inner), value) => {
+			coerce_items(value, inner.type_param.into(), ty_id, types)
+		}
+		(TypeDef::Primitive(inner), ValueDef::Primitive(primitive)) => {
+			ValueDef::Primitive(coerce_primitive(primitive, inner))
+		}
+		// Hex like `0x1234` is parsed into bytes, but is read as a big endian number if
+		// a number is expected.
+		(TypeDef::Primitive(inner), ValueDef::Composite(Composite::Unnamed(values))) => {
+			match bytes_to_number(&values, inner) {
+				Some(primitive) => ValueDef::Primitive(primitive),
+				None => ValueDef::Composite(Composite::Unnamed(values).map_context(|_| ty_id)),
+			}
+		}
+		// Compact values look like the type inside them.
+		(TypeDef::Compact(inner), value) => {
+			coerce(Value { value, context: () }, inner.type_param.into(), types, wrapping).value
+		}
+		(_, value) => value.map_context(|_| ty_id),
+	};
+
+	Value { value, context: ty_id }
+}
+
+/// Reshape the values given to fit some struct or variant fields.
+fn coerce_fields(
+	composite: Composite<()>,
+	fields: &[Field<PortableForm>],
+	ty_id: TypeId,
+	types: &PortableRegistry,
+	wrapping: &mut Vec<u32>,
+) -> Composite<TypeId> {
+	let is_named = fields.first().map(|f| f.name.is_some()).unwrap_or(false);
+	let field_named = |name: &str| fields.iter().find(|f| f.name.as_deref() == Some(name));
+
+	match composite {
+		Composite::Named(values)
+			if is_named && values.iter().all(|(name, _)| field_named(name).is_some()) =>
+		{
+			let values = values
+				.into_iter()
+				.map(|(name, value)| {
+					let field = field_named(&name).expect("all fields were found above");
+					let value = coerce(value, field.ty.into(), types, &mut Vec::new());
+					(name, value)
+				})
+				.collect();
+			Composite::Named(values)
+		}
+		// Unnamed values are matched up with fields in order, and given names if need be.
+		Composite::Unnamed(values) if values.len() == fields.len() => {
+			let values = values.into_iter().zip(fields).map(|(value, field)| {
+				(field, coerce(value, field.ty.into(), types, &mut Vec::new()))
+			});
+			if is_named {
+				Composite::Named(
+					values
+						.map(|(field, value)| (field.name.clone().unwrap_or_default(), value))
+						.collect(),
+				)
+			} else {
+				Composite::Unnamed(values.map(|(_, value)| value).collect())
+			}
+		}
+		// Values which don't fit might be for the only field, like `Id(0x1234..)` for `Id([u8; 32])`.
+		composite if fields.len() == 1 => {
+			let value = Value { value: ValueDef::Composite(composite), context: () };
+			wrap(value, &fields[0], ty_id, types, wrapping)
+		}
+		composite => composite.map_context(|_| ty_id),
+	}
+}
+
+/// Turn a value into the only field of some wrapper type.
+fn wrap(
+	value: Value<()>,
+	field: &Field<PortableForm>,
+	ty_id: TypeId,
+	types: &PortableRegistry,
+	wrapping: &mut Vec<u32>,
+) -> Composite<TypeId> {
+	wrapping.push(ty_id.id());
+	let value = coerce(value, field.ty.into(), types, wrapping);
+	wrapping.pop();
+
+	match &field.name {
+		Some(name) => Composite::Named(vec![(name.clone(), value)]),
+		None => Composite::Unnamed(vec![value]),
+	}
+}
+
+/// Reshape the items in a sequence or array. Strings are accepted as sequences of bytes.
+fn coerce_items(
+	value: ValueDef<()>,
+	item_id: TypeId,
+	ty_id: TypeId,
+	types: &PortableRegistry,
+) -> ValueDef<TypeId> {
+	let is_bytes = matches!(
+		types.resolve(item_id.id()).map(|ty| &ty.type_def),
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+	);
+
+	match value {
+		ValueDef::Composite(Composite::Unnamed(values)) => ValueDef::Composite(Composite::Unnamed(
+			values
+				.into_iter()
+				.map(|value| coerce(value, item_id, types, &mut Vec::new()))
+				.collect(),
+		)),
+		ValueDef::Primitive(Primitive::String(s)) if is_bytes => {
+			ValueDef::Composite(Composite::Unnamed(
+				s.into_bytes()
+					.into_iter()
+					.map(|b| Value::with_context(Primitive::uint(b).into(), item_id))
+					.collect(),
+			))
+		}
+		value => value.map_context(|_| ty_id),
+	}
+}
+
+/// Convert a primitive into the variant that best fits the type given, if it's
+/// a different variant but can be converted without any loss.
+fn coerce_primitive(primitive: Primitive, ty: &TypeDefPrimitive) -> Primitive {
+	use TypeDefPrimitive::*;
+	match (ty, primitive) {
+		(Char, Primitive::String(s)) if s.chars().count() == 1 => {
+			Primitive::Char(s.chars().next().expect("one char; checked above"))
+		}
+		(Str, Primitive::Char(c)) => Primitive::String(c.to_string()),
+		(U8 | U16 | U32 | U64 | U128, Primitive::I128(n)) if n >= 0 => Primitive::U128(n as u128),
+		(I8 | I16 | I32 | I64 | I128, Primitive::U128(n)) if n <= i128::MAX as u128 => {
+			Primitive::I128(n as i128)
+		}
+		(U256, Primitive::U128(n)) => Primitive::U256(extend_to_256(n.to_le_bytes(), 0)),
+		(U256, Primitive::I128(n)) if n >= 0 => Primitive::U256(extend_to_256(n.to_le_bytes(), 0)),
+		(I256, Primitive::U128(n)) => Primitive::I256(extend_to_256(n.to_le_bytes(), 0)),
+		(I256, Primitive::I128(n)) => {
+			let sign_byte = if n < 0 { 0xff } else { 0 };
+			Primitive::I256(extend_to_256(n.to_le_bytes(), sign_byte))
+		}
+		(_, primitive) => primitive,
+	}
+}
+
+/// Read some values which are all bytes as a big endian number of the type given.
+fn bytes_to_number(values: &[Value<()>], ty: &TypeDefPrimitive) -> Option<Primitive> {
+	use TypeDefPrimitive::*;
+	let bytes = values
+		.iter()
+		.map(|v| match v.value {
+			ValueDef::Primitive(Primitive::U128(n)) => u8::try_from(n).ok(),
+			_ => None,
+		})
+		.collect::<Option<Vec<u8>>>()?;
+	if bytes.is_empty() {
+		return None;
+	}
+
+	// Leading zeros don't change the number, so ignore them when checking the size.
+	let first_non_zero = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+	let bytes = &bytes[first_non_zero..];
+	match ty {
+		U8 | U16 | U32 | U64 | U128 | I8 | I16 | I32 | I64 | I128 if bytes.len() <= 16 => {
+			let n = bytes.iter().fold(0u128, |n, &b| n << 8 | b as u128);
+			Some(coerce_primitive(Primitive::U128(n), ty))
+		}
+		U256 | I256 if bytes.len() <= 32 => {
+			let mut n = [0u8; 32];
+			for (out, b) in n.iter_mut().zip(bytes.iter().rev()) {
+				*out = *b;
+			}
+			Some(if *ty == U256 { Primitive::U256(n) } else { Primitive::I256(n) })
+		}
+		_ => None,
+	}
+}
+
+/// Extend the little endian bytes of a 128 bit number to 256 bits.
+fn extend_to_256(bytes: [u8; 16], fill: u8) -> [u8; 32] {
+	let mut out = [fill; 32];
+	out[..16].copy_from_slice(&bytes);
+	out
+}
+
+fn with_context(value: Value<()>, ty_id: TypeId) -> Value<TypeId> {
+	value.map_context(|_| ty_id)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::scale_impls::encode_value_as_type;
+	use codec::{Compact, Encode};
+
+	/// Given a type definition, return the TypeId and PortableRegistry
+	/// that our parsing functions expect.
+	fn make_type<T: scale_info::TypeInfo + 'static>() -> (TypeId, PortableRegistry) {
+		let m = scale_info::MetaType::new::<T>();
+		let mut types = scale_info::Registry::new();
+		let id = types.register_type(&m);
+		let portable_registry: PortableRegistry = types.into();
+
+		(id.into(), portable_registry)
+	}
+
+	/// Parse the string as the type given, and check that it encodes to the same bytes as the
+	/// value given. Return the parsed value without context.
+	fn assert_parses_as<T: Encode + scale_info::TypeInfo + 'static>(
+		s: &str,
+		expected: T,
+	) -> Value<()> {
+		let (id, types) = make_type::<T>();
+		let (res, remaining) = from_str_as_type(s, id, &types);
+		let value = res.expect("can parse");
+		assert_eq!(remaining, "", "should be no unparsed input");
+		assert_eq!(value.context, id, "value should be given the type ID");
+
+		let mut bytes = Vec::new();
+		encode_value_as_type(value.clone(), id, &types, &mut bytes).expect("can encode");
+		assert_eq!(bytes, expected.encode(), "encoded bytes should match for '{s}'");
+		value.remove_context()
+	}
+
+	/// Parse the string as the type given, returning the value without context.
+	fn parse_as<T: scale_info::TypeInfo + 'static>(s: &str) -> Value<()> {
+		let (id, types) = make_type::<T>();
+		from_str_as_type(s, id, &types).0.expect("can parse").remove_context()
+	}
+
+	#[test]
+	fn parses_wrapper_types_from_inner_values() {
+		#[derive(Encode, scale_info::TypeInfo)]
+		struct Wrapper(u32);
+		#[derive(Encode, scale_info::TypeInfo)]
+		struct Named {
+			inner: Wrapper,
+		}
+
+		assert_eq!(assert_parses_as("5", Compact(5u32)), Value::uint(5u8));
+		assert_eq!(
+			assert_parses_as("5", Wrapper(5)),
+			Value::unnamed_composite(vec![Value::uint(5u8)])
+		);
+		assert_eq!(
+			assert_parses_as("5", Named { inner: Wrapper(5) }),
+			Value::named_composite(vec![(
+				"inner".into(),
+				Value::unnamed_composite(vec![Value::uint(5u8)])
+			)])
+		);
+		assert_parses_as("{ inner: 5 }", Named { inner: Wrapper(5) });
+		assert_parses_as("(5)", Named { inner: Wrapper(5) });
+		assert_parses_as("5", Compact(5u64));
+		assert_parses_as("5", (5u8,));
+	}
+
+	#[test]
+	fn parses_options() {
+		assert_parses_as("None", None::<u8>);
+		assert_parses_as("Some(5)", Some(5u8));
+		assert_eq!(
+			assert_parses_as("Some(5)", Some(Compact(5u32))),
+			Value::unnamed_variant("Some", vec![Value::uint(5u8)])
+		);
+	}
+
+	#[test]
+	fn parses_bytes_from_hex_and_strings() {
+		let bytes = [0x12u8; 32];
+		let hex = format!("0x{}", "12".repeat(32));
+		assert_parses_as(&hex, bytes);
+		assert_parses_as("\"hello\"", b"hello".to_vec());
+
+		#[derive(Encode, scale_info::TypeInfo)]
+		enum MultiAddress {
+			Id([u8; 32]),
+		}
+		assert_parses_as(&format!("Id({hex})"), MultiAddress::Id(bytes));
+	}
+
+	#[test]
+	fn parses_primitives_into_the_right_shape() {
+		#[derive(Encode, scale_info::TypeInfo)]
+		struct Foo {
+			a: String,
+			b: i64,
+		}
+
+		assert_eq!(assert_parses_as("'a'", String::from("a")), Value::string("a"));
+		assert_eq!(assert_parses_as("5", 5i8), Value::int(5));
+		assert_eq!(
+			assert_parses_as("(\"a\", 5)", Foo { a: "a".into(), b: 5 }),
+			Value::named_composite(vec![
+				("a".into(), Value::string("a")),
+				("b".into(), Value::int(5))
+			])
+		);
+		assert_eq!(parse_as::<char>("\"a\""), Value::char('a'));
+	}
+
+	#[test]
+	fn parses_hex_as_numbers_where_numbers_are_expected() {
+		assert_eq!(assert_parses_as("0xdeadbeef", 0xdeadbeefu32), Value::uint(0xdeadbeefu32));
+		assert_eq!(assert_parses_as("0x0000ff", 255u8), Value::uint(255u8));
+		assert_eq!(assert_parses_as("0x7f", 127i8), Value::int(127));
+		assert_eq!(assert_parses_as("0x1234", Compact(0x1234u64)), Value::uint(0x1234u16));
+		assert_eq!(assert_parses_as("+0xdead_beef", 0xdeadbeefu64), Value::uint(0xdeadbeefu32));
+
+		// Too many bytes to fit into a u128 are left as they are:
+		let hex = format!("0x01{}", "00".repeat(16));
+		let mut bytes = vec![1u8];
+		bytes.extend([0; 16]);
+		let bytes = Value::unnamed_composite(bytes.into_iter().map(Value::uint).collect());
+		assert_eq!(parse_as::<u128>(&hex), bytes);
+	}
+
+	#[test]
+	fn values_that_do_not_fit_are_left_alone() {
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		struct Foo {
+			a: u8,
+			b: bool,
+		}
+		assert_eq!(
+			parse_as::<Foo>("{ a: 1, c: true }"),
+			Value::named_composite(vec![
+				("a".into(), Value::uint(1u8)),
+				("c".into(), Value::bool(true))
+			])
+		);
+	}
+}
@@ -0,0 +1,455 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::from_string::{from_str, whitespace_len, ParseError};
+use crate::value::Value;
+use std::io::BufRead;
+
+/// An error reading values from some [`BufRead`].
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError {
+	/// The input could not be read.
+	#[error("Failed to read the input: {0}")]
+	Io(#[from] std::io::Error),
+	/// The input could not be parsed into a value.
+	#[error("{0}")]
+	Parse(#[from] ParseError),
+}
+
+/// Parse each of the values in a string, which are separated by whitespace or commas.
+pub fn values_from_str(s: &str) -> ValuesFromStr<'_> {
+	ValuesFromStr { input: s, offset: 0, after_value: false, finished: false }
+}
+
+/// An iterator over the values in a string. This is returned from [`values_from_str`].
+pub struct ValuesFromStr<'a> {
+	input: &'a str,
+	offset: usize,
+	after_value: bool,
+	finished: bool,
+}
+
+impl Iterator for ValuesFromStr<'_> {
+	type Item = Result<Value<()>, ParseError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.finished {
+			return None;
+		}
+
+		self.offset += separator_len(&self.input[self.offset..], self.after_value);
+		let rest = &self.input[self.offset..];
+		if rest.is_empty() {
+			self.finished = true;
+			return None;
+		}
+
+		match from_str(rest) {
+			(Ok(value), remaining) => {
+				self.offset = self.input.len() - remaining.len();
+				self.after_value = true;
+				Some(Ok(value))
+			}
+			// We can't tell where the broken value ends, so we don't look for any more.
+			(Err(e), _) => {
+				self.finished = true;
+				Some(Err(e.offset_by(self.offset)))
+			}
+		}
+	}
+}
+
+/// Parse each of the values read from some [`BufRead`], which are separated by whitespace
+/// or commas.
+pub fn values_from_reader<R: BufRead>(reader: R) -> ValuesFromReader<R> {
+	ValuesFromReader {
+		reader,
+		buf: String::new(),
+		offset: 0,
+		scanner: Scanner::default(),
+		after_value: false,
+		is_eof: false,
+		finished: false,
+	}
+}
+
+/// An iterator over the values read from some [`BufRead`]. This is returned from
+/// [`values_from_reader`].
+pub struct ValuesFromReader<R> {
+	reader: R,
+	// Input that we've read but not yet parsed.
+	buf: String,
+	// The offset of the start of `buf` into the whole input.
+	offset: usize,
+	// Keeps track of whether the input in `buf` could hold a whole value yet.
+	scanner: Scanner,
+	after_value: bool,
+	is_eof: bool,
+	finished: bool,
+}
+
+impl<R: BufRead> ValuesFromReader<R> {
+	// Read another line into the buffer, noting if there's nothing left to read.
+	fn read_line(&mut self) -> Result<(), std::io::Error> {
+		let len = self.buf.len();
+		if self.reader.read_line(&mut self.buf)? == 0 {
+			self.is_eof = true;
+		}
+		self.scanner.scan(&self.buf[len..]);
+		Ok(())
+	}
+
+	// Drop the number of bytes given from the start of the buffer.
+	fn consume(&mut self, n: usize) {
+		self.buf.drain(..n);
+		self.offset += n;
+	}
+
+	fn next_value(&mut self) -> Result<Option<Value<()>>, ReadError> {
+		loop {
+			let sep_len = separator_len(&self.buf, self.after_value);
+			let rest = &self.buf[sep_len..];
+
+			// Only try parsing once there's something to parse that might be a whole value,
+			// so that a value spread over many lines is parsed once rather than after every
+			// line.
+			if !self.is_eof && (rest.is_empty() || !self.scanner.could_be_complete()) {
+				self.read_line()?;
+				continue;
+			}
+			if rest.is_empty() {
+				return Ok(None);
+			}
+
+			match from_str(rest) {
+				(Ok(value), remaining) => {
+					let len = self.buf.len() - remaining.len();
+					self.consume(len);
+					self.after_value = true;
+					return Ok(Some(value));
+				}
+				// Errors at the end of the input might go away once we read more of it.
+				(Err(e), _) if !self.is_eof && e.start_loc >= rest.trim_end().len() => {
+					self.read_line()?;
+				}
+				(Err(e), _) => return Err(e.offset_by(self.offset + sep_len).into()),
+			}
+		}
+	}
+}
+
+impl<R: BufRead> Iterator for ValuesFromReader<R> {
+	type Item = Result<Value<()>, ReadError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.finished {
+			return None;
+		}
+		let res = self.next_value().transpose();
+		// Stop after any errors, since we can't tell where a broken value ends.
+		self.finished = !matches!(res, Some(Ok(_)));
+		res
+	}
+}
+
+/// Looks at input as it's read to keep track of whether we're inside brackets, a string or a
+/// comment, so that we know whether there might be a whole value to parse yet.
+#[derive(Debug, Default)]
+struct Scanner {
+	state: ScanState,
+	// How many brackets are open.
+	depth: usize,
+	// The last word seen outside of strings and comments, if nothing but whitespace
+	// and comments has been seen since.
+	word: String,
+	in_word: bool,
+	// The number of `#`s seen after an `r`, which might be the start of a raw string.
+	raw_hashes: Option<usize>,
+	prev: Option<char>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum ScanState {
+	#[default]
+	Normal,
+	// In a string or char that ends with the quote given.
+	Quoted {
+		quote: char,
+		escaped: bool,
+	},
+	// In a raw string opened with some `#`s, counting the `#`s after a `"` that might close it.
+	Raw {
+		hashes: usize,
+		closing: Option<usize>,
+	},
+	LineComment,
+	// In some number of nested block comments.
+	BlockComment(usize),
+}
+
+impl Scanner {
+	fn scan(&mut self, s: &str) {
+		for c in s.chars() {
+			// Once a pair like `/*` is seen, neither char can be a part of another pair.
+			let mut prev = Some(c);
+			self.state = match self.state {
+				ScanState::Normal => self.scan_normal(c, &mut prev),
+				ScanState::Quoted { quote, escaped } => match c {
+					_ if escaped => ScanState::Quoted { quote, escaped: false },
+					'\\' => ScanState::Quoted { quote, escaped: true },
+					_ if c == quote => ScanState::Normal,
+					_ => ScanState::Quoted { quote, escaped: false },
+				},
+				ScanState::Raw { hashes, closing } => match (c, closing) {
+					('"', _) if hashes == 0 => ScanState::Normal,
+					('"', _) => ScanState::Raw { hashes, closing: Some(0) },
+					('#', Some(n)) if n + 1 == hashes => ScanState::Normal,
+					('#', Some(n)) => ScanState::Raw { hashes, closing: Some(n + 1) },
+					_ => ScanState::Raw { hashes, closing: None },
+				},
+				ScanState::LineComment if c == '\n' => ScanState::Normal,
+				ScanState::LineComment => ScanState::LineComment,
+				ScanState::BlockComment(n) => match (self.prev, c) {
+					(Some('/'), '*') => {
+						prev = None;
+						ScanState::BlockComment(n + 1)
+					}
+					(Some('*'), '/') => {
+						prev = None;
+						if n == 1 {
+							ScanState::Normal
+						} else {
+							ScanState::BlockComment(n - 1)
+						}
+					}
+					_ => ScanState::BlockComment(n),
+				},
+			};
+			self.prev = prev;
+		}
+	}
+
+	fn scan_normal(&mut self, c: char, prev: &mut Option<char>) -> ScanState {
+		let raw_hashes = self.raw_hashes.take();
+		let after_r = self.in_word && self.word == "r";
+		let is_word_char = c.is_alphanumeric() || c == '_';
+		if !is_word_char {
+			self.in_word = false;
+		}
+
+		match c {
+			'/' if self.prev == Some('/') => return ScanState::LineComment,
+			'*' if self.prev == Some('/') => {
+				*prev = None;
+				return ScanState::BlockComment(1);
+			}
+			// A lone `/` isn't valid, so we wait to see if it starts a comment.
+			'/' => return ScanState::Normal,
+			_ if c.is_whitespace() => return ScanState::Normal,
+			_ => {}
+		}
+
+		self.word_ended_by(c, is_word_char);
+		match c {
+			'"' if after_r => ScanState::Raw { hashes: 0, closing: None },
+			'"' => match raw_hashes {
+				Some(hashes) => ScanState::Raw { hashes, closing: None },
+				None => ScanState::Quoted { quote: c, escaped: false },
+			},
+			'\'' => ScanState::Quoted { quote: c, escaped: false },
+			'#' if after_r => {
+				self.raw_hashes = Some(1);
+				ScanState::Normal
+			}
+			'#' => {
+				self.raw_hashes = raw_hashes.map(|n| n + 1);
+				ScanState::Normal
+			}
+			'(' | '{' | '[' | '<' => {
+				self.depth += 1;
+				ScanState::Normal
+			}
+			')' | '}' | ']' | '>' => {
+				self.depth = self.depth.saturating_sub(1);
+				ScanState::Normal
+			}
+			_ => ScanState::Normal,
+		}
+	}
+
+	// Keep track of the last word seen, given the next significant char.
+	fn word_ended_by(&mut self, c: char, is_word_char: bool) {
+		if !is_word_char {
+			self.word.clear();
+		} else if self.in_word {
+			self.word.push(c);
+		} else {
+			self.word.clear();
+			self.word.push(c);
+			self.in_word = true;
+		}
+	}
+
+	/// Could the input scanned so far be a whole value? Not if we're inside some brackets, a
+	/// string or a block comment, or if the input ends with a name, since a variant name
+	/// might be followed by its values on the next line.
+	fn could_be_complete(&self) -> bool {
+		let ends_with_name = self.word.starts_with(char::is_alphabetic)
+			&& self.word != "true"
+			&& self.word != "false";
+		matches!(self.state, ScanState::Normal | ScanState::LineComment)
+			&& self.depth == 0
+			&& !ends_with_name
+	}
+}
+
+// The length of whitespace and comments before the next value, including a comma if one
+// follows a previous value.
+fn separator_len(s: &str, after_value: bool) -> usize {
+	let mut len = whitespace_len(s);
+	if after_value && s[len..].starts_with(',') {
+		len += 1;
+		len += whitespace_len(&s[len..]);
+	}
+	len
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::string_impls::from_string::{ParseComplexError, ParseErrorKind};
+
+	fn values(s: &str) -> Vec<Result<Value<()>, ParseError>> {
+		values_from_str(s).collect()
+	}
+
+	/// Read values from the string, handing over a few bytes at a time so that values are
+	/// split across reads.
+	fn read_values(s: &str) -> Vec<Result<Value<()>, ParseError>> {
+		let reader = std::io::BufReader::with_capacity(3, s.as_bytes());
+		values_from_reader(reader)
+			.map(|res| {
+				res.map_err(|e| match e {
+					ReadError::Parse(e) => e,
+					ReadError::Io(e) => panic!("unexpected IO error: {e}"),
+				})
+			})
+			.collect()
+	}
+
+	#[test]
+	fn parses_separated_values() {
+		let input =
+			"1, (2, 3),\n\n  Foo { a: true } // A comment\n\"four\"\nBar\n(5)\n/* Done, */ ";
+		let expected = vec![
+			Ok(Value::uint(1u8)),
+			Ok(Value::unnamed_composite(vec![Value::uint(2u8), Value::uint(3u8)])),
+			Ok(Value::named_variant("Foo", vec![("a".into(), Value::bool(true))])),
+			Ok(Value::string("four")),
+			Ok(Value::unnamed_variant("Bar", vec![Value::uint(5u8)])),
+		];
+		assert_eq!(values(input), expected);
+		assert_eq!(read_values(input), expected);
+
+		assert_eq!(values(""), vec![]);
+		assert_eq!(read_values(" \n // nothing here\n"), vec![]);
+		assert_eq!(read_values("None()"), vec![Ok(Value::unnamed_variant("None", vec![]))]);
+	}
+
+	#[test]
+	fn error_locations_are_relative_to_the_whole_input() {
+		let input = "1\n2\n{\n  a: 1\n  b: 2\n}\n3";
+		let expected = vec![
+			Ok(Value::uint(1u8)),
+			Ok(Value::uint(2u8)),
+			Err(ParseError {
+				start_loc: 15,
+				end_loc: Some(16),
+				err: ParseComplexError::ExpectedCloserToMatch('}', 4).into(),
+			}),
+		];
+		assert_eq!(values(input), expected);
+		assert_eq!(read_values(input), expected);
+		assert_eq!(expected[2].clone().unwrap_err().start_line_column(input).to_string(), "5:3");
+
+		// Errors at the end of the input are found too:
+		let input = "1, (2,\n3";
+		let expected = vec![
+			Ok(Value::uint(1u8)),
+			Err(ParseError {
+				start_loc: 8,
+				end_loc: Some(9),
+				err: ParseComplexError::ExpectedCloserToMatch(')', 3).into(),
+			}),
+		];
+		assert_eq!(values(input), expected);
+		assert_eq!(read_values(input), expected);
+
+		// Commas need a value between them:
+		let expected = vec![
+			Ok(Value::uint(1u8)),
+			Err(ParseError { start_loc: 3, end_loc: None, err: ParseErrorKind::ExpectedValue }),
+		];
+		assert_eq!(values("1, , 2"), expected);
+		assert_eq!(read_values("1, , 2"), expected);
+	}
+
+	#[test]
+	fn variant_names_join_with_values_on_the_next_line() {
+		// A name at the end of a line might be followed by its values on the next one, and
+		// so `Foo` and `(1)` on separate lines are the single value `Foo(1)`:
+		let input = "Foo\n(1)\nBar\n\n{ a: true }";
+		let expected = vec![
+			Ok(Value::unnamed_variant("Foo", vec![Value::uint(1u8)])),
+			Ok(Value::named_variant("Bar", vec![("a".into(), Value::bool(true))])),
+		];
+		assert_eq!(values(input), expected);
+		assert_eq!(read_values(input), expected);
+	}
+
+	#[test]
+	fn values_are_read_once_they_might_be_complete() {
+		let complete_after = |lines: &[&str]| {
+			let mut scanner = Scanner::default();
+			lines
+				.iter()
+				.map(|line| {
+					scanner.scan(line);
+					scanner.could_be_complete()
+				})
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(
+			complete_after(&["{\n", "  a: (1,\n", "2)\n", "}\n"]),
+			[false, false, false, true]
+		);
+		assert_eq!(complete_after(&["\"a ) \\\" (\n", "b\"\n"]), [false, true]);
+		assert_eq!(complete_after(&["r#\"say \"hi\" (\n", "\"#\n"]), [false, true]);
+		assert_eq!(complete_after(&["'(' ')'\n"]), [true]);
+		assert_eq!(complete_after(&["1 // a ( comment\n"]), [true]);
+		assert_eq!(complete_after(&["1 /* a /* nested */\n", "comment */\n"]), [false, true]);
+		assert_eq!(complete_after(&["Foo // a name\n", "(1)\n"]), [false, true]);
+		assert_eq!(complete_after(&["true\n", "0xdead_beef\n", "12.5\n"]), [true, true, true]);
+	}
+
+	#[test]
+	fn io_errors_are_returned() {
+		let invalid_utf8: &[u8] = &[b'1', 0xff, b'\n'];
+		let mut values = values_from_reader(invalid_utf8);
+		assert!(matches!(values.next(), Some(Err(ReadError::Io(_)))));
+		assert!(values.next().is_none());
+	}
+}
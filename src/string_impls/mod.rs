@@ -13,10 +13,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "from_string")]
+mod diagnostic;
 #[cfg(feature = "from_string")]
 mod from_string;
+#[cfg(feature = "from_string")]
+mod from_string_as_type;
+#[cfg(feature = "from_string")]
+mod from_string_stream;
 mod string_helpers;
 mod to_string;
 
 #[cfg(feature = "from_string")]
-pub use from_string::{from_str, ParseError};
+pub use diagnostic::LineColumn;
+#[cfg(feature = "from_string")]
+pub use from_string::{
+	from_str, from_str_recovering, from_str_with_options, ParseError, ParseOptions,
+};
+#[cfg(feature = "from_string")]
+pub use from_string_as_type::from_str_as_type;
+#[cfg(feature = "from_string")]
+pub use from_string_stream::{values_from_reader, values_from_str, ReadError};
+pub use to_string::{to_string_with_options, FormatOptions};
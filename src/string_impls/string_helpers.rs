@@ -29,6 +29,32 @@ pub fn to_escape_code(c: char) -> Option<char> {
 	Some(escaped)
 }
 
+/// Is the char one that can be seen (or at least one that takes up space, like a
+/// normal space). Chars which aren't are escaped when we format strings, so that
+/// nothing is hidden.
+pub fn is_printable(c: char) -> bool {
+	if c == ' ' {
+		return true;
+	}
+	if c.is_control() || c.is_whitespace() {
+		return false;
+	}
+	// Invisible formatting characters, like zero width spaces and direction overrides:
+	!matches!(
+		c,
+		'\u{ad}'
+			| '\u{61c}'
+			| '\u{180e}'
+			| '\u{200b}'..='\u{200f}'
+			| '\u{202a}'..='\u{202e}'
+			| '\u{2060}'..='\u{2064}'
+			| '\u{2066}'..='\u{206f}'
+			| '\u{feff}'
+			| '\u{fff9}'..='\u{fffb}'
+			| '\u{e0000}'..='\u{e007f}'
+	)
+}
+
 /// Given some escape code (char following a '\'), return the
 /// unescaped char that it represents, or None if it is not a
 /// valid escape code.
@@ -46,3 +72,90 @@ pub fn from_escape_code(c: char) -> Option<char> {
 	};
 	Some(unescaped)
 }
+
+/// Format the little endian bytes of an unsigned 256 bit number as a decimal string.
+pub fn u256_to_decimal(bytes: &[u8; 32]) -> String {
+	let mut n = *bytes;
+	let mut digits = Vec::new();
+	loop {
+		// Divide by 10, starting from the most significant byte, and keep the remainder.
+		let mut rem = 0u16;
+		for byte in n.iter_mut().rev() {
+			let v = (rem << 8) | *byte as u16;
+			*byte = (v / 10) as u8;
+			rem = v % 10;
+		}
+		digits.push(b'0' + rem as u8);
+		if n.iter().all(|&b| b == 0) {
+			break;
+		}
+	}
+	digits.reverse();
+	String::from_utf8(digits).expect("only ASCII digits were pushed")
+}
+
+/// Format the little endian, two's complement bytes of a signed 256 bit number as a
+/// decimal string.
+pub fn i256_to_decimal(bytes: &[u8; 32]) -> String {
+	if is_negative_i256(bytes) {
+		format!("-{}", u256_to_decimal(&negate_256(bytes)))
+	} else {
+		u256_to_decimal(bytes)
+	}
+}
+
+/// Parse a string of digits in the given base into the little endian bytes of an unsigned
+/// 256 bit number, returning None if it is not valid or does not fit.
+#[cfg(feature = "from_string")]
+pub fn u256_from_str_radix(digits: &str, radix: u32) -> Option<[u8; 32]> {
+	if digits.is_empty() {
+		return None;
+	}
+	let mut out = [0u8; 32];
+	for digit in digits.chars() {
+		// Multiply by the base and add the digit, starting from the least significant byte.
+		let mut carry = digit.to_digit(radix)? as u16;
+		for byte in out.iter_mut() {
+			let v = *byte as u16 * radix as u16 + carry;
+			*byte = v as u8;
+			carry = v >> 8;
+		}
+		if carry != 0 {
+			return None;
+		}
+	}
+	Some(out)
+}
+
+/// Parse a string of digits in the given base, and whether they are negative or not, into
+/// the little endian, two's complement bytes of a signed 256 bit number, returning None
+/// if it is not valid or does not fit.
+#[cfg(feature = "from_string")]
+pub fn i256_from_str_radix(digits: &str, radix: u32, is_negative: bool) -> Option<[u8; 32]> {
+	let magnitude = u256_from_str_radix(digits, radix)?;
+	if !is_negative_i256(&magnitude) {
+		Some(if is_negative { negate_256(&magnitude) } else { magnitude })
+	} else if is_negative && negate_256(&magnitude) == magnitude {
+		// The only value with the sign bit set that fits is the minimum value, which
+		// is its own two's complement.
+		Some(magnitude)
+	} else {
+		None
+	}
+}
+
+fn is_negative_i256(bytes: &[u8; 32]) -> bool {
+	bytes[31] & 0x80 != 0
+}
+
+/// Negate a two's complement 256 bit number (invert the bits and add one).
+fn negate_256(bytes: &[u8; 32]) -> [u8; 32] {
+	let mut out = [0u8; 32];
+	let mut carry = 1u16;
+	for (o, b) in out.iter_mut().zip(bytes) {
+		let v = (!*b) as u16 + carry;
+		*o = v as u8;
+		carry = v >> 8;
+	}
+	out
+}
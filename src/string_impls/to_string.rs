@@ -17,6 +17,95 @@ use super::string_helpers;
 use crate::value::{BitSequence, Composite, Primitive, Value, ValueDef, Variant};
 use std::fmt::{Display, Write};
 
+/// Options to configure how a [`Value`] is formatted into a string. The default options
+/// match the output of `value.to_string()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+	hex_bytes: bool,
+	raw_strings: bool,
+	indent: Option<usize>,
+	// The field name, unit symbol and decimals of each unit to format numbers with.
+	units: Vec<(String, String, u32)>,
+}
+
+impl FormatOptions {
+	/// Create the default formatting options.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// If true, sequences of bytes (that is, non-empty unnamed composites which contain only
+	/// unsigned numbers no larger than 255) are formatted as hex literals like `0xdeadbeef`
+	/// rather than like `(222, 173, 190, 239)`. Hex literals parse back into numbers (or into
+	/// bytes, if there are more than 32 of them), which encode into the same bytes for arrays
+	/// of `u8`, and for sequences of `u8` that don't start with a zero byte.
+	/// [`crate::stringify::from_str_as_type`] parses them back into the same bytes for both.
+	pub fn hex_bytes(mut self, hex_bytes: bool) -> Self {
+		self.hex_bytes = hex_bytes;
+		self
+	}
+
+	/// If true, string values which contain quotes or backslashes are formatted as raw
+	/// strings like `r#"C:\path "with" quotes"#` where possible, rather than escaping them.
+	pub fn raw_strings(mut self, raw_strings: bool) -> Self {
+		self.raw_strings = raw_strings;
+		self
+	}
+
+	/// Format the value over multiple lines, indenting nested values by the number of
+	/// spaces given. Named composites are given a line per field, and unnamed composites
+	/// are kept on one line if they are short enough, and wrapped onto more lines if not.
+	pub fn indent(mut self, spaces: usize) -> Self {
+		self.indent = Some(spaces);
+		self
+	}
+
+	/// Format unsigned integers in fields with the name given as an amount of the unit given,
+	/// like `value: 12.5 DOT`, where the unit has the number of decimals given (`125_000_000_000`
+	/// would be `12.5 DOT` if `DOT` has 10 decimals). Only numbers which are the whole value of
+	/// such a field are formatted this way, so that other numbers, like a `nonce: 3`, are left
+	/// alone. Any number of fields can be given a unit, and giving a field a unit again replaces
+	/// it. The output parses back into the same value if the parser is told about the same unit.
+	pub fn unit_for_field(
+		mut self,
+		field: impl Into<String>,
+		symbol: impl Into<String>,
+		decimals: u32,
+	) -> Self {
+		let field = field.into();
+		self.units.retain(|(f, _, _)| *f != field);
+		self.units.push((field, symbol.into(), decimals));
+		self
+	}
+
+	/// The unit symbol and decimals for numbers in the field given, if it has been given a unit.
+	fn unit_for_field_named(&self, field: &str) -> Option<(&str, u32)> {
+		self.units.iter().find(|(f, _, _)| f == field).map(|(_, s, d)| (s.as_str(), *d))
+	}
+
+	/// The options used when a value is formatted with `{}` or `{:#}`.
+	fn from_formatter(f: &std::fmt::Formatter<'_>) -> Self {
+		if f.alternate() {
+			Self::new().indent(DEFAULT_INDENT)
+		} else {
+			Self::new()
+		}
+	}
+}
+
+/// How many spaces to indent by when a value is formatted with `{:#}`.
+const DEFAULT_INDENT: usize = 4;
+
+/// When formatting over multiple lines, try not to let lines get longer than this.
+const MAX_LINE_WIDTH: usize = 80;
+
+/// Format a value into a string according to the options given.
+pub fn to_string_with_options<T>(value: &Value<T>, options: &FormatOptions) -> String {
+	let mut out = String::new();
+	Printer::new(&mut out, options).value(&value.value).expect("writing to a String cannot fail");
+	out
+}
+
 impl<T> Display for Value<T> {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		self.value.fmt(f)
@@ -25,108 +114,316 @@ impl<T> Display for Value<T> {
 
 impl<T> Display for ValueDef<T> {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		match self {
-			ValueDef::Composite(c) => c.fmt(f),
-			ValueDef::Variant(v) => v.fmt(f),
-			ValueDef::BitSequence(b) => fmt_bitsequence(b, f),
-			ValueDef::Primitive(p) => p.fmt(f),
-		}
+		let options = FormatOptions::from_formatter(f);
+		Printer::new(f, &options).value(self)
 	}
 }
 
 impl<T> Display for Composite<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let options = FormatOptions::from_formatter(f);
+		Printer::new(f, &options).composite(self)
+	}
+}
+
+impl<T> Display for Variant<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let options = FormatOptions::from_formatter(f);
+		Printer::new(f, &options).variant(self)
+	}
+}
+
+impl Display for Primitive {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
+			Primitive::Bool(true) => f.write_str("true"),
+			Primitive::Bool(false) => f.write_str("false"),
+			Primitive::Char(c) => fmt_char(*c, f),
+			Primitive::I128(n) => n.fmt(f),
+			Primitive::U128(n) => n.fmt(f),
+			Primitive::String(s) => fmt_string(s, f),
+			// 256 bit numbers are given a suffix so that we know to parse them back into
+			// the same type, regardless of their size:
+			Primitive::U256(n) => write!(f, "{}u256", string_helpers::u256_to_decimal(n)),
+			Primitive::I256(n) => write!(f, "{}i256", string_helpers::i256_to_decimal(n)),
+		}
+	}
+}
+
+/// Write values out according to some [`FormatOptions`].
+struct Printer<'a, W> {
+	out: &'a mut W,
+	options: &'a FormatOptions,
+	// How many levels deep into nested values we are, for indenting.
+	depth: usize,
+}
+
+impl<'a, W: Write> Printer<'a, W> {
+	fn new(out: &'a mut W, options: &'a FormatOptions) -> Self {
+		Printer { out, options, depth: 0 }
+	}
+
+	fn value<T>(&mut self, value: &ValueDef<T>) -> std::fmt::Result {
+		match value {
+			ValueDef::Composite(c) => self.composite(c),
+			ValueDef::Variant(v) => self.variant(v),
+			ValueDef::BitSequence(b) => fmt_bitsequence(b, self.out),
+			ValueDef::Primitive(Primitive::String(s)) if self.options.raw_strings => {
+				if !fmt_raw_string(s, self.out)? {
+					fmt_string(s, self.out)?;
+				}
+				Ok(())
+			}
+			ValueDef::Primitive(p) => write!(self.out, "{p}"),
+		}
+	}
+
+	fn composite<T>(&mut self, composite: &Composite<T>) -> std::fmt::Result {
+		if let Composite::Unnamed(vals) = composite {
+			if self.options.hex_bytes {
+				if let Some(bytes) = as_bytes(vals) {
+					return fmt_hex(&bytes, self.out);
+				}
+			}
+		}
+		match self.options.indent {
+			None => self.composite_inline(composite),
+			Some(indent) => self.composite_pretty(composite, indent),
+		}
+	}
+
+	fn composite_inline<T>(&mut self, composite: &Composite<T>) -> std::fmt::Result {
+		match composite {
 			Composite::Named(vals) => {
-				f.write_str("{ ")?;
+				self.out.write_str("{ ")?;
 				for (idx, (name, val)) in vals.iter().enumerate() {
 					if idx != 0 {
-						f.write_str(", ")?;
+						self.out.write_str(", ")?;
 					}
-					if is_ident(name) {
-						f.write_str(name)?;
-					} else {
-						fmt_string(name, f)?;
-					}
-					f.write_str(": ")?;
-					val.fmt(f)?;
+					self.field(name, &val.value)?;
 				}
-				f.write_str(" }")?;
+				self.out.write_str(" }")?;
 			}
 			Composite::Unnamed(vals) => {
-				f.write_char('(')?;
+				self.out.write_char('(')?;
 				for (idx, val) in vals.iter().enumerate() {
 					if idx != 0 {
-						f.write_str(", ")?;
+						self.out.write_str(", ")?;
 					}
-					val.fmt(f)?;
+					self.value(&val.value)?;
 				}
-				f.write_char(')')?;
+				self.out.write_char(')')?;
 			}
 		}
 		Ok(())
 	}
-}
 
-impl<T> Display for Variant<T> {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		if is_ident(&self.name) {
-			f.write_str(&self.name)?;
+	fn composite_pretty<T>(&mut self, composite: &Composite<T>, indent: usize) -> std::fmt::Result {
+		match composite {
+			Composite::Named(vals) if vals.is_empty() => self.out.write_str("{}"),
+			Composite::Named(vals) => {
+				self.out.write_char('{')?;
+				self.depth += 1;
+				for (idx, (name, val)) in vals.iter().enumerate() {
+					if idx != 0 {
+						self.out.write_char(',')?;
+					}
+					self.newline(indent)?;
+					self.field(name, &val.value)?;
+				}
+				self.depth -= 1;
+				self.newline(indent)?;
+				self.out.write_char('}')
+			}
+			Composite::Unnamed(vals) => {
+				// Keep things on one line if they are short enough:
+				let inline = self.inline_string(|p| p.composite(composite));
+				if self.depth * indent + inline.chars().count() <= MAX_LINE_WIDTH {
+					return self.out.write_str(&inline);
+				}
+
+				self.out.write_char('(')?;
+				self.depth += 1;
+				if vals.iter().all(|v| matches!(v.value, ValueDef::Primitive(_))) {
+					// Long sequences of primitive values are wrapped, fitting as many
+					// on each line as we can.
+					let width = MAX_LINE_WIDTH.saturating_sub(self.depth * indent);
+					let mut line_len = 0;
+					for (idx, val) in vals.iter().enumerate() {
+						let s = self.inline_string(|p| p.value(&val.value));
+						let len = s.chars().count();
+						if idx == 0 {
+							self.newline(indent)?;
+						} else if line_len + len + 3 > width {
+							// Leave room for the ", " before and "," after the value.
+							self.out.write_char(',')?;
+							self.newline(indent)?;
+							line_len = 0;
+						} else {
+							self.out.write_str(", ")?;
+							line_len += 2;
+						}
+						self.out.write_str(&s)?;
+						line_len += len;
+					}
+				} else {
+					for (idx, val) in vals.iter().enumerate() {
+						if idx != 0 {
+							self.out.write_char(',')?;
+						}
+						self.newline(indent)?;
+						self.value(&val.value)?;
+					}
+				}
+				self.depth -= 1;
+				self.newline(indent)?;
+				self.out.write_char(')')
+			}
+		}
+	}
+
+	fn variant<T>(&mut self, variant: &Variant<T>) -> std::fmt::Result {
+		if is_ident(&variant.name) && variant.name != "true" && variant.name != "false" {
+			self.out.write_str(&variant.name)?;
 		} else {
 			// If the variant name isn't a valid ident, we parse it into
 			// a special "v" prefixed string to allow arbitrary content while
 			// keeping it easy to parse variant names with minimal lookahead.
 			// Most use cases should never see or care about this.
-			f.write_char('v')?;
-			fmt_string(&self.name, f)?;
+			self.out.write_char('v')?;
+			fmt_string(&variant.name, self.out)?;
 		}
-		self.values.fmt(f)
+		if self.options.indent.is_some() && matches!(variant.values, Composite::Named(_)) {
+			self.out.write_char(' ')?;
+		}
+		self.composite(&variant.values)
 	}
-}
 
-impl Display for Primitive {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		match self {
-			Primitive::Bool(true) => f.write_str("true"),
-			Primitive::Bool(false) => f.write_str("false"),
-			Primitive::Char(c) => fmt_char(*c, f),
-			Primitive::I128(n) => n.fmt(f),
-			Primitive::U128(n) => n.fmt(f),
-			Primitive::String(s) => fmt_string(s, f),
-			// We don't currently have a sane way to parse into these or
-			// format out of them:
-			Primitive::U256(_) | Primitive::I256(_) => Err(std::fmt::Error),
+	fn field<T>(&mut self, name: &str, value: &ValueDef<T>) -> std::fmt::Result {
+		if is_ident(name) {
+			self.out.write_str(name)?;
+		} else {
+			fmt_string(name, self.out)?;
+		}
+		self.out.write_str(": ")?;
+
+		match (value, self.options.unit_for_field_named(name)) {
+			(ValueDef::Primitive(Primitive::U128(n)), Some((symbol, decimals))) => {
+				fmt_unit(*n, symbol, decimals, self.out)
+			}
+			_ => self.value(value),
 		}
 	}
+
+	fn newline(&mut self, indent: usize) -> std::fmt::Result {
+		self.out.write_char('\n')?;
+		for _ in 0..self.depth * indent {
+			self.out.write_char(' ')?;
+		}
+		Ok(())
+	}
+
+	/// Format something on a single line, to see how long it is.
+	fn inline_string(
+		&self,
+		f: impl FnOnce(&mut Printer<'_, String>) -> std::fmt::Result,
+	) -> String {
+		let options = FormatOptions { indent: None, ..self.options.clone() };
+		let mut out = String::new();
+		f(&mut Printer::new(&mut out, &options)).expect("writing to a String cannot fail");
+		out
+	}
+}
+
+/// If the values given are all numbers that fit into a u8, return them as bytes. Empty
+/// composites are left alone, since `()` is a clearer way to write them.
+fn as_bytes<T>(vals: &[Value<T>]) -> Option<Vec<u8>> {
+	if vals.is_empty() {
+		return None;
+	}
+	vals.iter()
+		.map(|val| match val.value {
+			ValueDef::Primitive(Primitive::U128(n)) => u8::try_from(n).ok(),
+			_ => None,
+		})
+		.collect()
+}
+
+fn fmt_hex(bytes: &[u8], f: &mut impl Write) -> std::fmt::Result {
+	f.write_str("0x")?;
+	for byte in bytes {
+		write!(f, "{byte:02x}")?;
+	}
+	Ok(())
+}
+
+/// Format a number of the smallest units of something as a decimal number of the unit
+/// given, for example `12.5 DOT`.
+fn fmt_unit(n: u128, symbol: &str, decimals: u32, f: &mut impl Write) -> std::fmt::Result {
+	let digits = n.to_string();
+	let decimals = decimals as usize;
+	let (int, frac) = if digits.len() > decimals {
+		let (int, frac) = digits.split_at(digits.len() - decimals);
+		(int.to_owned(), frac.to_owned())
+	} else {
+		("0".to_owned(), format!("{digits:0>decimals$}"))
+	};
+
+	let frac = frac.trim_end_matches('0');
+	if frac.is_empty() {
+		write!(f, "{int} {symbol}")
+	} else {
+		write!(f, "{int}.{frac} {symbol}")
+	}
 }
 
-fn fmt_string(s: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+fn fmt_string(s: &str, f: &mut impl Write) -> std::fmt::Result {
 	f.write_char('"')?;
 	for char in s.chars() {
-		match string_helpers::to_escape_code(char) {
-			Some(escaped) => {
-				f.write_char('\\')?;
-				f.write_char(escaped)?
-			}
-			None => f.write_char(char)?,
-		}
+		fmt_escaped(char, f)?;
 	}
 	f.write_char('"')
 }
 
-fn fmt_char(c: char, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// Format a string as a raw string like `r#"say "hi""#` if it contains quotes or backslashes
+/// that would otherwise need escaping, and nothing that needs escaping regardless. Returns
+/// false if nothing was written.
+fn fmt_raw_string(s: &str, f: &mut impl Write) -> Result<bool, std::fmt::Error> {
+	let wants_raw = s.contains(['"', '\\']);
+	let can_be_raw = s.chars().all(string_helpers::is_printable);
+	if !wants_raw || !can_be_raw {
+		return Ok(false);
+	}
+
+	// Use just enough `#`'s that the closing `"#..` can't appear in the string.
+	let mut hashes = String::new();
+	while s.contains(&format!("\"{hashes}")) {
+		hashes.push('#');
+	}
+	write!(f, "r{hashes}\"{s}\"{hashes}")?;
+	Ok(true)
+}
+
+fn fmt_char(c: char, f: &mut impl Write) -> std::fmt::Result {
 	f.write_char('\'')?;
+	fmt_escaped(c, f)?;
+	f.write_char('\'')
+}
+
+/// Write a char that's inside quotes, escaping it if it has an escape code or
+/// would not be visible.
+fn fmt_escaped(c: char, f: &mut impl Write) -> std::fmt::Result {
 	match string_helpers::to_escape_code(c) {
 		Some(escaped) => {
 			f.write_char('\\')?;
-			f.write_char(escaped)?
+			f.write_char(escaped)
 		}
-		None => f.write_char(c)?,
+		None if !string_helpers::is_printable(c) => write!(f, "\\u{{{:x}}}", c as u32),
+		None => f.write_char(c),
 	}
-	f.write_char('\'')
 }
 
-fn fmt_bitsequence(b: &BitSequence, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+fn fmt_bitsequence(b: &BitSequence, f: &mut impl Write) -> std::fmt::Result {
 	f.write_char('<')?;
 	for bit in b.iter() {
 		match bit.as_ref() {
@@ -139,12 +436,11 @@ fn fmt_bitsequence(b: &BitSequence, f: &mut std::fmt::Formatter<'_>) -> std::fmt
 
 /// Is the string provided a valid ident (as per from_string::parse_ident).
 fn is_ident(s: &str) -> bool {
-	for (idx, c) in s.chars().enumerate() {
-		if (idx == 0 && !c.is_alphabetic()) || !c.is_alphanumeric() || c != '_' {
-			return false;
-		}
+	let mut chars = s.chars();
+	match chars.next() {
+		Some(c) if c.is_alphabetic() => chars.all(|c| c.is_alphanumeric() || c == '_'),
+		_ => false,
 	}
-	true
 }
 
 #[cfg(test)]
@@ -153,19 +449,25 @@ fn is_ident(s: &str) -> bool {
 mod test {
 	use super::*;
 
-	fn assert_from_to<T: std::fmt::Debug + PartialEq>(val: Value<T>) {
-		let s = val.to_string();
-		match crate::stringify::from_str(&s) {
-			(Err(e), _) => {
-				panic!("'{s}' cannot be parsed back into the value {val:?}: {e}");
-			}
-			(Ok(new_val), rest) => {
-				assert_eq!(
-					val.remove_context(),
-					new_val,
-					"value should be the same after parsing to/from a string"
-				);
-				assert_eq!(rest.len(), 0, "there should be no unparsed string but got '{rest}'");
+	fn assert_from_to<T: std::fmt::Debug + PartialEq + Clone>(val: Value<T>) {
+		// Both the normal and the pretty output should parse back into the same value:
+		for s in [val.to_string(), format!("{val:#}")] {
+			match crate::stringify::from_str(&s) {
+				(Err(e), _) => {
+					panic!("'{s}' cannot be parsed back into the value {val:?}: {e}");
+				}
+				(Ok(new_val), rest) => {
+					assert_eq!(
+						val.clone().remove_context(),
+						new_val,
+						"value should be the same after parsing to/from a string"
+					);
+					assert_eq!(
+						rest.len(),
+						0,
+						"there should be no unparsed string but got '{rest}'"
+					);
+				}
 			}
 		}
 	}
@@ -185,6 +487,16 @@ mod test {
 		assert_from_to(Value::uint(0u128));
 		assert_from_to(Value::uint(123456u128));
 
+		assert_from_to(Value::primitive(Primitive::U256([0; 32])));
+		assert_from_to(Value::primitive(Primitive::U256([255; 32])));
+		assert_from_to(Value::primitive(Primitive::U256([1; 32])));
+		assert_from_to(Value::primitive(Primitive::I256([0; 32])));
+		assert_from_to(Value::primitive(Primitive::I256([255; 32])));
+		assert_from_to(Value::primitive(Primitive::I256([1; 32])));
+		let mut min_i256 = [0; 32];
+		min_i256[31] = 0x80;
+		assert_from_to(Value::primitive(Primitive::I256(min_i256)));
+
 		assert_from_to(Value::string("hello \"you\",\n\n\t How are you??"));
 		assert_from_to(Value::string(""));
 	}
@@ -217,6 +529,18 @@ mod test {
 			"MyVariant",
 			vec![Value::uint(12345u128), Value::bool(true), Value::string("Woop!")],
 		));
+		assert_from_to(Value::unnamed_variant("true", vec![]));
+		assert_from_to(Value::unnamed_variant("trueish", vec![]));
+	}
+
+	#[test]
+	fn idents_are_not_quoted() {
+		let value = Value::named_composite(vec![
+			("a".into(), Value::unnamed_variant("Foo", vec![Value::uint(1u8)])),
+			("b c".into(), Value::unnamed_variant("Bar Baz", vec![])),
+			("d".into(), Value::unnamed_variant("true", vec![])),
+		]);
+		assert_eq!(value.to_string(), r#"{ a: Foo(1), "b c": v"Bar Baz"(), d: v"true"() }"#);
 	}
 
 	#[test]
@@ -225,4 +549,169 @@ mod test {
 		assert_from_to(Value::bit_sequence(bitvec![u8, Lsb0; 0,1,1,0,1,1,0]));
 		assert_from_to(Value::bit_sequence(bitvec![u8, Lsb0;]));
 	}
+
+	#[test]
+	fn non_printable_chars_are_escaped() {
+		assert_eq!(Value::string("a\u{1b}[0m\u{7f}").to_string(), "\"a\\u{1b}[0m\\u{7f}\"");
+		assert_eq!(Value::string("zero\u{200b}width").to_string(), "\"zero\\u{200b}width\"");
+		assert_eq!(Value::char('\u{202e}').to_string(), "'\\u{202e}'");
+		assert_eq!(Value::string("new\u{2028}line").to_string(), "\"new\\u{2028}line\"");
+		assert_eq!(Value::string("héllo 😀 wörld").to_string(), "\"héllo 😀 wörld\"");
+
+		assert_from_to(Value::string("\u{0}\u{1}\u{1b}\u{7f}\u{85}\u{200b}\u{feff}\u{e0001}"));
+		assert_from_to(Value::char('\u{1b}'));
+		assert_from_to(Value::char('\u{feff}'));
+	}
+
+	#[test]
+	fn raw_strings() {
+		let options = FormatOptions::new().raw_strings(true);
+		let raw = |s: &str| to_string_with_options(&Value::string(s), &options);
+
+		assert_eq!(raw(r"C:\path"), r#"r"C:\path""#);
+		assert_eq!(raw(r#"say "hi""#), r##"r#"say "hi""#"##);
+		assert_eq!(raw(r##"a "# b"##), r###"r##"a "# b"##"###);
+		// Strings which don't need escaping, or which need escaping regardless,
+		// aren't raw strings:
+		assert_eq!(raw("hello"), r#""hello""#);
+		assert_eq!(raw("\"hello\"\n"), r#""\"hello\"\n""#);
+
+		for s in [r"C:\path", r#"say "hi""#, r##"a "# b"##, "\"#\"##\"#"] {
+			let formatted = raw(s);
+			assert_eq!(crate::stringify::from_str(&formatted).0, Ok(Value::string(s)));
+		}
+	}
+
+	#[test]
+	fn units() {
+		let options = FormatOptions::new().unit_for_field("value", "DOT", 10);
+		let value = |n: Value<()>| Value::named_composite(vec![("value".into(), n)]);
+		let fmt = |n: u128| to_string_with_options(&value(Value::uint(n)), &options);
+		assert_eq!(fmt(125_000_000_000), "{ value: 12.5 DOT }");
+		assert_eq!(fmt(10_000_000_000), "{ value: 1 DOT }");
+		assert_eq!(fmt(0), "{ value: 0 DOT }");
+		assert_eq!(fmt(1), "{ value: 0.0000000001 DOT }");
+		assert_eq!(fmt(u128::MAX), "{ value: 34028236692093846346337460743.1768211455 DOT }");
+
+		// Only unsigned numbers that are the whole value of the fields named are affected:
+		let transfer = Value::named_composite(vec![
+			("nonce".into(), Value::uint(3u8)),
+			("value".into(), Value::uint(12_345_000_000u64)),
+			("refund".into(), value(Value::int(-5))),
+			("fees".into(), Value::unnamed_composite(vec![Value::uint(12_345_000_000u64)])),
+			("value_bytes".into(), Value::unnamed_composite(vec![Value::uint(1u8)])),
+		]);
+		let s = to_string_with_options(&transfer, &options);
+		assert_eq!(
+			s,
+			"{ nonce: 3, value: 1.2345 DOT, refund: { value: -5 }, fees: (12345000000), value_bytes: (1) }"
+		);
+		assert_eq!(to_string_with_options(&Value::uint(3u8), &options), "3");
+
+		// Everything parses back to the same value given the same unit:
+		let parse_options = crate::stringify::ParseOptions::new().unit("DOT", 10);
+		assert_eq!(crate::stringify::from_str_with_options(&s, &parse_options).0, Ok(transfer));
+		let max = value(Value::uint(u128::MAX));
+		let s =
+			to_string_with_options(&max, &FormatOptions::new().unit_for_field("value", "DOT", 40));
+		assert_eq!(s, "{ value: 0.0340282366920938463463374607431768211455 DOT }");
+		assert_eq!(
+			crate::stringify::from_str_with_options(&s, &parse_options.unit("DOT", 40)).0,
+			Ok(max)
+		);
+	}
+
+	#[test]
+	fn hex_bytes() {
+		let options = FormatOptions::new().hex_bytes(true);
+		let bytes = Value::unnamed_composite(vec![Value::uint(0xdeu8), Value::uint(0x0fu8)]);
+		assert_eq!(to_string_with_options(&bytes, &options), "0xde0f");
+		assert_eq!(bytes.to_string(), "(222, 15)");
+
+		// Only composites of u8's are formatted as hex:
+		let not_bytes = Value::unnamed_composite(vec![Value::uint(1u8), Value::uint(256u16)]);
+		assert_eq!(to_string_with_options(&not_bytes, &options), "(1, 256)");
+		let empty = Value::unnamed_composite(vec![]);
+		assert_eq!(to_string_with_options(&empty, &options), "()");
+
+		// Nested byte sequences are found too, and everything parses back into values which
+		// encode to the same bytes:
+		let nested = Value::unnamed_composite(vec![
+			bytes.clone(),
+			Value::unnamed_composite(vec![bytes, Value::bool(true)]),
+		]);
+		let s = to_string_with_options(&nested, &options);
+		assert_eq!(s, "(0xde0f, (0xde0f, true))");
+
+		let (types, ty) = {
+			let m = scale_info::MetaType::new::<(Vec<u8>, ([u8; 2], bool))>();
+			let mut types = scale_info::Registry::new();
+			let ty = types.register_type(&m);
+			(scale_info::PortableRegistry::from(types), ty.id)
+		};
+		let encode = |value| {
+			let mut bytes = Vec::new();
+			crate::scale::encode_as_type(value, ty, &types, &mut bytes).unwrap();
+			bytes
+		};
+		let parsed = crate::stringify::from_str(&s).0.unwrap();
+		assert_eq!(encode(parsed), encode(nested));
+	}
+
+	#[test]
+	fn pretty_formatting() {
+		let value = Value::unnamed_composite(vec![
+			Value::named_variant(
+				"Transfer",
+				vec![
+					("from".into(), Value::unnamed_composite(vec![Value::uint(1u8); 4])),
+					("amount".into(), Value::uint(1000u16)),
+					("weird name".into(), Value::named_composite(vec![])),
+				],
+			),
+			Value::unnamed_variant("Remarked", vec![Value::string("hello")]),
+		]);
+		let expected = r#"(
+  Transfer {
+    from: (1, 1, 1, 1),
+    amount: 1000,
+    "weird name": {}
+  },
+  Remarked("hello")
+)"#;
+		assert_eq!(to_string_with_options(&value, &FormatOptions::new().indent(2)), expected);
+		assert_from_to(value);
+	}
+
+	#[test]
+	fn pretty_formatting_wraps_long_sequences() {
+		let value = Value::named_composite(vec![(
+			"data".into(),
+			Value::unnamed_composite((0..40u32).map(|n| Value::uint(n * 1000)).collect()),
+		)]);
+		let expected = "{
+    data: (
+        0, 1000, 2000, 3000, 4000, 5000, 6000, 7000, 8000, 9000, 10000, 11000,
+        12000, 13000, 14000, 15000, 16000, 17000, 18000, 19000, 20000, 21000,
+        22000, 23000, 24000, 25000, 26000, 27000, 28000, 29000, 30000, 31000,
+        32000, 33000, 34000, 35000, 36000, 37000, 38000, 39000
+    )
+}";
+		assert_eq!(format!("{value:#}"), expected);
+		assert_from_to(value);
+	}
+
+	#[test]
+	fn u256_and_i256_formatting() {
+		let mut n = [0; 32];
+		n[0] = 1;
+		n[1] = 1;
+		assert_eq!(Value::primitive(Primitive::U256(n)).to_string(), "257u256");
+		assert_eq!(Value::primitive(Primitive::I256(n)).to_string(), "257i256");
+		assert_eq!(Value::primitive(Primitive::I256([255; 32])).to_string(), "-1i256");
+		assert_eq!(
+			Value::primitive(Primitive::U256([255; 32])).to_string(),
+			"115792089237316195423570985008687907853269984665640564039457584007913129639935u256"
+		);
+	}
 }
@@ -13,6 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::value_index::ValueIndex;
 use bitvec::{order::Lsb0, vec::BitVec};
 use either::Either;
 use std::convert::From;
@@ -175,6 +176,69 @@ impl<T> Composite<T> {
 		}
 	}
 
+	/// Append a value to the end of an unnamed composite type.
+	///
+	/// # Panics
+	///
+	/// Panics if this is a non-empty [`Composite::Named`]. An empty named composite
+	/// is turned into an unnamed one first.
+	pub fn push(&mut self, value: Value<T>) {
+		match self {
+			Composite::Named(values) if values.is_empty() => {
+				*self = Composite::Unnamed(vec![value])
+			}
+			Composite::Named(_) => panic!("cannot push an unnamed value onto a named composite"),
+			Composite::Unnamed(values) => values.push(value),
+		}
+	}
+
+	/// Set the value of a field in a named composite type. If a field with the name given
+	/// already exists, its value is replaced in place and the old value is returned.
+	/// Otherwise, the field is appended to the end.
+	///
+	/// # Panics
+	///
+	/// Panics if this is a non-empty [`Composite::Unnamed`]. An empty unnamed composite
+	/// is turned into a named one first.
+	pub fn insert<S: Into<String>>(&mut self, name: S, value: Value<T>) -> Option<Value<T>> {
+		let name = name.into();
+		match self {
+			Composite::Named(values) => match values.iter_mut().find(|(n, _)| *n == name) {
+				Some((_, old)) => Some(std::mem::replace(old, value)),
+				None => {
+					values.push((name, value));
+					None
+				}
+			},
+			Composite::Unnamed(values) if values.is_empty() => {
+				*self = Composite::Named(vec![(name, value)]);
+				None
+			}
+			Composite::Unnamed(_) => {
+				panic!("cannot insert a named value into an unnamed composite")
+			}
+		}
+	}
+
+	/// Remove the value at the index given (either a field name or a position), shifting
+	/// any values after it down to preserve their order. Returns the removed value, or
+	/// `None` if no such value exists.
+	pub fn remove<I: ValueIndex>(&mut self, index: I) -> Option<Value<T>> {
+		let idx = index.position_in(self)?;
+		match self {
+			Composite::Named(values) => Some(values.remove(idx).1),
+			Composite::Unnamed(values) => Some(values.remove(idx)),
+		}
+	}
+
+	/// Iterate over references to the values stored in this composite type.
+	pub fn values(&self) -> impl Iterator<Item = &Value<T>> {
+		match self {
+			Composite::Named(values) => Either::Left(values.iter().map(|(_k, v)| v)),
+			Composite::Unnamed(values) => Either::Right(values.iter()),
+		}
+	}
+
 	/// Iterate over the values stored in this composite type.
 	pub fn into_values(self) -> impl Iterator<Item = Value<T>> {
 		match self {
@@ -356,5 +420,103 @@ macro_rules! impl_primitive_type {
 
 impl_primitive_type!(Bool(bool), Char(char), String(String), U128(u128), I128(i128),);
 
+// Smaller numbers and string slices are converted into the closest primitive type.
+macro_rules! impl_into_primitive_type {
+    ($($variant:ident($ty:ty),)*) => {$(
+        impl From<$ty> for Primitive {
+            fn from(val: $ty) -> Self {
+                Primitive::$variant(val.into())
+            }
+        }
+
+        impl From<$ty> for Value<()> {
+            fn from(val: $ty) -> Self {
+                Value::primitive(val.into())
+            }
+        }
+    )*}
+}
+
+impl_into_primitive_type!(
+	U128(u8),
+	U128(u16),
+	U128(u32),
+	U128(u64),
+	I128(i8),
+	I128(i16),
+	I128(i32),
+	I128(i64),
+	String(&str),
+);
+
 /// A sequence of bits.
 pub type BitSequence = BitVec<u8, Lsb0>;
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn composite_insert_replaces_in_place_or_appends() {
+		let mut c = Composite::Named(vec![
+			("a".to_string(), Value::uint(1u8)),
+			("b".to_string(), Value::uint(2u8)),
+		]);
+
+		assert_eq!(c.insert("a", Value::bool(true)), Some(Value::uint(1u8)));
+		assert_eq!(c.insert("c", Value::uint(3u8)), None);
+		assert_eq!(
+			c,
+			Composite::Named(vec![
+				("a".to_string(), Value::bool(true)),
+				("b".to_string(), Value::uint(2u8)),
+				("c".to_string(), Value::uint(3u8)),
+			])
+		);
+
+		let mut empty = Composite::Unnamed(vec![]);
+		empty.insert("a", Value::uint(1u8));
+		assert_eq!(empty, Composite::Named(vec![("a".to_string(), Value::uint(1u8))]));
+	}
+
+	#[test]
+	fn composite_push_appends() {
+		let mut c = Composite::Unnamed(vec![Value::uint(1u8)]);
+		c.push(Value::uint(2u8));
+		assert_eq!(c, Composite::Unnamed(vec![Value::uint(1u8), Value::uint(2u8)]));
+
+		let mut empty = Composite::Named(vec![]);
+		empty.push(Value::uint(1u8));
+		assert_eq!(empty, Composite::Unnamed(vec![Value::uint(1u8)]));
+	}
+
+	#[test]
+	#[should_panic]
+	fn composite_push_to_named_panics() {
+		let mut c = Composite::Named(vec![("a".to_string(), Value::uint(1u8))]);
+		c.push(Value::uint(2u8));
+	}
+
+	#[test]
+	fn composite_remove_preserves_order() {
+		let mut c = Composite::Named(vec![
+			("a".to_string(), Value::uint(1u8)),
+			("b".to_string(), Value::uint(2u8)),
+			("c".to_string(), Value::uint(3u8)),
+		]);
+		assert_eq!(c.remove("a"), Some(Value::uint(1u8)));
+		assert_eq!(c.remove("nope"), None);
+		assert_eq!(
+			c,
+			Composite::Named(vec![
+				("b".to_string(), Value::uint(2u8)),
+				("c".to_string(), Value::uint(3u8)),
+			])
+		);
+
+		let mut c = Composite::Unnamed(vec![Value::uint(1u8), Value::uint(2u8), Value::uint(3u8)]);
+		assert_eq!(c.remove(1), Some(Value::uint(2u8)));
+		assert_eq!(c.remove(2), None);
+		assert_eq!(c, Composite::Unnamed(vec![Value::uint(1u8), Value::uint(3u8)]));
+	}
+}
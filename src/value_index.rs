@@ -0,0 +1,424 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd. (admin@parity.io)
+// This file is a part of the scale-value crate.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//         http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the means to look up the values nested inside a [`Value`],
+//! [`Composite`] or [`Variant`] by field name or position, without cloning anything.
+
+use crate::value::{Composite, Value, ValueDef, Variant};
+
+/// Something that can be used to look up a value nested inside a [`Composite`] (and by
+/// extension, a [`Variant`] or [`Value`]). This is implemented for:
+///
+/// - `usize`: look up a value by its position. This works for both named and unnamed
+///   composites.
+/// - `str` and `String`: look up a value by its field name. This only works for named
+///   composites.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait ValueIndex: private::Sealed {
+	/// Return the position of the value that this index points to in the composite given,
+	/// or `None` if no such value exists.
+	#[doc(hidden)]
+	fn position_in<T>(&self, composite: &Composite<T>) -> Option<usize>;
+}
+
+impl ValueIndex for usize {
+	fn position_in<T>(&self, composite: &Composite<T>) -> Option<usize> {
+		(*self < composite.len()).then_some(*self)
+	}
+}
+
+impl ValueIndex for str {
+	fn position_in<T>(&self, composite: &Composite<T>) -> Option<usize> {
+		match composite {
+			Composite::Named(values) => values.iter().position(|(n, _)| n == self),
+			Composite::Unnamed(_) => None,
+		}
+	}
+}
+
+impl ValueIndex for String {
+	fn position_in<T>(&self, composite: &Composite<T>) -> Option<usize> {
+		self.as_str().position_in(composite)
+	}
+}
+
+impl<I: ValueIndex + ?Sized> ValueIndex for &I {
+	fn position_in<T>(&self, composite: &Composite<T>) -> Option<usize> {
+		(**self).position_in(composite)
+	}
+}
+
+mod private {
+	pub trait Sealed {}
+	impl Sealed for usize {}
+	impl Sealed for str {}
+	impl Sealed for String {}
+	impl<I: Sealed + ?Sized> Sealed for &I {}
+}
+
+impl<T> Composite<T> {
+	/// Return a reference to the value at the index given (either a field name or
+	/// a position), or `None` if no such value exists.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use scale_value::{ Composite, Value };
+	///
+	/// let composite = Composite::Named(vec![
+	///     ("foo".into(), Value::bool(true)),
+	///     ("bar".into(), Value::uint(123u8)),
+	/// ]);
+	///
+	/// assert_eq!(composite.get("bar"), Some(&Value::uint(123u8)));
+	/// assert_eq!(composite.get(0), Some(&Value::bool(true)));
+	/// assert_eq!(composite.get("wibble"), None);
+	/// ```
+	pub fn get<I: ValueIndex>(&self, index: I) -> Option<&Value<T>> {
+		let idx = index.position_in(self)?;
+		match self {
+			Composite::Named(values) => values.get(idx).map(|(_, v)| v),
+			Composite::Unnamed(values) => values.get(idx),
+		}
+	}
+
+	/// Return a mutable reference to the value at the index given (either a field name
+	/// or a position), or `None` if no such value exists.
+	pub fn get_mut<I: ValueIndex>(&mut self, index: I) -> Option<&mut Value<T>> {
+		let idx = index.position_in(self)?;
+		match self {
+			Composite::Named(values) => values.get_mut(idx).map(|(_, v)| v),
+			Composite::Unnamed(values) => values.get_mut(idx),
+		}
+	}
+}
+
+impl<T> Variant<T> {
+	/// Return a reference to the value at the index given (either a field name or
+	/// a position) in this variant's fields, or `None` if no such value exists.
+	pub fn get<I: ValueIndex>(&self, index: I) -> Option<&Value<T>> {
+		self.values.get(index)
+	}
+
+	/// Return a mutable reference to the value at the index given (either a field name
+	/// or a position) in this variant's fields, or `None` if no such value exists.
+	pub fn get_mut<I: ValueIndex>(&mut self, index: I) -> Option<&mut Value<T>> {
+		self.values.get_mut(index)
+	}
+}
+
+impl<T> Value<T> {
+	/// If this value is a composite or variant, return a reference to the value at
+	/// the index given (either a field name or a position) inside it. Return `None`
+	/// if no such value exists.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use scale_value::Value;
+	///
+	/// let value = Value::named_variant("Transfer", vec![
+	///     ("dest".into(), Value::unnamed_variant("Id", vec![Value::uint(1u8)])),
+	///     ("value".into(), Value::uint(1000u16)),
+	/// ]);
+	///
+	/// assert_eq!(value.get("value"), Some(&Value::uint(1000u16)));
+	/// assert_eq!(value.get("dest").and_then(|v| v.get(0)), Some(&Value::uint(1u8)));
+	/// ```
+	pub fn get<I: ValueIndex>(&self, index: I) -> Option<&Value<T>> {
+		self.fields()?.get(index)
+	}
+
+	/// If this value is a composite or variant, return a mutable reference to the value
+	/// at the index given (either a field name or a position) inside it. Return `None`
+	/// if no such value exists.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use scale_value::Value;
+	///
+	/// let mut value = Value::named_variant("Transfer", vec![
+	///     ("dest".into(), Value::unnamed_variant("Id", vec![Value::uint(1u8)])),
+	///     ("value".into(), Value::uint(1000u16)),
+	/// ]);
+	///
+	/// *value.get_mut("value").unwrap() = Value::uint(2000u16);
+	/// assert_eq!(value.get("value"), Some(&Value::uint(2000u16)));
+	/// ```
+	pub fn get_mut<I: ValueIndex>(&mut self, index: I) -> Option<&mut Value<T>> {
+		self.fields_mut()?.get_mut(index)
+	}
+
+	/// Look up a value nested inside this one using a path like `"/call/args/0"`. The path
+	/// syntax mirrors that of a JSON pointer (RFC 6901): each `/` separated segment is a
+	/// field name or a position, and `~1` and `~0` can be used to escape `/` and `~`
+	/// respectively in field names. Variants are transparent here; segments index
+	/// straight into their fields. An empty path points to this value.
+	///
+	/// If a named composite has no field matching a segment, we fall back to treating
+	/// the segment as a position, if possible.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use scale_value::Value;
+	///
+	/// let value = Value::named_composite(vec![
+	///     ("call".into(), Value::named_variant("Transfer", vec![
+	///         ("args".into(), Value::unnamed_composite(vec![Value::bool(true)])),
+	///     ])),
+	/// ]);
+	///
+	/// assert_eq!(value.pointer("/call/args/0"), Some(&Value::bool(true)));
+	/// assert_eq!(value.pointer(""), Some(&value));
+	/// assert_eq!(value.pointer("/call/nope"), None);
+	/// ```
+	pub fn pointer(&self, pointer: &str) -> Option<&Value<T>> {
+		let mut target = self;
+		for segment in pointer_segments(pointer)? {
+			let fields = target.fields()?;
+			target = fields.get(segment_position(fields, &segment)?)?;
+		}
+		Some(target)
+	}
+
+	/// Like [`Value::pointer`], but returns a mutable reference to the value found.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use scale_value::Value;
+	///
+	/// let mut value = Value::named_composite(vec![
+	///     ("call".into(), Value::named_variant("Transfer", vec![
+	///         ("args".into(), Value::unnamed_composite(vec![Value::bool(true)])),
+	///     ])),
+	/// ]);
+	///
+	/// *value.pointer_mut("/call/args/0").unwrap() = Value::bool(false);
+	/// assert_eq!(value.pointer("/call/args/0"), Some(&Value::bool(false)));
+	/// ```
+	pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value<T>> {
+		let mut target = self;
+		for segment in pointer_segments(pointer)? {
+			let fields = target.fields_mut()?;
+			let idx = segment_position(fields, &segment)?;
+			target = fields.get_mut(idx)?;
+		}
+		Some(target)
+	}
+
+	/// The fields of a composite or variant value, if this is one.
+	fn fields(&self) -> Option<&Composite<T>> {
+		match &self.value {
+			ValueDef::Composite(composite) => Some(composite),
+			ValueDef::Variant(variant) => Some(&variant.values),
+			ValueDef::BitSequence(_) | ValueDef::Primitive(_) => None,
+		}
+	}
+
+	/// The fields of a composite or variant value, if this is one.
+	fn fields_mut(&mut self) -> Option<&mut Composite<T>> {
+		match &mut self.value {
+			ValueDef::Composite(composite) => Some(composite),
+			ValueDef::Variant(variant) => Some(&mut variant.values),
+			ValueDef::BitSequence(_) | ValueDef::Primitive(_) => None,
+		}
+	}
+}
+
+/// Split a JSON-pointer style string into its unescaped segments, returning
+/// `None` if it's not a valid pointer.
+fn pointer_segments(pointer: &str) -> Option<impl Iterator<Item = String> + '_> {
+	let segments = match pointer {
+		"" => None,
+		p if p.starts_with('/') => Some(p[1..].split('/')),
+		_ => return None,
+	};
+	Some(segments.into_iter().flatten().map(|s| s.replace("~1", "/").replace("~0", "~")))
+}
+
+/// Find the position of the value that a single pointer segment refers to. The segment
+/// is either a field name or (if there is no field with that name) a position.
+fn segment_position<T>(composite: &Composite<T>, segment: &str) -> Option<usize> {
+	segment.position_in(composite).or_else(|| segment.parse::<usize>().ok()?.position_in(composite))
+}
+
+impl<T, I: ValueIndex> std::ops::Index<I> for Composite<T> {
+	type Output = Value<T>;
+	/// Index into a composite by field name or position.
+	///
+	/// # Panics
+	///
+	/// Panics if no value can be found at the index given. Use [`Composite::get`]
+	/// to avoid this.
+	fn index(&self, index: I) -> &Self::Output {
+		self.get(index).expect("no value found at the index given")
+	}
+}
+
+impl<T, I: ValueIndex> std::ops::IndexMut<I> for Composite<T> {
+	/// Mutably index into a composite by field name or position.
+	///
+	/// # Panics
+	///
+	/// Panics if no value can be found at the index given. Use [`Composite::get_mut`]
+	/// to avoid this.
+	fn index_mut(&mut self, index: I) -> &mut Self::Output {
+		self.get_mut(index).expect("no value found at the index given")
+	}
+}
+
+impl<T, I: ValueIndex> std::ops::Index<I> for Variant<T> {
+	type Output = Value<T>;
+	/// Index into the fields of a variant by field name or position.
+	///
+	/// # Panics
+	///
+	/// Panics if no value can be found at the index given. Use [`Variant::get`]
+	/// to avoid this.
+	fn index(&self, index: I) -> &Self::Output {
+		self.get(index).expect("no value found at the index given")
+	}
+}
+
+impl<T, I: ValueIndex> std::ops::IndexMut<I> for Variant<T> {
+	/// Mutably index into the fields of a variant by field name or position.
+	///
+	/// # Panics
+	///
+	/// Panics if no value can be found at the index given. Use [`Variant::get_mut`]
+	/// to avoid this.
+	fn index_mut(&mut self, index: I) -> &mut Self::Output {
+		self.get_mut(index).expect("no value found at the index given")
+	}
+}
+
+impl<T, I: ValueIndex> std::ops::Index<I> for Value<T> {
+	type Output = Value<T>;
+	/// Index into a composite or variant value by field name or position.
+	///
+	/// # Panics
+	///
+	/// Panics if this value is not a composite or variant, or if no value can be
+	/// found at the index given. Use [`Value::get`] to avoid this.
+	fn index(&self, index: I) -> &Self::Output {
+		self.get(index).expect("no value found at the index given")
+	}
+}
+
+impl<T, I: ValueIndex> std::ops::IndexMut<I> for Value<T> {
+	/// Mutably index into a composite or variant value by field name or position.
+	///
+	/// # Panics
+	///
+	/// Panics if this value is not a composite or variant, or if no value can be
+	/// found at the index given. Use [`Value::get_mut`] to avoid this.
+	fn index_mut(&mut self, index: I) -> &mut Self::Output {
+		self.get_mut(index).expect("no value found at the index given")
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn example_value() -> Value<()> {
+		Value::named_composite(vec![
+			(
+				"call".into(),
+				Value::named_variant(
+					"Transfer",
+					vec![
+						("dest".into(), Value::unnamed_variant("Id", vec![Value::uint(1u8)])),
+						("value".into(), Value::uint(1000u16)),
+					],
+				),
+			),
+			("a/weird~name".into(), Value::bool(true)),
+			("tuple".into(), Value::unnamed_composite(vec![Value::bool(false), Value::char('a')])),
+		])
+	}
+
+	#[test]
+	fn get_by_name_and_position() {
+		let value = example_value();
+
+		assert_eq!(value.get("tuple").and_then(|v| v.get(1)), Some(&Value::char('a')));
+		assert_eq!(value.get(2), value.get("tuple"));
+		assert_eq!(value.get("a/weird~name".to_string()), Some(&Value::bool(true)));
+		assert_eq!(value.get("tuple").and_then(|v| v.get("0")), None);
+		assert_eq!(value.get(3), None);
+		assert_eq!(Value::bool(true).get(0), None);
+	}
+
+	#[test]
+	fn index_by_name_and_position() {
+		let value = example_value();
+
+		assert_eq!(value["call"]["dest"][0], Value::uint(1u8));
+		assert_eq!(value["call"][1], Value::uint(1000u16));
+		assert_eq!(value[2][0], Value::bool(false));
+	}
+
+	#[test]
+	#[should_panic]
+	fn index_panics_if_not_found() {
+		let _ = &example_value()["nope"];
+	}
+
+	#[test]
+	fn get_by_pointer() {
+		let value = example_value();
+
+		assert_eq!(value.pointer(""), Some(&value));
+		assert_eq!(value.pointer("/call/dest/0"), Some(&Value::uint(1u8)));
+		assert_eq!(value.pointer("/call/1"), Some(&Value::uint(1000u16)));
+		assert_eq!(value.pointer("/a~1weird~0name"), Some(&Value::bool(true)));
+		assert_eq!(value.pointer("/tuple/1"), Some(&Value::char('a')));
+		assert_eq!(value.pointer("/tuple/2"), None);
+		assert_eq!(value.pointer("/tuple/1/0"), None);
+		assert_eq!(value.pointer("call"), None);
+	}
+
+	#[test]
+	fn get_mut_by_name_and_position() {
+		let mut value = example_value();
+
+		*value.get_mut("tuple").and_then(|v| v.get_mut(1)).unwrap() = Value::char('b');
+		*value.get_mut(1).unwrap() = Value::bool(false);
+		value["call"]["dest"][0] = Value::uint(2u8);
+
+		assert_eq!(value.pointer("/tuple/1"), Some(&Value::char('b')));
+		assert_eq!(value.pointer("/a~1weird~0name"), Some(&Value::bool(false)));
+		assert_eq!(value.pointer("/call/dest/0"), Some(&Value::uint(2u8)));
+		assert!(value.get_mut("nope").is_none());
+	}
+
+	#[test]
+	fn get_mut_by_pointer() {
+		let mut value = example_value();
+
+		*value.pointer_mut("/call/value").unwrap() = Value::uint(1u8);
+		*value.pointer_mut("/tuple/0").unwrap() = Value::bool(true);
+
+		assert_eq!(value["call"]["value"], Value::uint(1u8));
+		assert_eq!(value["tuple"][0], Value::bool(true));
+		assert!(value.pointer_mut("/tuple/2").is_none());
+		assert!(value.pointer_mut("tuple").is_none());
+	}
+}